@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use glam::{vec3, Vec3};
+use serde::{Deserialize, Serialize};
+
+// Where baked per-vertex ambient occlusion is stored in OBJ vertex colors (`v x y z r g b [a]`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AoSource {
+    Alpha,
+    Red,
+    Green,
+    Blue,
+}
+
+impl AoSource {
+    fn channel(self) -> usize {
+        match self {
+            AoSource::Red => 0,
+            AoSource::Green => 1,
+            AoSource::Blue => 2,
+            AoSource::Alpha => 3,
+        }
+    }
+}
+
+// AO in [0; 1] range by object name, indexed the same way as Object::vertices
+pub type VertexAo = HashMap<String, Vec<f32>>;
+
+// Linear RGB colors in [0; 1] range by object name, indexed the same way as Object::vertices
+pub type VertexColors = HashMap<String, Vec<Vec3>>;
+
+// Read per-vertex AO from vertex colors of OBJ file. Vertices without a color (or without alpha
+// for AoSource::Alpha) are not occluded at all (AO of 1).
+// NOTE: objects with the same name share the entry, the first one wins
+pub fn read_vertex_ao(path: &str, source: AoSource) -> Result<VertexAo> {
+    let model = std::fs::read_to_string(path)?;
+    parse_vertex_data(&model, |color| {
+        let value = color.get(source.channel()).copied().unwrap_or(1.0);
+        value.clamp(0.0, 1.0)
+    })
+}
+
+// Read vertex colors (`v x y z r g b`) of OBJ file, vertices without one are white.
+// NOTE: objects with the same name share the entry, the first one wins
+pub fn read_vertex_colors(path: &str) -> Result<VertexColors> {
+    let model = std::fs::read_to_string(path)?;
+    parse_vertex_data(&model, |color| match color {
+        [r, g, b, ..] => vec3(*r, *g, *b).max(Vec3::zero()).min(Vec3::splat(1.0)),
+        _ => Vec3::splat(1.0),
+    })
+}
+
+// f turns components of the color of every vertex (empty if it has none) into its value.
+// Vertices before the first `o` belong to an unnamed object, like they do for wavefront_obj.
+fn parse_vertex_data<T, F>(input: &str, f: F) -> Result<HashMap<String, Vec<T>>>
+where
+    F: Fn(&[f32]) -> T,
+{
+    let mut objects: Vec<(String, Vec<T>)> = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("o") => objects.push((words.collect::<Vec<_>>().join(" "), Vec::new())),
+            Some("v") => {
+                let color = words
+                    .skip(3)
+                    .map(|word| word.parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| anyhow!("Failed to parse line #{}: Expected a number", i + 1))?;
+                if objects.is_empty() {
+                    objects.push((String::new(), Vec::new()));
+                }
+                objects.last_mut().unwrap().1.push(f(&color));
+            }
+            _ => {}
+        }
+    }
+
+    let mut data = HashMap::new();
+    for (name, values) in objects {
+        data.entry(name).or_insert(values);
+    }
+    Ok(data)
+}
+
+// Drop vertex colors from `v` lines, wavefront_obj can't parse them
+pub(crate) fn strip_vertex_colors(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    for line in input.lines() {
+        let mut words = line.split_whitespace();
+        if words.next() == Some("v") && line.split_whitespace().count() > 4 {
+            output.push('v');
+            for word in words.take(3) {
+                output.push(' ');
+                output.push_str(word);
+            }
+        } else {
+            output.push_str(line);
+        }
+        output.push('\n');
+    }
+    output
+}
@@ -0,0 +1,366 @@
+use glam::{vec3, Mat4, Vec3};
+use wavefront_obj::obj::ObjSet;
+
+use crate::model::to_vec3;
+use crate::{Camera, Projection, Scene};
+
+// What frustum culling did since the renderer was created (or last cleared)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CullingStats {
+    // instances drawn and skipped because they are outside of the view
+    pub visible: usize,
+    pub culled: usize,
+    // BVH nodes (or bounding spheres of instances) tested against the frustum
+    pub nodes_tested: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Aabb {
+    min: Vec3,
+    max: Vec3,
+}
+
+impl Aabb {
+    fn empty() -> Self {
+        Aabb {
+            min: Vec3::splat(f32::INFINITY),
+            max: Vec3::splat(f32::NEG_INFINITY),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    fn include(&mut self, p: Vec3) {
+        self.min = self.min.min(p);
+        self.max = self.max.max(p);
+    }
+
+    fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    // bounds of the box transformed by transform
+    fn transform(&self, transform: &Mat4) -> Aabb {
+        let mut bounds = Aabb::empty();
+        for &x in &[self.min.x(), self.max.x()] {
+            for &y in &[self.min.y(), self.max.y()] {
+                for &z in &[self.min.z(), self.max.z()] {
+                    bounds.include(transform.transform_point3(vec3(x, y, z)));
+                }
+            }
+        }
+        bounds
+    }
+}
+
+fn model_bounds(model: &ObjSet) -> Aabb {
+    let mut bounds = Aabb::empty();
+    for object in &model.objects {
+        for &v in &object.vertices {
+            bounds.include(to_vec3(v));
+        }
+    }
+    bounds
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Sphere {
+    center: Vec3,
+    radius: f32,
+}
+
+impl Sphere {
+    // bounds of the sphere transformed by transform (stretched ones are bounded by their longest
+    // axis)
+    fn transform(&self, transform: &Mat4) -> Sphere {
+        let scale = [transform.x_axis(), transform.y_axis(), transform.z_axis()]
+            .iter()
+            .map(|axis| axis.truncate().length())
+            .fold(0.0, f32::max);
+        Sphere {
+            center: transform.transform_point3(self.center),
+            radius: self.radius * scale,
+        }
+    }
+}
+
+// around the center of the model's box, None for empty models
+fn model_sphere(model: &ObjSet) -> Option<Sphere> {
+    let bounds = model_bounds(model);
+    if bounds.is_empty() {
+        return None;
+    }
+
+    let center = bounds.center();
+    let radius = model
+        .objects
+        .iter()
+        .flat_map(|object| &object.vertices)
+        .map(|&v| (to_vec3(v) - center).length())
+        .fold(0.0, f32::max);
+    Some(Sphere { center, radius })
+}
+
+// Indices of scene instances whose bounding spheres (grown by margin, in model units) intersect
+// the frustum, in ascending order. Every instance is tested, there's nothing to build.
+pub(crate) fn visible_spheres(
+    scene: &Scene,
+    margin: f32,
+    frustum: &Frustum,
+    stats: &mut CullingStats,
+) -> Vec<usize> {
+    let spheres: Vec<Option<Sphere>> = scene
+        .meshes
+        .iter()
+        .map(|mesh| {
+            model_sphere(&mesh.model).map(|sphere| Sphere {
+                radius: sphere.radius + margin,
+                ..sphere
+            })
+        })
+        .collect();
+
+    let mut visible = Vec::new();
+    for (i, instance) in scene.instances.iter().enumerate() {
+        // instances of empty meshes are never visible
+        if let Some(sphere) = spheres[instance.mesh] {
+            stats.nodes_tested += 1;
+            if frustum.intersects_sphere(&sphere.transform(&instance.transform)) {
+                visible.push(i);
+            }
+        }
+    }
+    visible
+}
+
+// World space half-spaces (normal, offset) the camera sees, points p with normal.dot(p) + offset
+// >= 0 for all of them are visible
+pub(crate) struct Frustum {
+    planes: Vec<(Vec3, f32)>,
+}
+
+impl Frustum {
+    pub(crate) fn new(camera: &Camera) -> Self {
+        // half-spaces a.dot(v) + b >= 0 in camera space, see Camera::project
+        let camera_space = match camera.projection {
+            Projection::Orthographic { scale } => vec![
+                (vec3(-scale, 0.0, 0.0), 1.0),
+                (vec3(scale, 0.0, 0.0), 1.0),
+                (vec3(0.0, -scale, 0.0), 1.0),
+                (vec3(0.0, scale, 0.0), 1.0),
+            ],
+            Projection::Perspective { fov } => {
+                let distance = (camera.lookfrom - camera.lookat).length();
+                let f = 1.0 / (fov / 2.0).tan();
+                vec![
+                    (vec3(-f, 0.0, -1.0), distance),
+                    (vec3(f, 0.0, -1.0), distance),
+                    (vec3(0.0, -f, -1.0), distance),
+                    (vec3(0.0, f, -1.0), distance),
+                    (vec3(0.0, 0.0, -1.0), distance * (1.0 - 1e-3)),
+                ]
+            }
+        };
+
+        // camera space is v = translation * (p - lookat)
+        let rotation = camera.translation.transpose();
+        let planes = camera_space
+            .into_iter()
+            .map(|(a, b)| {
+                let normal = rotation * a;
+                (normal, b - normal.dot(camera.lookat))
+            })
+            .collect();
+        Frustum { planes }
+    }
+
+    // false if the sphere is entirely outside of one of the planes
+    fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes.iter().all(|&(normal, offset)| {
+            // normals aren't unit length
+            normal.dot(sphere.center) + offset >= -sphere.radius * normal.length()
+        })
+    }
+
+    // false if the box is entirely outside of one of the planes
+    fn intersects(&self, bounds: &Aabb) -> bool {
+        self.planes.iter().all(|&(normal, offset)| {
+            // corner furthest along the normal
+            let corner = vec3(
+                if normal.x() > 0.0 {
+                    bounds.max.x()
+                } else {
+                    bounds.min.x()
+                },
+                if normal.y() > 0.0 {
+                    bounds.max.y()
+                } else {
+                    bounds.min.y()
+                },
+                if normal.z() > 0.0 {
+                    bounds.max.z()
+                } else {
+                    bounds.min.z()
+                },
+            );
+            normal.dot(corner) + offset >= 0.0
+        })
+    }
+}
+
+enum Node {
+    // range of Bvh::instances
+    Leaf {
+        bounds: Aabb,
+        start: usize,
+        end: usize,
+    },
+    // indices of children in Bvh::nodes
+    Inner {
+        bounds: Aabb,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl Node {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            Node::Leaf { bounds, .. } | Node::Inner { bounds, .. } => bounds,
+        }
+    }
+}
+
+// Bounding volume hierarchy over world space bounds of scene instances, split at the median
+// along the longest axis until at most leaf_size instances are left
+pub(crate) struct Bvh {
+    nodes: Vec<Node>,
+    root: Option<usize>,
+    // (instance index, its bounds), in the order of leaves
+    instances: Vec<(usize, Aabb)>,
+}
+
+impl Bvh {
+    // margin grows bounds of meshes in every direction (in model units)
+    pub(crate) fn build(scene: &Scene, leaf_size: usize, margin: f32) -> Self {
+        let mesh_bounds: Vec<Aabb> = scene
+            .meshes
+            .iter()
+            .map(|mesh| {
+                let bounds = model_bounds(&mesh.model);
+                Aabb {
+                    min: bounds.min - Vec3::splat(margin),
+                    max: bounds.max + Vec3::splat(margin),
+                }
+            })
+            .collect();
+
+        // instances of empty meshes are never visible
+        let mut instances: Vec<(usize, Aabb)> = scene
+            .instances
+            .iter()
+            .enumerate()
+            .filter(|(_, instance)| !mesh_bounds[instance.mesh].is_empty())
+            .map(|(i, instance)| (i, mesh_bounds[instance.mesh].transform(&instance.transform)))
+            .collect();
+
+        let mut nodes = Vec::new();
+        let root = if instances.is_empty() {
+            None
+        } else {
+            Some(Self::split(&mut nodes, &mut instances, 0, leaf_size.max(1)))
+        };
+        Bvh {
+            nodes,
+            root,
+            instances,
+        }
+    }
+
+    // push node for instances (starting at start in the leaf order) and its children to nodes,
+    // returns its index
+    fn split(
+        nodes: &mut Vec<Node>,
+        instances: &mut [(usize, Aabb)],
+        start: usize,
+        leaf_size: usize,
+    ) -> usize {
+        let bounds = instances
+            .iter()
+            .fold(Aabb::empty(), |acc, (_, bounds)| acc.union(bounds));
+        if instances.len() <= leaf_size {
+            nodes.push(Node::Leaf {
+                bounds,
+                start,
+                end: start + instances.len(),
+            });
+            return nodes.len() - 1;
+        }
+
+        let extent = bounds.max - bounds.min;
+        let axis = if extent.x() >= extent.y() && extent.x() >= extent.z() {
+            0
+        } else if extent.y() >= extent.z() {
+            1
+        } else {
+            2
+        };
+        let key = |bounds: &Aabb| match axis {
+            0 => bounds.center().x(),
+            1 => bounds.center().y(),
+            _ => bounds.center().z(),
+        };
+        instances.sort_by(|(_, a), (_, b)| key(a).total_cmp(&key(b)));
+
+        let middle = instances.len() / 2;
+        let (left, right) = instances.split_at_mut(middle);
+        let left = Self::split(nodes, left, start, leaf_size);
+        let right = Self::split(nodes, right, start + middle, leaf_size);
+        nodes.push(Node::Inner {
+            bounds,
+            left,
+            right,
+        });
+        nodes.len() - 1
+    }
+
+    // indices of instances intersecting the frustum, in ascending order
+    pub(crate) fn visible(&self, frustum: &Frustum, stats: &mut CullingStats) -> Vec<usize> {
+        let mut visible = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(i) = stack.pop() {
+            let node = &self.nodes[i];
+            stats.nodes_tested += 1;
+            if !frustum.intersects(node.bounds()) {
+                continue;
+            }
+
+            match *node {
+                Node::Leaf { start, end, .. } => {
+                    for (instance, bounds) in &self.instances[start..end] {
+                        if frustum.intersects(bounds) {
+                            visible.push(*instance);
+                        }
+                    }
+                }
+                Node::Inner { left, right, .. } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+
+        visible.sort_unstable();
+        visible
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use glam::{vec3, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AmbientSh, AoSource, Bloom, Camera, Color, DepthOfField, FrontFace, GroupFilter, GroupingMode,
+    Handedness, Light, LoadOptions, Lod, MaterialOverride, NormalMapSpace, NormalSpace, PassBlend,
+    Projection, Renderer, ResolveFilter, ShadingMode, ShadowMode, Texture, Timeline,
+    UvInterpretation, Vignette,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraConfig {
+    pub lookfrom: Vec3,
+    pub lookat: Vec3,
+    pub up: Vec3,
+    pub projection: Projection,
+    pub handedness: Handedness,
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        CameraConfig {
+            lookfrom: vec3(0.5, 0.0, -0.5),
+            lookat: vec3(0.0, 0.0, 0.0),
+            up: vec3(0.0, 1.0, 0.0),
+            projection: Projection::Orthographic { scale: 1.0 },
+            handedness: Handedness::RightHanded,
+        }
+    }
+}
+
+impl CameraConfig {
+    pub fn camera(&self) -> Camera {
+        let mut camera =
+            Camera::with_projection(self.lookfrom, self.lookat, self.up, self.projection);
+        camera.set_handedness(self.handedness);
+        camera
+    }
+}
+
+// Everything needed to reproduce a render, missing fields take their default values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenderConfig {
+    pub model: String,
+    // an empty string in files for no texture, there's no null in TOML to override the default
+    #[serde(with = "empty_as_none")]
+    pub texture: Option<String>,
+    // grow UV islands of the texture by this many texels when it's read, see TextureExt::dilate
+    pub texture_dilation: u32,
+    // model of the same topology to blend towards by morph_weight (1 is the target itself)
+    pub morph_target: Option<String>,
+    pub morph_weight: f32,
+    // MTL file with materials used by the model, the one its `mtllib` references by default
+    pub materials: Option<String>,
+    // replaces properties of the materials from the file (and of the default one)
+    pub material_override: MaterialOverride,
+    // color (in [0; 1] range) of material textures that can't be read instead of the default
+    // checkerboard, see Renderer::set_missing_texture_fallback
+    pub missing_texture_color: Option<Vec3>,
+    // normal map (sampled with the same UV coordinates as texture) and its space
+    pub normal_map: Option<String>,
+    pub normal_map_space: NormalMapSpace,
+    // whether UVs of the model are in [0; 1] or texture pixels, see
+    // Renderer::set_uv_interpretation
+    pub uv_interpretation: UvInterpretation,
+    // height map to displace geometry with, its scale in model units and subdivision level, see
+    // Renderer::set_displacement_map
+    pub displacement_map: Option<String>,
+    pub displacement_scale: f32,
+    pub subdivision_level: u32,
+    // multiply lighting by per-vertex AO baked into vertex colors of the model
+    pub vertex_ao: Option<AoSource>,
+    pub output: String,
+    // image (e.g. an earlier output) to render over instead of the empty background, and how
+    // the render is blended with it
+    pub paint_over: Option<String>,
+    pub blend: PassBlend,
+    // .cube 3D LUT to color grade the output with
+    pub color_lut: Option<String>,
+    // post effects applied to the output, auto exposure to this average luminance first
+    pub auto_exposure: Option<f32>,
+    pub dof: Option<DepthOfField>,
+    pub bloom: Option<Bloom>,
+    pub vignette: Option<Vignette>,
+    // per-channel gains of the output, see Renderer::set_tint
+    pub tint: Option<Vec3>,
+    // skip tiny triangles and distant instances, see Lod
+    pub lod: Option<Lod>,
+    // BVH leaf size for frustum culling (no culling if None), see Renderer::set_frustum_culling
+    pub frustum_culling: Option<usize>,
+    // test bounding spheres instead (frustum_culling is ignored), see
+    // Renderer::set_sphere_culling
+    pub sphere_culling: bool,
+    // size of tiles rasterized one by one (picked from the number of threads if None) and tiles
+    // per thread it's picked for, see Renderer::set_tile_size
+    pub tile_size: Option<usize>,
+    pub bins_per_thread: usize,
+    pub width: usize,
+    pub height: usize,
+    // render at every one of these sizes instead, to output with the size appended (e.g.
+    // target_256x256.png), see Renderer::resize
+    pub sizes: Vec<(usize, usize)>,
+    // render at supersampling times the size, downsampled with resolve_filter when saved, see
+    // Renderer::resolve
+    pub supersampling: u32,
+    pub resolve_filter: ResolveFilter,
+    // dither transparent materials instead of blending them, see Renderer::set_alpha_to_coverage
+    pub alpha_to_coverage: bool,
+    pub shading: ShadingMode,
+    // space of normals drawn with ShadingMode::Normals
+    pub normal_space: NormalSpace,
+    pub front_face: FrontFace,
+    // draw back faces too, see Renderer::set_double_sided
+    pub double_sided: bool,
+    // set up triangles of models far from the origin precisely, see Renderer::set_high_precision
+    pub high_precision: bool,
+    // size (in pixels) of points, whether it's attenuated with depth and their texture, see
+    // Renderer::set_point_size
+    pub point_size: f32,
+    pub point_attenuation: bool,
+    pub point_sprite: Option<String>,
+    // resolve transparency with this many depth peeling layers instead of sorting, see
+    // Renderer::render_depth_peeled
+    pub depth_peeling_layers: Option<usize>,
+    // whether object ids (and outlines) follow `o` or `g` directives
+    pub grouping: GroupingMode,
+    // `g` groups to draw and colors of some of them, see Renderer::set_group_filter and
+    // Renderer::set_group_color
+    pub groups: GroupFilter,
+    pub group_colors: BTreeMap<String, Vec3>,
+    // in camera space, see Renderer::set_light_direction
+    pub light_direction: Vec3,
+    // more directional lights, see Renderer::add_light
+    pub lights: Vec<Light>,
+    // world space planes cutting off what's on their negative side, see Renderer::add_clip_plane
+    pub clip_planes: Vec<Vec4>,
+    // ambient light (instead of the constant minimum), see Renderer::set_ambient_sh
+    pub ambient_sh: Option<AmbientSh>,
+    // cubemap faces (+X, -X, +Y, -Y, +Z, -Z) of ambient light replacing ambient_sh, see
+    // Renderer::set_ambient_environment, and the scale of ambient light
+    pub ambient_environment: Option<Vec<String>>,
+    pub ambient_strength: f32,
+    // shadow map resolution (no shadows if None), PCF kernel size and filtering, see
+    // Renderer::set_shadows
+    pub shadow_map_size: Option<usize>,
+    pub pcf_kernel_size: usize,
+    pub shadow_mode: ShadowMode,
+    // ray march steps of screen-space reflections (none if None), see Renderer::set_ssr
+    pub ssr_steps: Option<usize>,
+    // ray length and march steps of contact shadows (none if None), see
+    // Renderer::set_contact_shadows
+    pub contact_shadows: Option<(f32, usize)>,
+    pub camera: CameraConfig,
+    // camera animation to render frame by frame instead of the camera, to output with the frame
+    // number appended (e.g. target_0007.png)
+    pub timeline: Timeline,
+    pub load: LoadOptions,
+}
+
+// serde of Option<String> as a string that's empty for None
+mod empty_as_none {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<String>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(value.as_deref().unwrap_or(""))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<String>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(Some(value).filter(|value| !value.is_empty()))
+    }
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        RenderConfig {
+            model: "obj/african_head.obj".to_owned(),
+            texture: Some("obj/african_head_diffuse.png".to_owned()),
+            texture_dilation: 0,
+            morph_target: None,
+            morph_weight: 1.0,
+            materials: None,
+            material_override: MaterialOverride::default(),
+            missing_texture_color: None,
+            normal_map: None,
+            normal_map_space: NormalMapSpace::Tangent,
+            uv_interpretation: UvInterpretation::Auto,
+            displacement_map: None,
+            displacement_scale: 0.05,
+            subdivision_level: 2,
+            vertex_ao: None,
+            output: "target.png".to_owned(),
+            paint_over: None,
+            blend: PassBlend::Depth,
+            color_lut: None,
+            bloom: None,
+            dof: None,
+            vignette: None,
+            auto_exposure: None,
+            tint: None,
+            lod: None,
+            frustum_culling: None,
+            sphere_culling: false,
+            tile_size: None,
+            bins_per_thread: 4,
+            width: 1024,
+            height: 1024,
+            sizes: Vec::new(),
+            supersampling: 1,
+            resolve_filter: ResolveFilter::Box,
+            alpha_to_coverage: false,
+            shading: ShadingMode::Lit,
+            normal_space: NormalSpace::View,
+            front_face: FrontFace::Ccw,
+            double_sided: false,
+            high_precision: false,
+            point_size: 1.0,
+            point_attenuation: false,
+            point_sprite: None,
+            depth_peeling_layers: None,
+            grouping: GroupingMode::Objects,
+            groups: GroupFilter::All,
+            group_colors: BTreeMap::new(),
+            light_direction: vec3(0.0, 0.0, 1.0),
+            lights: Vec::new(),
+            clip_planes: Vec::new(),
+            ambient_sh: None,
+            ambient_environment: None,
+            ambient_strength: 1.0,
+            shadow_map_size: None,
+            pcf_kernel_size: 1,
+            shadow_mode: ShadowMode::Pcf,
+            ssr_steps: None,
+            contact_shadows: None,
+            camera: CameraConfig::default(),
+            timeline: Timeline::default(),
+            load: LoadOptions::default(),
+        }
+    }
+}
+
+impl RenderConfig {
+    // read config from TOML file
+    pub fn load(path: &str) -> Result<Self> {
+        let config = std::fs::read_to_string(path)?;
+        toml::from_str(&config).with_context(|| format!("Invalid config file {:?}", path))
+    }
+
+    // write config to TOML file
+    pub fn save(&self, path: &str) -> Result<()> {
+        std::fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    // empty renderer set up according to this config
+    pub fn renderer(&self) -> Renderer {
+        let factor = self.supersampling.max(1) as usize;
+        let size = (self.width * factor, self.height * factor);
+        let mut renderer = Renderer::new(self.camera.camera(), size);
+        renderer.set_shading_mode(self.shading);
+        renderer.set_normal_space(self.normal_space);
+        renderer.set_front_face(self.front_face);
+        renderer.set_double_sided(self.double_sided);
+        renderer.set_alpha_to_coverage(self.alpha_to_coverage);
+        renderer.set_high_precision(self.high_precision);
+        renderer.set_point_size(self.point_size);
+        renderer.set_point_attenuation(self.point_attenuation);
+        renderer.set_grouping_mode(self.grouping);
+        renderer.set_group_filter(self.groups.clone());
+        renderer.set_max_texture_pixels(self.load.max_texture_pixels);
+        renderer.set_uv_interpretation(self.uv_interpretation);
+        for (group, &color) in &self.group_colors {
+            renderer.set_group_color(group, color);
+        }
+        renderer.set_pass_blend(self.blend);
+        renderer.set_light_direction(self.light_direction);
+        for &light in &self.lights {
+            renderer.add_light(light);
+        }
+        for &plane in &self.clip_planes {
+            renderer.add_clip_plane(plane);
+        }
+        renderer.set_material_override(self.material_override.clone());
+        if let Some(color) = self.missing_texture_color {
+            let rgb = (color * 255.0).round();
+            let pixel = Color::from([rgb.x() as u8, rgb.y() as u8, rgb.z() as u8]);
+            renderer.set_missing_texture_fallback(Texture::from_pixel(1, 1, pixel));
+        }
+        if let Some(sh) = self.ambient_sh {
+            renderer.set_ambient_sh(sh);
+        }
+        renderer.set_ambient_strength(self.ambient_strength);
+        if let Some(size) = self.shadow_map_size {
+            renderer.set_shadows(size);
+        }
+        renderer.set_pcf_kernel_size(self.pcf_kernel_size);
+        renderer.set_shadow_mode(self.shadow_mode);
+        if let Some(steps) = self.ssr_steps {
+            renderer.set_ssr(true, steps);
+        }
+        if let Some((length, steps)) = self.contact_shadows {
+            renderer.set_contact_shadows(length, steps);
+        }
+        renderer.set_subdivision_level(self.subdivision_level);
+        if let Some(bloom) = self.bloom {
+            renderer.set_bloom(bloom);
+        }
+        if let Some(target) = self.auto_exposure {
+            renderer.set_auto_exposure(target);
+        }
+        if let Some(dof) = self.dof {
+            renderer.set_dof(dof.focus_distance, dof.aperture);
+        }
+        if let Some(vignette) = self.vignette {
+            renderer.set_vignette(vignette);
+        }
+        if let Some(gains) = self.tint {
+            renderer.set_tint(gains);
+        }
+        if let Some(lod) = self.lod {
+            renderer.set_lod(lod);
+        }
+        if let Some(leaf_size) = self.frustum_culling {
+            renderer.set_frustum_culling(leaf_size);
+        }
+        if self.sphere_culling {
+            renderer.set_sphere_culling();
+        }
+        renderer.set_tile_size(self.tile_size);
+        renderer.set_bins_per_thread(self.bins_per_thread);
+        renderer
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use glam::{vec2, Vec2, Vec3};
+use wavefront_obj::obj::{Geometry, ObjSet, Object, Primitive, Shape, TVertex, VTNIndex};
+
+use crate::model::{to_vec3, to_vertex};
+use crate::{compute_normals, Texture};
+
+// Height in [0; 1] range (average of the channels) at uv, bilinearly filtered
+fn sample_height(map: &Texture, uv: Vec2) -> f32 {
+    let height = |x: u32, y: u32| {
+        let p = map.get_pixel(x.min(map.width() - 1), y.min(map.height() - 1));
+        (p[0] as f32 + p[1] as f32 + p[2] as f32) / (3.0 * 255.0)
+    };
+
+    let x = (uv.x() * (map.width() - 1) as f32).max(0.0);
+    let y = (uv.y() * (map.height() - 1) as f32).max(0.0);
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (tx, ty) = (x.fract(), y.fract());
+
+    let top = height(x0, y0) * (1.0 - tx) + height(x0 + 1, y0) * tx;
+    let bottom = height(x0, y0 + 1) * (1.0 - tx) + height(x0 + 1, y0 + 1) * tx;
+    top * (1.0 - ty) + bottom * ty
+}
+
+// Split every triangle of object into 4^level triangles and offset the vertices along the
+// (interpolated) normal by height map value at their UV coordinates times scale.
+// Returned object has normals recomputed for the displaced surface.
+pub(crate) fn displace(object: &Object, map: &Texture, scale: f32, level: u32) -> Object {
+    let n = 1usize << level.min(8);
+
+    let mut displaced = Object {
+        name: object.name.clone(),
+        vertices: Vec::new(),
+        tex_vertices: Vec::new(),
+        normals: Vec::new(),
+        geometry: Vec::new(),
+    };
+
+    // subdivided vertices are identified by the original corners they are interpolated from
+    // (with their weights), so points on edges shared by two triangles are shared as well
+    let mut indices: HashMap<Vec<(VTNIndex, usize)>, VTNIndex> = HashMap::new();
+
+    for geometry in &object.geometry {
+        let mut shapes = Vec::new();
+        for shape in &geometry.shapes {
+            let corners = match shape.primitive {
+                Primitive::Triangle(a, b, c) => [a, b, c],
+                _ => continue,
+            };
+
+            let mut vertex = |i: usize, j: usize| -> VTNIndex {
+                let weights = [n - i - j, i, j];
+                let mut key: Vec<(VTNIndex, usize)> = corners
+                    .iter()
+                    .zip(weights.iter())
+                    .filter(|(_, &w)| w != 0)
+                    .map(|(&corner, &w)| (corner, w))
+                    .collect();
+                key.sort_unstable();
+
+                if let Some(&index) = indices.get(&key) {
+                    return index;
+                }
+
+                let mut position = Vec3::zero();
+                let mut normal = Vec3::zero();
+                let mut uv = Some(Vec3::zero());
+                for &((v, t, vn), w) in &key {
+                    let w = w as f32 / n as f32;
+                    position += to_vec3(object.vertices[v]) * w;
+                    if let Some(vn) = vn {
+                        normal += to_vec3(object.normals[vn]) * w;
+                    }
+                    uv = match (uv, t) {
+                        (Some(uv), Some(t)) => {
+                            let t = object.tex_vertices[t];
+                            Some(uv + Vec3::new(t.u as f32, t.v as f32, t.w as f32) * w)
+                        }
+                        _ => None,
+                    };
+                }
+
+                let t = uv.map(|uv| {
+                    if normal.length_squared() > 0.0 {
+                        let height = sample_height(map, vec2(uv.x(), uv.y()));
+                        position += normal.normalize() * height * scale;
+                    }
+
+                    displaced.tex_vertices.push(TVertex {
+                        u: uv.x() as f64,
+                        v: uv.y() as f64,
+                        w: uv.z() as f64,
+                    });
+                    displaced.tex_vertices.len() - 1
+                });
+
+                displaced.vertices.push(to_vertex(position));
+                let index = (displaced.vertices.len() - 1, t, None);
+                indices.insert(key, index);
+                index
+            };
+
+            // same winding as the original triangle: (i, j) moves towards b and c respectively
+            let mut triangle = |a: VTNIndex, b: VTNIndex, c: VTNIndex| {
+                shapes.push(Shape {
+                    primitive: Primitive::Triangle(a, b, c),
+                    groups: shape.groups.clone(),
+                    smoothing_groups: vec![1],
+                });
+            };
+            for i in 0..n {
+                for j in 0..n - i {
+                    triangle(vertex(i, j), vertex(i + 1, j), vertex(i, j + 1));
+                    if i + j + 1 < n {
+                        triangle(vertex(i + 1, j), vertex(i + 1, j + 1), vertex(i, j + 1));
+                    }
+                }
+            }
+        }
+
+        displaced.geometry.push(Geometry {
+            material_name: geometry.material_name.clone(),
+            shapes,
+        });
+    }
+
+    let mut model = ObjSet {
+        material_library: None,
+        objects: vec![displaced],
+    };
+    compute_normals(&mut model);
+    model.objects.remove(0)
+}
@@ -0,0 +1,152 @@
+use anyhow::{bail, Result};
+
+use crate::{scene_models, scene_textures, Renderer, Scene};
+
+// What passes of a RenderGraph read and write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PassBuffer {
+    // shadow maps of the lights
+    ShadowMaps,
+    // the depth buffer
+    Depth,
+    // the image (and its linear lighting)
+    Color,
+}
+
+struct Node<'a> {
+    name: String,
+    reads: Vec<PassBuffer>,
+    writes: Vec<PassBuffer>,
+    run: Box<dyn FnMut(&mut Renderer) + 'a>,
+}
+
+impl Node<'_> {
+    fn reads(&self, buffer: PassBuffer) -> bool {
+        self.reads.contains(&buffer)
+    }
+
+    fn writes(&self, buffer: PassBuffer) -> bool {
+        self.writes.contains(&buffer)
+    }
+}
+
+// Render passes declared with the buffers they read and write, run in the order these
+// dependencies require rather than the one they're added in. A pass runs after the passes
+// writing a buffer it reads (that don't read it themselves), and passes both reading and
+// writing a buffer (e.g. screen-space effects modifying the image) run in the order they're
+// added in, before passes only reading it. Otherwise passes keep the order they're added in.
+// NOTE: the whole graph is a single render call, blended as set with Renderer::set_pass_blend
+#[derive(Default)]
+pub struct RenderGraph<'a> {
+    nodes: Vec<Node<'a>>,
+}
+
+impl<'a> RenderGraph<'a> {
+    pub fn new() -> Self {
+        RenderGraph { nodes: Vec::new() }
+    }
+
+    pub fn add_pass<F: FnMut(&mut Renderer) + 'a>(
+        &mut self,
+        name: &str,
+        reads: &[PassBuffer],
+        writes: &[PassBuffer],
+        run: F,
+    ) -> &mut Self {
+        self.nodes.push(Node {
+            name: name.to_owned(),
+            reads: reads.to_vec(),
+            writes: writes.to_vec(),
+            run: Box::new(run),
+        });
+        self
+    }
+
+    // shadow maps of every instance of scene (if shadows are on), see Renderer::set_shadows
+    pub fn shadow_pass(&mut self, scene: &'a Scene) -> &mut Self {
+        self.add_pass("shadow", &[], &[PassBuffer::ShadowMaps], move |renderer| {
+            renderer.shadow_pass(&scene_models(scene))
+        })
+    }
+
+    // every instance of scene, transparent triangles after the opaque ones
+    pub fn main_pass(&mut self, scene: &'a Scene) -> &mut Self {
+        let (reads, writes) = (
+            [PassBuffer::ShadowMaps],
+            [PassBuffer::Depth, PassBuffer::Color],
+        );
+        self.add_pass("main", &reads, &writes, move |renderer| {
+            let textures = scene_textures(scene);
+            renderer.draw_instances(scene);
+            renderer.draw_opaque(&textures);
+            renderer.draw_transparent(&textures);
+        })
+    }
+
+    // contact shadows of what's been drawn (if on), see Renderer::set_contact_shadows
+    pub fn contact_shadow_pass(&mut self) -> &mut Self {
+        let (reads, writes) = ([PassBuffer::Depth, PassBuffer::Color], [PassBuffer::Color]);
+        self.add_pass("contact shadows", &reads, &writes, Renderer::shade_contacts)
+    }
+
+    // reflections of what's been drawn (if on), see Renderer::set_ssr
+    pub fn ssr_pass(&mut self) -> &mut Self {
+        let (reads, writes) = ([PassBuffer::Depth, PassBuffer::Color], [PassBuffer::Color]);
+        self.add_pass("ssr", &reads, &writes, Renderer::reflect)
+    }
+
+    // names of the passes in the order they run, fails if they depend on each other
+    pub fn order(&self) -> Result<Vec<&str>> {
+        Ok(self
+            .sorted()?
+            .into_iter()
+            .map(|i| self.nodes[i].name.as_str())
+            .collect())
+    }
+
+    // run the passes in the order they depend on each other in
+    pub fn execute(&mut self, renderer: &mut Renderer) -> Result<()> {
+        let order = self.sorted()?;
+        let nodes = &mut self.nodes;
+        renderer.blended(|renderer| {
+            for i in order {
+                (nodes[i].run)(renderer);
+            }
+        });
+        Ok(())
+    }
+
+    // whether pass a has to run before pass b
+    fn precedes(&self, a: usize, b: usize) -> bool {
+        let (a_node, b_node) = (&self.nodes[a], &self.nodes[b]);
+        a_node.writes.iter().any(|&buffer| {
+            b_node.reads(buffer) && (!a_node.reads(buffer) || !b_node.writes(buffer) || a < b)
+        })
+    }
+
+    // indices of the passes in topological order, the earliest added one first among the ready
+    fn sorted(&self) -> Result<Vec<usize>> {
+        let count = self.nodes.len();
+        let mut done = vec![false; count];
+        let mut order = Vec::with_capacity(count);
+        while order.len() < count {
+            let ready = (0..count).find(|&b| {
+                !done[b] && (0..count).all(|a| a == b || done[a] || !self.precedes(a, b))
+            });
+            match ready {
+                Some(b) => {
+                    done[b] = true;
+                    order.push(b);
+                }
+                None => {
+                    let names: Vec<_> = (0..count)
+                        .filter(|&i| !done[i])
+                        .map(|i| &self.nodes[i].name)
+                        .collect();
+                    bail!("Render passes {:?} depend on each other", names)
+                }
+            }
+        }
+        Ok(order)
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use glam::vec3;
+use wavefront_obj::obj::{ObjSet, Primitive};
+
+use crate::{Camera, Color, Image, Renderer, PIXEL_UV_THRESHOLD};
+
+// Edges of model's triangles in texture space, white on black in an image of size (e.g. that of
+// the model's texture) laid out like the texture file, to see UV islands and where they
+// overlap. Objects with UVs in texture pixels (see UvInterpretation::Auto) are scaled down by
+// size, triangles without UVs are left out.
+pub fn uv_layout(model: &ObjSet, size: (usize, usize)) -> Image {
+    // the [0; 1] UV square is the visible area of the xy plane
+    let mut camera = Camera::new(
+        vec3(0.5, 0.5, -1.0),
+        vec3(0.5, 0.5, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    camera.zoom(2.0);
+    let mut renderer = Renderer::new(camera, size);
+
+    let color = Color::from([255, 255, 255]);
+    for object in &model.objects {
+        let pixels = object
+            .tex_vertices
+            .iter()
+            .any(|v| v.u.max(v.v) > PIXEL_UV_THRESHOLD);
+        let scale = if pixels {
+            vec3(size.0.max(1) as f32, size.1.max(1) as f32, 1.0)
+        } else {
+            vec3(1.0, 1.0, 1.0)
+        };
+        let uv = |i: usize| {
+            let v = object.tex_vertices[i];
+            vec3(v.u as f32, v.v as f32, 0.0) / scale
+        };
+
+        // edges shared by several triangles are drawn once
+        let mut drawn = HashSet::new();
+        let shapes = object.geometry.iter().flat_map(|geometry| &geometry.shapes);
+        for shape in shapes {
+            let (a, b, c) = match shape.primitive {
+                Primitive::Triangle((_, Some(a), _), (_, Some(b), _), (_, Some(c), _)) => (a, b, c),
+                _ => continue,
+            };
+            for &(from, to) in &[(a, b), (b, c), (c, a)] {
+                if drawn.insert((from.min(to), from.max(to))) {
+                    renderer.line(uv(from), uv(to), color);
+                }
+            }
+        }
+    }
+    // v grows up, textures are flipped when they're read
+    renderer.flipv();
+    renderer.image().clone()
+}
+
+// render the UV layout of model (see uv_layout) to image file out
+pub fn render_uv_layout(model: &ObjSet, size: (usize, usize), out: &str) -> Result<()> {
+    uv_layout(model, size).save(out)?;
+    Ok(())
+}
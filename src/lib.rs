@@ -0,0 +1,4604 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use glam::{vec2, vec3, Mat3, Mat4, Vec2, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+use wavefront_obj::obj::{self, ObjSet, Object, Primitive, TVertex, Vertex};
+
+mod ao;
+mod bvh;
+mod config;
+mod displace;
+mod graph;
+mod layout;
+mod lut;
+mod material;
+mod model;
+mod morph;
+mod post;
+mod scene;
+mod sh;
+mod shadow;
+mod shapes;
+mod sheet;
+mod skin;
+#[cfg(feature = "stl")]
+mod stl;
+mod subdivide;
+mod texture;
+mod timeline;
+
+pub use ao::{read_vertex_ao, read_vertex_colors, AoSource, VertexAo, VertexColors};
+pub use bvh::CullingStats;
+use bvh::{visible_spheres, Bvh, Frustum};
+pub use config::{CameraConfig, RenderConfig};
+pub use graph::{PassBuffer, RenderGraph};
+pub use layout::{render_uv_layout, uv_layout};
+use lut::ColorLut;
+pub use material::{read_materials, read_model_materials, Material, MaterialOverride, Materials};
+use model::to_vec3;
+pub use model::{
+    compute_normals, compute_weighted_normals, convert_axes, weld_vertices, AxisConvention,
+    NormalWeighting,
+};
+pub use morph::morph;
+pub use post::{Bloom, DepthOfField, ResolveFilter, Vignette};
+#[cfg(feature = "gltf")]
+pub use scene::read_gltf;
+pub use scene::{Instance, Lod, LodStats, Mesh, Scene};
+pub use sh::{AmbientSh, EnvironmentMap};
+use shadow::ShadowMap;
+pub use shadow::ShadowMode;
+pub use sheet::{contact_sheet, fit_transform, render_contact_sheet};
+pub use skin::{skin, BoneWeights};
+#[cfg(feature = "stl")]
+pub use stl::read_stl;
+pub use subdivide::loop_subdivide;
+pub use texture::TextureExt;
+pub use timeline::{Interpolation, Keyframe, Timeline};
+
+pub type Image = image::RgbImage;
+pub type Color = image::Rgb<u8>;
+pub type Texture = Image;
+
+#[inline(always)]
+fn min(a: f32, b: f32) -> f32 {
+    if a > b {
+        b
+    } else {
+        a
+    }
+}
+
+#[inline(always)]
+fn max(a: f32, b: f32) -> f32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+// Barycentric coordinates (weights of a, b and c) of point p in triangle abc
+#[inline(always)]
+pub fn barycentric(a: Vec2, b: Vec2, c: Vec2, p: Vec2) -> Vec3 {
+    let xs = vec3(c.x() - a.x(), b.x() - a.x(), a.x() - p.x());
+    let ys = vec3(c.y() - a.y(), b.y() - a.y(), a.y() - p.y());
+    let u = xs.cross(ys);
+    // (u.y/u.z, u.x/u.z) are coordinates in (a, ab, ac) basis
+    // TODO: why are we dividing by z here?
+    vec3(1.0 - (u.x() + u.y()) / u.z(), u.y() / u.z(), u.x() / u.z())
+}
+
+// Rectangle of pixels, (x, y) is its corner nearest to the origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    fn intersect(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let top = (self.y + self.height).min(other.y + other.height);
+        Rect {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: top.saturating_sub(y),
+        }
+    }
+
+    fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+}
+
+// Invoke function f for every point in triangle abc that is inside of bounds. Conservative
+// rasterization covers every pixel (unit square around its point) the triangle overlaps, points
+// outside of the triangle get its barycentric coordinates clamped to it.
+fn in_triangle<F>(a: Vec2, b: Vec2, c: Vec2, bounds: Rect, conservative: bool, mut f: F)
+where
+    F: FnMut(usize, usize, Vec3),
+{
+    if bounds.width == 0 || bounds.height == 0 {
+        return;
+    }
+
+    // degenerate (zero area) triangle, barycentric coordinates are undefined
+    if (b - a).perp_dot(c - a) == 0.0 {
+        return;
+    }
+
+    let (mut min_p, mut max_p) = (
+        vec2(min(a.x(), min(b.x(), c.x())), min(a.y(), min(b.y(), c.y()))),
+        vec2(max(a.x(), max(b.x(), c.x())), max(a.y(), max(b.y(), c.y()))),
+    );
+    // how much barycentric coordinates can grow within a pixel from its point
+    let mut margin = Vec3::zero();
+    if conservative {
+        min_p = vec2((min_p.x() - 0.5).ceil(), (min_p.y() - 0.5).ceil());
+        max_p += Vec2::splat(0.5);
+        let origin = barycentric(a, b, c, Vec2::zero());
+        let dx = barycentric(a, b, c, vec2(1.0, 0.0)) - origin;
+        let dy = barycentric(a, b, c, vec2(0.0, 1.0)) - origin;
+        margin = (dx.abs() + dy.abs()) * 0.5;
+    }
+
+    let min_x = (min_p.x() as usize).max(bounds.x);
+    let min_y = (min_p.y() as usize).max(bounds.y);
+
+    let max_x = (max_p.x() as usize).min(bounds.x + bounds.width - 1);
+    let max_y = (max_p.y() as usize).min(bounds.y + bounds.height - 1);
+
+    for y in min_y..(max_y + 1) {
+        for x in min_x..(max_x + 1) {
+            let p = vec2(x as f32, y as f32);
+            let mut bc = barycentric(a, b, c, p);
+            let reach = bc + margin;
+            if reach.x() < 0.0 || reach.y() < 0.0 || reach.z() < 0.0 {
+                continue;
+            }
+            if conservative {
+                let inside = bc.max(Vec3::zero());
+                bc = inside / (inside.x() + inside.y() + inside.z());
+            }
+
+            check_barycentric(bc, p, [a, b, c]);
+            f(x, y, bc);
+        }
+    }
+}
+
+// Clip triangle abc (in screen coordinates) to the guard band: screen area of (width, height)
+// size extended by half of it in every direction. Returns the clipped polygon as barycentric
+// coordinates (weights of a, b and c) of its vertices, None if the triangle fits entirely.
+fn guard_band_clip(triangle: [Vec2; 3], (width, height): (usize, usize)) -> Option<Vec<Vec3>> {
+    let (width, height) = (width as f32, height as f32);
+    let (min_x, max_x) = (-width / 2.0, width * 1.5);
+    let (min_y, max_y) = (-height / 2.0, height * 1.5);
+
+    let inside = |p: Vec2| p.x() >= min_x && p.x() <= max_x && p.y() >= min_y && p.y() <= max_y;
+    if triangle.iter().all(|&p| inside(p)) {
+        return None;
+    }
+
+    let position = |w: Vec3| triangle[0] * w.x() + triangle[1] * w.y() + triangle[2] * w.z();
+    // signed distances to the band edges, positive inside
+    let edges: [&dyn Fn(Vec2) -> f32; 4] = [
+        &|p| p.x() - min_x,
+        &|p| max_x - p.x(),
+        &|p| p.y() - min_y,
+        &|p| max_y - p.y(),
+    ];
+    Some(clip_polygon(&edges.map(|edge| move |w| edge(position(w)))))
+}
+
+// Guard band of the frustum in normalized device coordinates: x and y in [-2; 2] (the screen
+// extended by half of it in every direction, inside of guard_band_clip's)
+const GUARD_BAND: f32 = 2.0;
+
+// Clip triangle with vertices at clip space positions (see Camera::clip) to the six planes of
+// camera's frustum: the near and the far one (see Camera::set_far) and the four sides. The
+// sides are deliberately widened from |x|, |y| <= w to the guard band: what's between them is
+// off screen but cheap to rasterize, and clipped there exactly (see guard_band_clip) with fewer
+// new vertices. Returns the clipped polygon as barycentric coordinates of its vertices like
+// guard_band_clip. Attributes interpolated with them are exact at the new vertices, since clip
+// space positions are linear in world space ones.
+fn frustum_clip(triangle: [Vec4; 3], camera: &Camera) -> Vec<Vec3> {
+    let position = |w: Vec3| triangle[0] * w.x() + triangle[1] * w.y() + triangle[2] * w.z();
+    let near = camera.near_w();
+    let planes: [&dyn Fn(Vec4) -> f32; 6] = [
+        &|p| GUARD_BAND * p.w() + p.x(),
+        &|p| GUARD_BAND * p.w() - p.x(),
+        &|p| GUARD_BAND * p.w() + p.y(),
+        &|p| GUARD_BAND * p.w() - p.y(),
+        &|p| p.w() - near,
+        &|p| camera.far_distance(p),
+    ];
+    clip_polygon(&planes.map(|plane| move |w| plane(position(w))))
+}
+
+// Sutherland-Hodgman clipping of triangle (1, 0, 0), (0, 1, 0), (0, 0, 1) in barycentric
+// coordinates by edges, signed distances of points to them (positive inside)
+fn clip_polygon<F: Fn(Vec3) -> f32>(edges: &[F]) -> Vec<Vec3> {
+    let mut polygon = vec![
+        vec3(1.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 0.0, 1.0),
+    ];
+    for distance in edges.iter() {
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for (i, &current) in polygon.iter().enumerate() {
+            let next = polygon[(i + 1) % polygon.len()];
+            let (d0, d1) = (distance(current), distance(next));
+            if d0 >= 0.0 {
+                clipped.push(current);
+            }
+            // interpolate from the inside vertex, so t stays small and the tiny weight of a far
+            // away vertex isn't rounded away
+            if d0 >= 0.0 && d1 < 0.0 {
+                clipped.push(current + (next - current) * (d0 / (d0 - d1)));
+            } else if d0 < 0.0 && d1 >= 0.0 {
+                clipped.push(next + (current - next) * (d1 / (d1 - d0)));
+            }
+        }
+
+        polygon = clipped;
+        if polygon.is_empty() {
+            break;
+        }
+    }
+    polygon
+}
+
+// Panics if barycentric coordinates of accepted pixel p don't sum up to 1 or are out of [0; 1]
+// range, which indicates a bug in clipping or degenerate triangles handling. Debug builds only.
+#[inline(always)]
+pub fn check_barycentric(bc: Vec3, p: Vec2, triangle: [Vec2; 3]) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    const EPSILON: f32 = 1e-3;
+    let sum = bc.x() + bc.y() + bc.z();
+    let in_range = |v: f32| (-EPSILON..=1.0 + EPSILON).contains(&v);
+    assert!(
+        (sum - 1.0).abs() <= EPSILON && in_range(bc.x()) && in_range(bc.y()) && in_range(bc.z()),
+        "Invalid barycentric coordinates {:?} (sum {}) of pixel {:?} in triangle {:?}",
+        bc,
+        sum,
+        p,
+        triangle
+    );
+}
+
+// Panics if depth z of pixel (x, y) isn't finite, e.g. from infinite or NaN vertices that
+// got past projection. Debug builds only.
+#[inline(always)]
+fn check_depth(z: f32, (x, y): (usize, usize)) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    assert!(z.is_finite(), "Invalid depth {} of pixel ({}, {})", z, x, y);
+}
+
+// Panics if texel coordinates uv (in texture pixels) of pixel (x, y) don't point into a texture
+// of size, which indicates a bug in UV scaling or wrapping. Debug builds only.
+#[inline(always)]
+fn check_texel(uv: Vec2, size: (u32, u32), (x, y): (usize, usize)) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    // negative coordinates saturate to 0 when cast
+    let inside = uv.x().is_finite()
+        && uv.y().is_finite()
+        && (uv.x() as u32) < size.0
+        && (uv.y() as u32) < size.1;
+    assert!(
+        inside,
+        "Texel coordinates {:?} of pixel ({}, {}) are outside of {}x{} texture",
+        uv, x, y, size.0, size.1
+    );
+}
+
+// Panics if pixel (x, y) is outside of the target of size, its position in the buffers would
+// be that of another pixel. Debug builds only.
+#[inline(always)]
+fn check_pixel((x, y): (usize, usize), size: (usize, usize)) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    assert!(
+        x < size.0 && y < size.1,
+        "Pixel ({}, {}) is outside of {}x{} target",
+        x,
+        y,
+        size.0,
+        size.1
+    );
+}
+
+// threshold (in (0; 1)) of the 4x4 ordered dither pattern at pixel (x, y), alpha to coverage
+// covers pixels where alpha is above it
+fn dither(x: usize, y: usize) -> f32 {
+    const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+    (BAYER[y % 4][x % 4] as f32 + 0.5) / 16.0
+}
+
+// direction and up vector of cubemap faces (+X, -X, +Y, -Y, +Z, -Z), see Renderer::render_cubemap
+fn cubemap_faces() -> [(Vec3, Vec3); 6] {
+    let (x, y, z) = (
+        vec3(1.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 0.0, 1.0),
+    );
+    [(x, y), (-x, y), (y, z), (-y, -z), (z, y), (-z, y)]
+}
+
+// texel at uv (in texture pixels, see Renderer::texture_coords), wrapped outside of the texture
+fn sample(texture: &Texture, uv: Vec2, wrap: TextureWrap) -> Color {
+    let (max_x, max_y) = ((texture.width() - 1) as f32, (texture.height() - 1) as f32);
+    let outside = uv.x() < 0.0 || uv.y() < 0.0 || uv.x() > max_x || uv.y() > max_y;
+    let (x, y) = match wrap {
+        TextureWrap::Clamp => (uv.x().min(max_x), uv.y().min(max_y)),
+        // a period is max_x (max_y) pixels long, as UVs are scaled by it
+        TextureWrap::Repeat => (
+            uv.x().rem_euclid(max_x.max(1.0)),
+            uv.y().rem_euclid(max_y.max(1.0)),
+        ),
+        TextureWrap::Border(color) if outside => return color,
+        TextureWrap::Border(_) => (uv.x(), uv.y()),
+    };
+    // NOTE: float to int casts saturate, so negative coordinates are clamped to 0
+    *texture.get_pixel(x as u32, y as u32)
+}
+
+#[inline(always)]
+fn to_linear(color: Color) -> Vec3 {
+    vec3(color[0] as f32, color[1] as f32, color[2] as f32) / 255.0
+}
+
+// Range of Renderer::luminance_histogram in stops: log2 of luminance of linear colors, from
+// about 1/4000 to 16 times white
+pub const LUMINANCE_STOPS: (f32, f32) = (-12.0, 4.0);
+
+// bins of the luminance histogram auto exposure is computed from, a third of a stop each
+const EXPOSURE_BINS: usize = 48;
+
+// relative luminance of linear color c
+fn luminance(c: Vec3) -> f32 {
+    0.2126 * c.x() + 0.7152 * c.y() + 0.0722 * c.z()
+}
+
+// bin of linear color c in luminance histogram of bins bins, see Renderer::luminance_histogram
+fn luminance_bin(c: Vec3, bins: usize) -> usize {
+    let (min, max) = LUMINANCE_STOPS;
+    let stops = luminance(c).max(0.0).log2().max(min);
+    (((stops - min) / (max - min) * bins as f32) as usize).min(bins - 1)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Projection {
+    // visible area is [-1; 1] / scale around lookat
+    Orthographic { scale: f32 },
+    // vertical field of view (in radians)
+    Perspective { fov: f32 },
+}
+
+// Handedness of world coordinates. The camera basis follows it, so scenes from either kind of
+// tool aren't mirrored, and triangle winding is read the same way (see FrontFace), so the same
+// faces are culled and lit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Handedness {
+    // e.g. OBJ files and OpenGL: x to the right and z towards the viewer
+    #[default]
+    RightHanded,
+    // e.g. Direct3D: x to the right and z away from the viewer
+    LeftHanded,
+}
+
+#[derive(Debug, Clone)]
+pub struct Camera {
+    lookfrom: Vec3,
+    lookat: Vec3,
+    up: Vec3,
+    projection: Projection,
+    handedness: Handedness,
+    translation: Mat3,
+    // inverse of translation, from camera-centric coordinates to world space (relative to lookat)
+    to_world: Mat3,
+    // depth of the far clipping plane, see set_far
+    far: f32,
+}
+
+impl Camera {
+    // orthographic camera
+    pub fn new(lookfrom: Vec3, lookat: Vec3, up: Vec3) -> Self {
+        Self::with_projection(
+            lookfrom,
+            lookat,
+            up,
+            Projection::Orthographic { scale: 1.0 },
+        )
+    }
+
+    // perspective camera with vertical field of view fov (in radians)
+    pub fn perspective(lookfrom: Vec3, lookat: Vec3, up: Vec3, fov: f32) -> Self {
+        Self::with_projection(lookfrom, lookat, up, Projection::Perspective { fov })
+    }
+
+    pub fn with_projection(lookfrom: Vec3, lookat: Vec3, up: Vec3, projection: Projection) -> Self {
+        let mut camera = Camera {
+            lookfrom,
+            lookat,
+            up,
+            projection,
+            handedness: Handedness::RightHanded,
+            translation: Mat3::identity(),
+            to_world: Mat3::identity(),
+            far: f32::INFINITY,
+        };
+        camera.update();
+        camera
+    }
+
+    // distance of world space point p from the viewer along the view direction
+    fn depth(&self, p: Vec3) -> f32 {
+        self.camera_depth(self.translate(p - self.lookat))
+    }
+
+    // depth of point v in camera-centric coordinates, the viewer is at distance from lookat on
+    // the z axis (see project_camera)
+    fn camera_depth(&self, v: Vec3) -> f32 {
+        (self.lookfrom - self.lookat).length() - v.z()
+    }
+
+    fn update(&mut self) {
+        // z axis points from the camera
+        let z_axis = (self.lookat - self.lookfrom).normalize();
+        // y axis points up
+        let y_axis = self.up.normalize();
+        // x axis points to the left, or to the right in left-handed coordinates (which mirrors
+        // the image)
+        let x_axis = match self.handedness {
+            Handedness::RightHanded => y_axis.cross(z_axis),
+            Handedness::LeftHanded => z_axis.cross(y_axis),
+        };
+        let x_axis = x_axis.normalize();
+        // translation to camera-centric coordinate system (rotation part)
+        self.translation = Mat3::from_cols(x_axis, y_axis, z_axis);
+        self.to_world = self.translation.inverse();
+    }
+
+    pub fn set_handedness(&mut self, handedness: Handedness) {
+        self.handedness = handedness;
+        self.update();
+    }
+
+    // Don't draw triangles (or parts of them) farther than far from the viewer along the view
+    // direction. There's no far plane by default.
+    pub fn set_far(&mut self, far: f32) {
+        self.far = far;
+    }
+
+    pub fn clear_far(&mut self) {
+        self.far = f32::INFINITY;
+    }
+
+    // signed distance of clip space point p (see clip) to the far plane, positive in front of it
+    fn far_distance(&self, p: Vec4) -> f32 {
+        // the depth of p is distance - z with both projections
+        self.far - ((self.lookfrom - self.lookat).length() - p.z())
+    }
+
+    // move towards lookat by delta (away from it if delta is negative), lookat stays in place.
+    // NOTE: has no visible effect with orthographic projection
+    pub fn dolly(&mut self, delta: f32) {
+        let offset = self.lookfrom - self.lookat;
+        let distance = offset.length();
+        // don't go through (or too close to) lookat
+        let new_distance = max(distance - delta, distance * 1e-3);
+        self.lookfrom = self.lookat + offset * (new_distance / distance);
+    }
+
+    // move both lookfrom and lookat in the view plane, dx to the right and dy up on the screen
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let offset = self.to_world * vec3(dx, dy, 0.0);
+        self.lookfrom += offset;
+        self.lookat += offset;
+    }
+
+    // magnify the image by factor (> 1 zooms in, < 1 zooms out)
+    pub fn zoom(&mut self, factor: f32) {
+        if factor <= 0.0 {
+            return;
+        }
+
+        self.projection = match self.projection {
+            Projection::Orthographic { scale } => Projection::Orthographic {
+                scale: scale * factor,
+            },
+            Projection::Perspective { fov } => {
+                let fov = 2.0 * ((fov / 2.0).tan() / factor).atan();
+                Projection::Perspective { fov }
+            }
+        };
+    }
+
+    // translate point p to camera-centric coordinate system
+    fn translate(&self, point: Vec3) -> Vec3 {
+        self.translation * point //+ self.lookfrom
+    }
+
+    // project world space point to [-1; 1] range (z is growing towards the viewer),
+    // None if the point is behind the viewer
+    fn project(&self, point: Vec3) -> Option<Vec3> {
+        self.project_camera(self.translate(point - self.lookat))
+    }
+
+    // Homogeneous clip space position of world space point lookat + offset: its projection (see
+    // project) is (x, y, z) / w, and w is its distance from the viewer along the view direction
+    // (1 for orthographic projection)
+    fn clip(&self, offset: Vec3) -> Vec4 {
+        let v = self.translate(offset);
+        match self.projection {
+            Projection::Orthographic { scale } => {
+                Vec4::new(v.x() * scale, v.y() * scale, v.z(), 1.0)
+            }
+            Projection::Perspective { fov } => {
+                let distance = (self.lookfrom - self.lookat).length();
+                let w = distance - v.z();
+                let f = 1.0 / (fov / 2.0).tan();
+                Vec4::new(v.x() * f, v.y() * f, distance - w, w)
+            }
+        }
+    }
+
+    // smallest w (see clip) of points that can be projected
+    fn near_w(&self) -> f32 {
+        match self.projection {
+            Projection::Orthographic { .. } => 0.0,
+            Projection::Perspective { .. } => (self.lookfrom - self.lookat).length() * 1e-3,
+        }
+    }
+
+    // project point in camera-centric coordinates relative to lookat, see project
+    fn project_camera(&self, v: Vec3) -> Option<Vec3> {
+        match self.projection {
+            Projection::Orthographic { scale } => Some(vec3(v.x() * scale, v.y() * scale, v.z())),
+            Projection::Perspective { fov } => {
+                // the viewer is at distance from lookat on the z axis
+                let distance = (self.lookfrom - self.lookat).length();
+                let w = distance - v.z();
+                if w < distance * 1e-3 {
+                    return None;
+                }
+
+                let f = 1.0 / (fov / 2.0).tan();
+                // 1/w is linear in screen space, so z can be interpolated across triangles
+                Some(vec3(v.x() * f / w, v.y() * f / w, distance / w - 1.0))
+            }
+        }
+    }
+
+    // inverse of project_camera
+    fn unproject_camera(&self, p: Vec3) -> Vec3 {
+        match self.projection {
+            Projection::Orthographic { scale } => vec3(p.x() / scale, p.y() / scale, p.z()),
+            Projection::Perspective { fov } => {
+                let distance = (self.lookfrom - self.lookat).length();
+                let w = distance / (p.z() + 1.0);
+                let f = 1.0 / (fov / 2.0).tan();
+                vec3(p.x() * w / f, p.y() * w / f, distance - w)
+            }
+        }
+    }
+
+    // camera-centric direction from the viewer towards point v (relative to lookat)
+    fn view_direction(&self, v: Vec3) -> Vec3 {
+        match self.projection {
+            Projection::Orthographic { .. } => vec3(0.0, 0.0, -1.0),
+            Projection::Perspective { .. } => {
+                let distance = (self.lookfrom - self.lookat).length();
+                v - vec3(0.0, 0.0, distance)
+            }
+        }
+    }
+}
+
+// Winding order (as seen on the screen, or mirrored in left-handed coordinates) of front
+// facing triangles, back faces are culled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrontFace {
+    Cw,
+    Ccw,
+}
+
+// What textures are sampled with outside of UVs in [0; 1], see Renderer::set_texture_wrap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextureWrap {
+    // the nearest edge texel
+    #[default]
+    Clamp,
+    // tile
+    Repeat,
+    // fixed color, e.g. for decals that shouldn't tile or smear
+    Border(Color),
+}
+
+// How texture coordinates of models are read, see Renderer::set_uv_interpretation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UvInterpretation {
+    // texture pixels for objects with any UV well above 1 (see PIXEL_UV_THRESHOLD), normalized
+    // otherwise
+    #[default]
+    Auto,
+    // [0; 1] across the texture (and tiled or clamped outside of it)
+    Normalized,
+    // texture pixels, divided by the size of the texture they're sampled from
+    Pixels,
+}
+
+// UVs of tiled textures rarely go above it, pixel ones of anything but tiny textures do
+const PIXEL_UV_THRESHOLD: f64 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShadingMode {
+    // textured and lit, the material color alone is lit if there is no texture (or texture
+    // coordinates)
+    Lit,
+    // unlit, every triangle gets its own color derived from its index in the model
+    TriangleColors,
+    // lit vertex colors (see Renderer::set_vertex_colors) interpolated across triangles, objects
+    // without them are lit like with ShadingMode::Lit
+    VertexColors,
+    // unlit vertex normals (face normals without them) interpolated across triangles, x, y and z
+    // in [-1; 1] as red, green and blue in [0; 255], in the space set with
+    // Renderer::set_normal_space
+    Normals,
+}
+
+// Space of normals visualized with ShadingMode::Normals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalSpace {
+    // camera space (x to the right, y up and z towards the viewer): the model's normal matrix
+    // (inverse transpose of the model transform) followed by the camera rotation
+    #[default]
+    View,
+    // world space: the model's normal matrix only, independent of the camera
+    World,
+}
+
+// How a render call (render_scene, obj, ...) is combined with what's already been rendered
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PassBlend {
+    // nearer fragments replace farther ones, as if everything was rendered at once
+    #[default]
+    Depth,
+    // the pass is laid over with opacity wherever it covers anything, regardless of depth
+    Over {
+        opacity: f32,
+    },
+    // colors of the pass are added to the previous ones
+    Add,
+    // or multiply them
+    Multiply,
+}
+
+impl PassBlend {
+    // combine color of the pass with the previous one, both either 8-bit or linear
+    fn apply(self, previous: Vec3, pass: Vec3, white: f32) -> Vec3 {
+        match self {
+            PassBlend::Depth => pass,
+            PassBlend::Over { opacity } => previous.lerp(pass, opacity.clamp(0.0, 1.0)),
+            PassBlend::Add => previous + pass,
+            PassBlend::Multiply => previous * pass / white,
+        }
+    }
+}
+
+// Directional light in addition to the key one (see Renderer::add_light)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Light {
+    // in camera space, towards the light (like Renderer::set_light_direction)
+    pub direction: Vec3,
+    // scale of its diffuse and specular light, the key light has 1
+    pub intensity: f32,
+    // whether it gets its own shadow map when shadows are on, see Renderer::set_shadows
+    pub casts_shadows: bool,
+}
+
+// Byte layout of an exported image (see Renderer::export_as), alpha is always opaque
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelFormat {
+    #[default]
+    Rgb,
+    Rgba,
+    // e.g. for Windows bitmaps
+    Bgra,
+}
+
+impl PixelFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgb => 3,
+            PixelFormat::Rgba | PixelFormat::Bgra => 4,
+        }
+    }
+}
+
+// What an object (for object ids and outlines) of an OBJ file is, exporters use either `o` or `g`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupingMode {
+    // `o` blocks
+    #[default]
+    Objects,
+    // `g` groups (named after all groups of the shape, "default" if there are none)
+    Groups,
+}
+
+// Which shapes are drawn (and cast shadows) by the `g` groups they are in, shapes without any
+// are in the "default" one
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupFilter {
+    #[default]
+    All,
+    // shapes in any of these groups
+    Only(Vec<String>),
+    // shapes in none of these groups
+    Except(Vec<String>),
+}
+
+impl GroupFilter {
+    pub fn includes(&self, groups: &[String]) -> bool {
+        let listed = |names: &[String]| match groups {
+            [] => names.iter().any(|name| name == "default"),
+            groups => groups.iter().any(|group| names.contains(group)),
+        };
+        match self {
+            GroupFilter::All => true,
+            GroupFilter::Only(names) => listed(names),
+            GroupFilter::Except(names) => !listed(names),
+        }
+    }
+}
+
+// Color scale of false-color images (see Renderer::save_depth_colored), from t = 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Colormap {
+    // perceptually uniform dark purple to yellow of matplotlib
+    #[default]
+    Viridis,
+    // dark blue through cyan, yellow and red to dark red
+    Jet,
+}
+
+// viridis at t = 0, 1/8, ..., 1
+const VIRIDIS: [[u8; 3]; 9] = [
+    [68, 1, 84],
+    [71, 44, 122],
+    [59, 81, 139],
+    [44, 113, 142],
+    [33, 144, 141],
+    [39, 173, 129],
+    [92, 200, 99],
+    [170, 220, 50],
+    [253, 231, 37],
+];
+
+impl Colormap {
+    pub fn color(self, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let rgb = match self {
+            Colormap::Viridis => {
+                let x = t * (VIRIDIS.len() - 1) as f32;
+                let i = (x as usize).min(VIRIDIS.len() - 2);
+                let (from, to) = (VIRIDIS[i], VIRIDIS[i + 1]);
+                let c =
+                    |k: usize| from[k] as f32 + (to[k] as f32 - from[k] as f32) * (x - i as f32);
+                vec3(c(0), c(1), c(2))
+            }
+            Colormap::Jet => {
+                let c = |center: f32| (1.5 - (4.0 * t - center).abs()).clamp(0.0, 1.0) * 255.0;
+                vec3(c(3.0), c(2.0), c(1.0))
+            }
+        };
+        Color::from([
+            rgb.x().round() as u8,
+            rgb.y().round() as u8,
+            rgb.z().round() as u8,
+        ])
+    }
+}
+
+// Triangle visible at a pixel, see Renderer::pick
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickResult {
+    // name of the object (or group, see set_grouping_mode) it belongs to
+    pub object: String,
+    // index of the triangle in its model (the one ShadingMode::TriangleColors colors it by)
+    pub triangle: usize,
+    // screen space depth (larger is nearer) of the pixel, transparent triangles don't write it
+    pub depth: f32,
+}
+
+// How colors of a normal map are decoded into normals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalMapSpace {
+    // relative to the surface: x along u, y along v and z along the interpolated vertex normal
+    Tangent,
+    // model space normals (transformed to the world the same way as vertex normals)
+    Object,
+}
+
+// Normal sampled from a normal map (in camera space), normal is the interpolated vertex normal
+// and tangents are the directions of growing u and v (see Renderer::tangents)
+fn map_normal(
+    sample: Color,
+    space: NormalMapSpace,
+    normal: Vec3,
+    tangents: [Vec3; 2],
+    object_to_camera: Mat3,
+) -> Vec3 {
+    let n = vec3(sample[0] as f32, sample[1] as f32, sample[2] as f32) / 127.5 - Vec3::splat(1.0);
+    match space {
+        NormalMapSpace::Tangent => {
+            // orthonormal TBN basis around the normal (keeping handedness of the UV mapping)
+            let normal = normal.normalize();
+            let t = tangents[0] - normal * normal.dot(tangents[0]);
+            if t.length_squared() == 0.0 {
+                return normal;
+            }
+            let t = t.normalize();
+            let b = normal.cross(t);
+            let b = if b.dot(tangents[1]) < 0.0 { -b } else { b };
+            t * n.x() + b * n.y() + normal * n.z()
+        }
+        NormalMapSpace::Object => object_to_camera * n,
+    }
+}
+
+// Fragment being lit by Renderer::triangle_texture
+struct Fragment<'a> {
+    // in world space (for shadow map lookups)
+    position: Vec3,
+    // normalized, in camera space
+    normal: Vec3,
+    material: &'a Material,
+    // whether the material has specular highlights at all
+    shiny: bool,
+    pcf_kernel_size: usize,
+}
+
+impl Fragment<'_> {
+    // Diffuse light (the cosine term times intensity) and specular highlight of the fragment from
+    // the light in the (camera space) direction, shadowed by the light's shadow map if it has one
+    #[inline(always)]
+    fn light(
+        &self,
+        direction: Vec3,
+        intensity: f32,
+        shadow_map: Option<&ShadowMap>,
+    ) -> (f32, Vec3) {
+        let cos = self.normal.dot(direction);
+        let lit = match shadow_map {
+            Some(shadow_map) => {
+                let p = shadow_map.to_light(self.position);
+                shadow_map.visibility(p, cos, self.pcf_kernel_size)
+            }
+            None => 1.0,
+        };
+        let glow = if self.shiny {
+            highlight(self.material, self.normal, direction) * (lit * intensity)
+        } else {
+            Vec3::zero()
+        };
+        (max(cos, 0.0) * lit * intensity, glow)
+    }
+}
+
+// Blinn-Phong specular highlight of material at the point with normal (both normal and light
+// direction are normalized, in camera space)
+fn highlight(material: &Material, normal: Vec3, light_direction: Vec3) -> Vec3 {
+    if normal.dot(light_direction) <= 0.0 {
+        return Vec3::zero();
+    }
+
+    // viewer is in the z direction (see Renderer::set_light_direction)
+    let half = (light_direction + vec3(0.0, 0.0, 1.0)).normalize();
+    material.specular * max(normal.dot(half), 0.0).powf(material.shininess)
+}
+
+// 8x8 magenta and black checkerboard of 2x2 squares
+fn missing_texture() -> Texture {
+    Texture::from_fn(8, 8, |x, y| {
+        if (x / 2 + y / 2) % 2 == 0 {
+            Color::from([0xff, 0x00, 0xff])
+        } else {
+            Color::from([0x00, 0x00, 0x00])
+        }
+    })
+}
+
+// Subpixel offset (in [-0.5; 0.5) pixels) for frame number frame from the Halton sequence in
+// bases 2 and 3, see Renderer::set_jitter. Offsets of consecutive frames cover the pixel evenly.
+pub fn halton_jitter(frame: usize) -> Vec2 {
+    let halton = |base: usize| {
+        // 1-based, the first element of the sequence is 0 in every base
+        let (mut i, mut f, mut value) = (frame + 1, 1.0, 0.0);
+        while i > 0 {
+            f /= base as f32;
+            value += f * (i % base) as f32;
+            i /= base;
+        }
+        value
+    };
+    vec2(halton(2) - 0.5, halton(3) - 0.5)
+}
+
+// Deterministic pseudo-random color of triangle (or object id) with given index
+fn triangle_color(index: usize) -> Color {
+    // splitmix64 finalizer
+    let mut h = index as u64 ^ 0x9e37_79b9_7f4a_7c15;
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^= h >> 31;
+
+    // keep colors away from black so triangles don't blend into the background
+    let channel = |shift: u64| 0x40 + ((h >> shift) & 0xff) as u8 % 0xc0;
+    Color::from([channel(0), channel(8), channel(16)])
+}
+
+// Key of the triangle with given screen space vertices deciding which one of coplanar triangles
+// is visible, see Renderer::set_depth_tie_break. It doesn't depend on the order of vertices
+fn tie_key(vertices: [Vec3; 3]) -> u64 {
+    let mut corners = vertices.map(|v| [v.x(), v.y(), v.z()].map(|c| (c + 0.0).to_bits()));
+    corners.sort_unstable();
+    corners
+        .iter()
+        .flatten()
+        .fold(0x9e37_79b9_7f4a_7c15, |h: u64, &c| {
+            // splitmix64 finalizer of every coordinate mixed in
+            let mut h = h ^ c as u64;
+            h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            h ^ (h >> 31)
+        })
+}
+
+// altitudes of a screen space triangle from every vertex, i.e. distances at which its
+// barycentric coordinate is 1
+fn altitudes([a, b, c]: [Vec3; 3]) -> Vec3 {
+    let (a, b, c) = (a.truncate(), b.truncate(), c.truncate());
+    let area = (b - a).perp_dot(c - a).abs();
+    vec3(
+        area / (c - b).length(),
+        area / (a - c).length(),
+        area / (b - a).length(),
+    )
+}
+
+// distances (in pixels) of screen space point p to lines, homogeneous ones (see
+// Renderer::screen_lines)
+fn edge_distances(lines: &[Vec3; 3], p: Vec3) -> Vec3 {
+    let p = p.truncate().extend(1.0);
+    let distance = |line: Vec3| match line.truncate().length() {
+        length if length > 0.0 => line.dot(p).abs() / length,
+        // the edge is seen end on
+        _ => f32::INFINITY,
+    };
+    vec3(distance(lines[0]), distance(lines[1]), distance(lines[2]))
+}
+
+// Where the texture of a Fill::Textured triangle comes from
+#[derive(Clone)]
+enum TextureRef {
+    // the mesh, index into textures of the current draw call
+    Mesh(usize),
+    // the material (`map_Kd`), or the missing texture fallback if it couldn't be read
+    Material(Arc<Texture>),
+}
+
+// How a triangle in screen coordinates is filled
+#[derive(Clone)]
+enum Fill {
+    // lit texture (None for texture-less materials, lit with their color alone)
+    Textured {
+        texture: Option<TextureRef>,
+        // in texture pixels, z is the w coordinate (as is)
+        uv: [Vec3; 3],
+        normals: [Vec3; 3],
+        // ambient occlusion of every vertex
+        ao: Vec3,
+        // directions of growing u and v in camera space, for tangent space normal maps
+        tangents: [Vec3; 2],
+        // world space positions of vertices for shadow map lookups (zero if shadows are off)
+        world: [Vec3; 3],
+    },
+    Flat(Color),
+    // 8-bit (but not rounded) color of every vertex
+    Gradient([Vec3; 3]),
+}
+
+impl Fill {
+    // fill of a part of the triangle with vertices at barycentric coordinates weights (see
+    // guard_band_clip), attributes are interpolated the same way as they would be by the
+    // rasterizer
+    fn lerp(&self, weights: [Vec3; 3]) -> Fill {
+        let lerp = |v: [Vec3; 3]| weights.map(|w| lerp3(v, w));
+        match self {
+            Fill::Textured {
+                texture,
+                uv,
+                normals,
+                ao,
+                tangents,
+                world,
+            } => Fill::Textured {
+                texture: texture.clone(),
+                uv: lerp(*uv),
+                normals: lerp(*normals),
+                ao: vec3(ao.dot(weights[0]), ao.dot(weights[1]), ao.dot(weights[2])),
+                tangents: *tangents,
+                world: lerp(*world),
+            },
+            Fill::Flat(color) => Fill::Flat(*color),
+            Fill::Gradient(colors) => Fill::Gradient(lerp(*colors)),
+        }
+    }
+}
+
+// value at barycentric coordinates w of a triangle with values v at its vertices
+fn lerp3(v: [Vec3; 3], w: Vec3) -> Vec3 {
+    v[0] * w.x() + v[1] * w.y() + v[2] * w.z()
+}
+
+// Triangle in screen coordinates waiting for its pass to be drawn
+#[derive(Clone)]
+struct Deferred {
+    vertices: [Vec3; 3],
+    fill: Fill,
+    // alpha of opaque ones is always 1
+    material: Material,
+    id: u32,
+    // index of the triangle in its model, see PickResult::triangle
+    triangle: u32,
+    // distances (in pixels) of the vertices to the edges of the whole triangle before clipping,
+    // column i is vertex i's to the edges opposite the original vertices (see edge_distances)
+    edges: Mat3,
+    tie_key: u64,
+}
+
+impl Deferred {
+    // sum of vertex depths (screen z is growing towards the viewer)
+    fn depth(&self) -> f32 {
+        self.vertices.iter().map(|v| v.z()).sum()
+    }
+
+    fn texture<'a>(&'a self, textures: &[Option<&'a Texture>]) -> Option<&'a Texture> {
+        match &self.fill {
+            Fill::Textured { texture, .. } => match texture {
+                Some(TextureRef::Mesh(index)) => textures[*index],
+                Some(TextureRef::Material(texture)) => Some(texture),
+                None => None,
+            },
+            Fill::Flat(_) | Fill::Gradient(_) => None,
+        }
+    }
+}
+
+// Layer of transparent fragments being peeled off by Renderer::render_depth_peeled
+#[derive(Clone)]
+struct Peel {
+    // depth of the previous layer, fragments have to be behind it
+    front: Vec<f32>,
+    // depth, color (8-bit and linear), alpha, id and triangle index of the nearest fragment
+    // behind front
+    depth: Vec<f32>,
+    color: Vec<Vec3>,
+    linear: Vec<Vec3>,
+    alpha: Vec<f32>,
+    id: Vec<u32>,
+    triangle: Vec<u32>,
+}
+
+// How frustum culling tests instances, see Renderer::set_frustum_culling and
+// Renderer::set_sphere_culling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Culling {
+    // boxes in a BVH with up to this many instances in its leaves
+    Bvh(usize),
+    Spheres,
+}
+
+// Screen-space reflections, see Renderer::set_ssr
+#[derive(Clone)]
+struct Ssr {
+    steps: usize,
+    // camera space normal and reflectance of the reflective fragment of every pixel, collected
+    // during a render call
+    surfaces: Vec<Option<(Vec3, Vec3)>>,
+}
+
+// Screen-space contact shadows, see Renderer::set_contact_shadows
+#[derive(Clone)]
+struct ContactShadows {
+    length: f32,
+    steps: usize,
+    // fraction of the light of every pixel's fragment that's left in a contact shadow (1 if
+    // there's nothing to shadow), collected during a render call
+    kept: Vec<f32>,
+}
+
+#[derive(Clone)]
+pub struct Renderer {
+    camera: Camera,
+    // model to world transform and the matching transform for normals
+    transform: Mat4,
+    normal_transform: Mat3,
+    front_face: FrontFace,
+    // draw back faces too (with flipped normals) instead of culling them
+    double_sided: bool,
+    shading: ShadingMode,
+    normal_space: NormalSpace,
+    grouping: GroupingMode,
+    group_filter: GroupFilter,
+    // diffuse colors replacing the material ones of shapes in these groups
+    group_colors: HashMap<String, Vec3>,
+    target: Image,
+    zbuffer: Vec<f32>,
+    // linear (not tone mapped, not gamma corrected) color of every pixel in target
+    hdr: Vec<Vec3>,
+    // index of the visible object + 1 for every pixel (0 for background)
+    ids: Vec<u32>,
+    // names of rendered objects, object with id N is objects[N - 1]
+    objects: Vec<String>,
+    // texture coordinates of the visible textured fragment of every pixel, if enabled
+    uvw: Option<Vec<Option<Vec3>>>,
+    // index of the visible triangle (in its model) of every pixel, if enabled
+    triangles: Option<Vec<Option<u32>>>,
+    // tie_key of the visible triangle of every pixel, if depth ties are broken by it
+    tie_keys: Option<Vec<u64>>,
+    light_direction: Vec3,
+    // lights added to the key one (with normalized directions)
+    lights: Vec<Light>,
+    // directional ambient light replacing the constant one (of 0.2) of textured triangles
+    ambient: Option<AmbientSh>,
+    // cubemap sampled by normals for directional ambient light, replacing ambient
+    environment: Option<EnvironmentMap>,
+    // scale of ambient light, constant or directional
+    ambient_strength: f32,
+    // height map and its scale (in model units) along with subdivision level for displacement
+    displacement: Option<(Texture, f32)>,
+    subdivision_level: u32,
+    // objects of the last models drawn displaced with them and the originals, see
+    // displace_models
+    displaced: Vec<Arc<(Object, Object)>>,
+    // materials by name (`usemtl`), geometry without one uses Material::default()
+    materials: Materials,
+    // textures of materials by path and the one used for those that couldn't be read
+    material_textures: HashMap<String, Arc<Texture>>,
+    missing_texture: Arc<Texture>,
+    // the single white pixel texture-less triangles sample, see triangle_texture
+    white_texture: Arc<Texture>,
+    max_texture_pixels: Option<u64>,
+    texture_wrap: TextureWrap,
+    uv_interpretation: UvInterpretation,
+    // whether UVs of the object being drawn are in texture pixels
+    pixel_uvs: bool,
+    material_override: MaterialOverride,
+    // normal map (sampled with the same UV coordinates as texture) and its space
+    normal_map: Option<(Texture, NormalMapSpace)>,
+    // shadow map resolution (None if shadows are off), maps of the current render (of the key
+    // light, then of every one of lights casting shadows, empty if shadows are off) and the size
+    // of PCF kernel
+    shadow_resolution: Option<usize>,
+    shadow_maps: Vec<Option<ShadowMap>>,
+    shadow_mode: ShadowMode,
+    pcf_kernel_size: usize,
+    // per-vertex ambient occlusion by object name, multiplies lighting of textured triangles
+    vertex_ao: VertexAo,
+    // per-vertex colors by object name for ShadingMode::VertexColors
+    vertex_colors: VertexColors,
+    // triangles of the current draw call deferred to opaque and transparent passes
+    sort_opaque: bool,
+    opaque: Vec<Deferred>,
+    transparent: Vec<Deferred>,
+    depth_write: bool,
+    // only fill pixels nothing covers yet, see render_background
+    background: bool,
+    peel: Option<Peel>,
+    ssr: Option<Ssr>,
+    contact_shadows: Option<ContactShadows>,
+    // edge color and line width (in pixels) of wireframe overlay
+    wireframe: Option<(Color, f32)>,
+    // depths (along the view direction) lines start and finish fading to black at
+    line_fade: Option<(f32, f32)>,
+    // size (in pixels) of points, whether it's scaled with their depth and their texture, see
+    // set_point_size
+    point_size: f32,
+    point_attenuation: bool,
+    point_sprite: Option<Texture>,
+    // color grading applied to saved images
+    lut: Option<ColorLut>,
+    bloom: Option<Bloom>,
+    vignette: Option<Vignette>,
+    dof: Option<DepthOfField>,
+    // average luminance saved images are exposed for
+    auto_exposure: Option<f32>,
+    // per-channel gains of saved images
+    tint: Option<Vec3>,
+    // level of detail limits and what they've skipped so far
+    lod: Option<Lod>,
+    lod_stats: LodStats,
+    // how frustum culling (if it's on) tests instances, and what it's culled
+    culling: Option<Culling>,
+    culling_stats: CullingStats,
+    // cover pixels triangles overlap at all instead of just those with the point inside, see
+    // set_conservative
+    conservative: bool,
+    // draw transparent materials opaque with their alpha as dithered coverage, see
+    // set_alpha_to_coverage
+    alpha_to_coverage: bool,
+    // set up vertices of every triangle relative to its centroid in double precision, see
+    // set_high_precision
+    high_precision: bool,
+    // fragments outside of it are discarded
+    scissor: Option<Rect>,
+    // size of tiles render_scene rasterizes one by one (picked for bins_per_thread tiles per
+    // thread if None), see set_tile_size
+    tile_size: Option<usize>,
+    bins_per_thread: usize,
+    // world space planes (a, b, c, d), fragments with a * x + b * y + c * z + d < 0 are
+    // discarded, see add_clip_plane
+    clip_planes: Vec<Vec4>,
+    // subpixel offset of the projection, see set_jitter
+    jitter: Vec2,
+    pass_blend: PassBlend,
+    // id, index, tie_key, alpha and coverage (alpha to coverage) of the triangle being
+    // rasterized, barycentric coordinates of its vertices in the original (unclipped) triangle
+    // and altitudes of that one (for wireframe overlay)
+    id: u32,
+    triangle: u32,
+    tie_key: u64,
+    alpha: f32,
+    coverage: f32,
+    edges: (Mat3, Vec3),
+}
+
+impl Renderer {
+    pub fn new(camera: Camera, (width, height): (usize, usize)) -> Self {
+        Renderer {
+            camera,
+            transform: Mat4::identity(),
+            normal_transform: Mat3::identity(),
+            front_face: FrontFace::Ccw,
+            double_sided: false,
+            shading: ShadingMode::Lit,
+            normal_space: NormalSpace::View,
+            grouping: GroupingMode::Objects,
+            group_filter: GroupFilter::All,
+            group_colors: HashMap::new(),
+            target: Image::new(width as u32, height as u32),
+            zbuffer: vec![f32::NEG_INFINITY; width * height],
+            hdr: vec![Vec3::zero(); width * height],
+            ids: vec![0; width * height],
+            objects: Vec::new(),
+            uvw: None,
+            triangles: None,
+            tie_keys: None,
+            light_direction: vec3(0.0, 0.0, 1.0),
+            lights: Vec::new(),
+            ambient: None,
+            environment: None,
+            ambient_strength: 1.0,
+            displacement: None,
+            subdivision_level: 0,
+            displaced: Vec::new(),
+            materials: Materials::new(),
+            material_textures: HashMap::new(),
+            missing_texture: Arc::new(missing_texture()),
+            white_texture: Arc::new(Texture::from_pixel(1, 1, Color::from([0xff; 3]))),
+            max_texture_pixels: None,
+            texture_wrap: TextureWrap::Clamp,
+            uv_interpretation: UvInterpretation::Auto,
+            pixel_uvs: false,
+            material_override: MaterialOverride::default(),
+            normal_map: None,
+            shadow_resolution: None,
+            shadow_maps: Vec::new(),
+            shadow_mode: ShadowMode::Pcf,
+            pcf_kernel_size: 1,
+            vertex_ao: VertexAo::new(),
+            vertex_colors: VertexColors::new(),
+            sort_opaque: false,
+            opaque: Vec::new(),
+            transparent: Vec::new(),
+            depth_write: true,
+            background: false,
+            peel: None,
+            ssr: None,
+            contact_shadows: None,
+            wireframe: None,
+            line_fade: None,
+            point_size: 1.0,
+            point_attenuation: false,
+            point_sprite: None,
+            lut: None,
+            bloom: None,
+            vignette: None,
+            dof: None,
+            auto_exposure: None,
+            tint: None,
+            lod: None,
+            lod_stats: LodStats::default(),
+            culling: None,
+            culling_stats: CullingStats::default(),
+            conservative: false,
+            alpha_to_coverage: false,
+            high_precision: false,
+            scissor: None,
+            tile_size: None,
+            bins_per_thread: 4,
+            clip_planes: Vec::new(),
+            jitter: Vec2::zero(),
+            pass_blend: PassBlend::Depth,
+            id: 0,
+            triangle: 0,
+            tie_key: 0,
+            alpha: 1.0,
+            coverage: 1.0,
+            edges: (Mat3::identity(), Vec3::zero()),
+        }
+    }
+
+    // camera used for the following draw calls (e.g. to dolly/pan/zoom between frames)
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    // reset all buffers to the initial (empty) state
+    pub fn clear_all(&mut self) {
+        for pixel in self.target.pixels_mut() {
+            *pixel = Color::from([0, 0, 0]);
+        }
+        self.zbuffer.iter_mut().for_each(|z| *z = f32::NEG_INFINITY);
+        self.hdr.iter_mut().for_each(|c| *c = Vec3::zero());
+        self.ids.iter_mut().for_each(|id| *id = 0);
+        if let Some(uvw) = &mut self.uvw {
+            uvw.iter_mut().for_each(|uvw| *uvw = None);
+        }
+        if let Some(triangles) = &mut self.triangles {
+            triangles.iter_mut().for_each(|triangle| *triangle = None);
+        }
+        if let Some(keys) = &mut self.tie_keys {
+            keys.iter_mut().for_each(|key| *key = 0);
+        }
+        if let Some(ssr) = &mut self.ssr {
+            ssr.surfaces.iter_mut().for_each(|surface| *surface = None);
+        }
+        if let Some(contact) = &mut self.contact_shadows {
+            contact.kept.iter_mut().for_each(|kept| *kept = 1.0);
+        }
+        self.objects.clear();
+        self.lod_stats = LodStats::default();
+        self.culling_stats = CullingStats::default();
+    }
+
+    // reallocate all buffers for a different output size, cleared like after clear_all(); the
+    // rest of the setup (camera, materials, maps, effects) is kept, so the same scene renders
+    // with the same framing at every size
+    pub fn resize(&mut self, (width, height): (usize, usize)) {
+        let size = width * height;
+        self.target = Image::new(width as u32, height as u32);
+        self.zbuffer = vec![f32::NEG_INFINITY; size];
+        self.hdr = vec![Vec3::zero(); size];
+        self.ids = vec![0; size];
+        if let Some(uvw) = &mut self.uvw {
+            *uvw = vec![None; size];
+        }
+        if let Some(triangles) = &mut self.triangles {
+            *triangles = vec![None; size];
+        }
+        if let Some(keys) = &mut self.tie_keys {
+            *keys = vec![0; size];
+        }
+        if let Some(ssr) = &mut self.ssr {
+            ssr.surfaces = vec![None; size];
+        }
+        if let Some(contact) = &mut self.contact_shadows {
+            contact.kept = vec![1.0; size];
+        }
+        self.clear_all();
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.target.width() as usize, self.target.height() as usize)
+    }
+
+    // pixels triangles may cover: the whole target clipped by the scissor
+    fn raster_bounds(&self) -> Rect {
+        let (width, height) = self.size();
+        let target = Rect {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        };
+        match &self.scissor {
+            Some(scissor) => target.intersect(scissor),
+            None => target,
+        }
+    }
+
+    // transform applied to models before they are rendered
+    pub fn set_model_transform(&mut self, transform: Mat4) {
+        let linear = Mat3::from_cols(
+            transform.x_axis().truncate(),
+            transform.y_axis().truncate(),
+            transform.z_axis().truncate(),
+        );
+
+        self.transform = transform;
+        self.normal_transform = linear.inverse().transpose();
+    }
+
+    // direction towards the light in camera space, (0, 0, 1) is straight from the viewer
+    pub fn set_light_direction(&mut self, direction: Vec3) {
+        self.light_direction = direction.normalize();
+    }
+
+    // Light triangles by one more directional light, its diffuse and specular light is added to
+    // that of the key light (see set_light_direction) and of the previous ones.
+    // NOTE: ShadingMode::VertexColors is lit by the key light only
+    pub fn add_light(&mut self, light: Light) {
+        self.lights.push(Light {
+            direction: light.direction.normalize(),
+            ..light
+        });
+    }
+
+    // remove lights added by add_light, the key light stays
+    pub fn clear_lights(&mut self) {
+        self.lights.clear();
+    }
+
+    // Light triangles by ambient light from sh (in world space) where it's brighter than
+    // the direct light, instead of by constant 0.2
+    pub fn set_ambient_sh(&mut self, sh: AmbientSh) {
+        self.ambient = Some(sh);
+    }
+
+    pub fn clear_ambient_sh(&mut self) {
+        self.ambient = None;
+    }
+
+    // Light triangles by ambient light from the environment map in the direction of the normal
+    // (like set_ambient_sh, which it takes precedence over)
+    pub fn set_ambient_environment(&mut self, environment: EnvironmentMap) {
+        self.environment = Some(environment);
+    }
+
+    pub fn clear_ambient_environment(&mut self) {
+        self.environment = None;
+    }
+
+    // scale ambient light, the constant 0.2 as well as that of set_ambient_sh and
+    // set_ambient_environment
+    pub fn set_ambient_strength(&mut self, strength: f32) {
+        self.ambient_strength = strength;
+    }
+
+    // displace geometry along vertex normals by height map (average of the channels, 0..1)
+    // sampled at vertex UVs times scale. Triangles are subdivided first, see set_subdivision_level
+    pub fn set_displacement_map(&mut self, map: Texture, scale: f32) {
+        self.displacement = Some((map, scale));
+        self.displaced.clear();
+    }
+
+    pub fn clear_displacement_map(&mut self) {
+        self.displacement = None;
+        self.displaced.clear();
+    }
+
+    // every displaced triangle is split into 4^level smaller ones
+    pub fn set_subdivision_level(&mut self, level: u32) {
+        self.subdivision_level = level;
+        self.displaced.clear();
+    }
+
+    // Materials by name, their textures are read right away. Ones that can't be read (or have
+    // more pixels than set_max_texture_pixels allows) are replaced by the missing texture
+    // fallback instead of failing the render, a warning for every one of them is returned.
+    pub fn set_materials(&mut self, materials: Materials) -> Vec<MaterialWarning> {
+        self.material_textures.clear();
+        let mut warnings = Vec::new();
+        for (name, material) in &materials {
+            let path = match &material.texture {
+                Some(path) if !self.material_textures.contains_key(path) => path,
+                _ => continue,
+            };
+            let (material, path) = (name.clone(), path.clone());
+            match read_texture_limited(&path, self.max_texture_pixels) {
+                Ok(texture) => {
+                    self.material_textures.insert(path, Arc::new(texture));
+                }
+                Err(e) => warnings.push(match e.downcast::<TextureTooLarge>() {
+                    Ok(size) => MaterialWarning::TooLarge {
+                        material,
+                        path,
+                        size,
+                    },
+                    Err(e) => MaterialWarning::Unreadable {
+                        material,
+                        path,
+                        message: e.to_string(),
+                    },
+                }),
+            }
+        }
+        self.materials = materials;
+        warnings
+    }
+
+    // material textures with more pixels are replaced by the missing texture fallback by the
+    // following set_materials calls (with a MaterialWarning::TooLarge)
+    pub fn set_max_texture_pixels(&mut self, max: Option<u64>) {
+        self.max_texture_pixels = max;
+    }
+
+    // used for material textures that couldn't be read, a magenta checkerboard by default (a
+    // single pixel texture makes it a solid color)
+    pub fn set_missing_texture_fallback(&mut self, texture: Texture) {
+        self.missing_texture = Arc::new(texture);
+    }
+
+    // how textures (not normal maps) are sampled outside of UVs in [0; 1]
+    pub fn set_texture_wrap(&mut self, wrap: TextureWrap) {
+        self.texture_wrap = wrap;
+    }
+
+    // whether UVs are in [0; 1] or texture pixels, looking at them by default
+    pub fn set_uv_interpretation(&mut self, interpretation: UvInterpretation) {
+        self.uv_interpretation = interpretation;
+    }
+
+    // replace properties of every material (e.g. for quick experiments without editing MTL)
+    pub fn set_material_override(&mut self, material_override: MaterialOverride) {
+        self.material_override = material_override;
+    }
+
+    // perturb normals of textured triangles with normal map
+    pub fn set_normal_map(&mut self, map: Texture, space: NormalMapSpace) {
+        self.normal_map = Some((map, space));
+    }
+
+    pub fn clear_normal_map(&mut self) {
+        self.normal_map = None;
+    }
+
+    // Cast shadows from the key light (see set_light_direction) and from added lights casting
+    // them onto lit triangles. Shadow maps of resolution x resolution texels (one per light) are
+    // rendered from the whole scene before every render, a fragment is shadowed from every light
+    // on its own.
+    // NOTE: displacement is not applied to shadow casters
+    pub fn set_shadows(&mut self, resolution: usize) {
+        self.shadow_resolution = Some(resolution);
+    }
+
+    pub fn clear_shadows(&mut self) {
+        self.shadow_resolution = None;
+    }
+
+    // Soften shadow edges with percentage-closer filtering: fraction of size x size shadow map
+    // texels around the fragment it's lit in (even sizes are rounded up), 1 gives hard shadows
+    pub fn set_pcf_kernel_size(&mut self, size: usize) {
+        self.pcf_kernel_size = size;
+    }
+
+    // filter shadows by PCF (the default) or variance shadow maps, see ShadowMode
+    pub fn set_shadow_mode(&mut self, mode: ShadowMode) {
+        self.shadow_mode = mode;
+    }
+
+    // Darken lighting by baked per-vertex AO (see read_vertex_ao) of objects with matching names.
+    // NOTE: it's ignored for displaced objects, their vertices don't match the original ones
+    pub fn set_vertex_ao(&mut self, ao: VertexAo) {
+        self.vertex_ao = ao;
+    }
+
+    // Colors of objects with matching names for ShadingMode::VertexColors, see read_vertex_colors.
+    // NOTE: like AO, they are ignored for displaced objects
+    pub fn set_vertex_colors(&mut self, colors: VertexColors) {
+        self.vertex_colors = colors;
+    }
+
+    // draw opaque triangles sorted front to back (after all of them are set up), which saves
+    // shading of fragments hidden by nearer geometry at the cost of memory for the sort
+    pub fn set_sort_opaque(&mut self, enabled: bool) {
+        self.sort_opaque = enabled;
+    }
+
+    // Draw triangle edges over shaded fragments within width / 2 pixels from them. It's done when
+    // shading fragments, so edges exactly match the geometry and are hidden with it.
+    pub fn set_wireframe_overlay(&mut self, color: Color, width: f32) {
+        self.wireframe = Some((color, width));
+    }
+
+    pub fn clear_wireframe_overlay(&mut self) {
+        self.wireframe = None;
+    }
+
+    // Fade lines (line, draw_wireframe, ...) with depth along the view direction (in world
+    // units): full color up to near, linearly darker beyond it and black at far
+    pub fn set_line_depth_fade(&mut self, near: f32, far: f32) {
+        self.line_fade = Some((near, far));
+    }
+
+    pub fn clear_line_depth_fade(&mut self) {
+        self.line_fade = None;
+    }
+
+    // Draw points of models (`p` elements of OBJ files) as squares of size pixels facing the
+    // camera, in the material color (or their triangle color, see ShadingMode::TriangleColors)
+    // and not lit. Points are drawn with depth tests like triangles.
+    pub fn set_point_size(&mut self, size: f32) {
+        self.point_size = size.max(0.0);
+    }
+
+    // Scale the size of points by the distance from the viewer to lookat over their depth, so
+    // they have the size set by set_point_size at the depth of lookat, and get smaller farther
+    pub fn set_point_attenuation(&mut self, enabled: bool) {
+        self.point_attenuation = enabled;
+    }
+
+    // Texture stretched over every point (e.g. a white disc on black for round points, or one
+    // fading out for soft ones) modulating its color, fragments of black texels are discarded
+    pub fn set_point_sprite(&mut self, sprite: Texture) {
+        self.point_sprite = Some(sprite);
+    }
+
+    pub fn clear_point_sprite(&mut self) {
+        self.point_sprite = None;
+    }
+
+    // Grade saved images with 3D LUT from .cube file. It's the last post-process step, applied
+    // to target as it's written out (the linear color buffer stays ungraded).
+    pub fn set_color_lut(&mut self, path: &str) -> Result<()> {
+        self.lut = Some(ColorLut::read(path)?);
+        Ok(())
+    }
+
+    pub fn clear_color_lut(&mut self) {
+        self.lut = None;
+    }
+
+    // Expose saved images so the log-average luminance of linear colors of covered pixels
+    // becomes target (in [0; 1], e.g. 0.18 for middle gray, which is about 0.42 in the gamma
+    // corrected image), brightening dim scenes and darkening bright ones. The darkest and the
+    // brightest pixels (see luminance_histogram) are left out of the average, so small
+    // highlights or deep shadows don't swing the exposure. It's the first post effect, so what
+    // was clipped at white stays flat.
+    pub fn set_auto_exposure(&mut self, target: f32) {
+        self.auto_exposure = Some(target);
+    }
+
+    pub fn clear_auto_exposure(&mut self) {
+        self.auto_exposure = None;
+    }
+
+    // target luminance of auto exposure, None if it's off
+    pub fn auto_exposure(&self) -> Option<f32> {
+        self.auto_exposure
+    }
+
+    // factor auto exposure scales linear colors by, None if it's off or nothing was rendered
+    pub fn exposure(&self) -> Option<f32> {
+        let target = self.auto_exposure?;
+        let histogram = self.luminance_histogram(EXPOSURE_BINS);
+        let count: usize = histogram.iter().sum();
+        if count == 0 {
+            return None;
+        }
+
+        // bins the darkest 10% and the brightest 5% of pixels fall into are left out, unless the
+        // rest shares them
+        let mut total = 0;
+        let cumulative: Vec<usize> = histogram
+            .iter()
+            .map(|&n| {
+                total += n;
+                total
+            })
+            .collect();
+        let first = cumulative.iter().position(|&n| n * 10 > count).unwrap();
+        let last = cumulative
+            .iter()
+            .position(|&n| n * 20 >= count * 19)
+            .unwrap();
+
+        // offset, so black pixels don't make the average 0
+        let log_luminance = |c: Vec3| ((luminance(c).max(0.0) + 1e-4) as f64).ln();
+        let covered = self.zbuffer.iter().map(|&z| z != f32::NEG_INFINITY);
+        let (sum, count) = self
+            .hdr
+            .iter()
+            .zip(covered)
+            .filter(|&(&c, covered)| {
+                covered && (first..=last).contains(&luminance_bin(c, EXPOSURE_BINS))
+            })
+            .fold((0.0, 0), |(sum, count), (&c, _)| {
+                (sum + log_luminance(c), count + 1)
+            });
+        Some(target / (sum / count as f64).exp() as f32)
+    }
+
+    // blur saved images away from the focus plane at focus_distance (in world units from the
+    // viewer), the blur of the background is aperture pixels, see DepthOfField. It's applied
+    // after auto exposure.
+    pub fn set_dof(&mut self, focus_distance: f32, aperture: f32) {
+        self.dof = Some(DepthOfField {
+            focus_distance,
+            aperture,
+        });
+    }
+
+    pub fn clear_dof(&mut self) {
+        self.dof = None;
+    }
+
+    // add glow around highlights of saved images (before vignette and color grading)
+    pub fn set_bloom(&mut self, bloom: Bloom) {
+        self.bloom = Some(bloom);
+    }
+
+    pub fn clear_bloom(&mut self) {
+        self.bloom = None;
+    }
+
+    // darken corners of saved images (before color grading)
+    pub fn set_vignette(&mut self, vignette: Vignette) {
+        self.vignette = Some(vignette);
+    }
+
+    pub fn clear_vignette(&mut self) {
+        self.vignette = None;
+    }
+
+    // multiply channels of saved images by gains, e.g. (1.1, 1.0, 0.9) to warm them up (after
+    // vignette, before color grading)
+    pub fn set_tint(&mut self, gains: Vec3) {
+        self.tint = Some(gains);
+    }
+
+    pub fn clear_tint(&mut self) {
+        self.tint = None;
+    }
+
+    // skip tiny triangles and distant instances, see Lod
+    pub fn set_lod(&mut self, lod: Lod) {
+        self.lod = Some(lod);
+    }
+
+    pub fn clear_lod(&mut self) {
+        self.lod = None;
+    }
+
+    // triangles and instances skipped by the LOD limits since the last clear
+    pub fn lod_stats(&self) -> LodStats {
+        self.lod_stats
+    }
+
+    // Skip scene instances whose bounds are entirely out of view. The bounds are put into a BVH
+    // (with up to leaf_size instances in its leaves) every render_scene, so only the nodes of
+    // visible parts of the scene are tested. Shadows are still cast by everything.
+    pub fn set_frustum_culling(&mut self, leaf_size: usize) {
+        self.culling = Some(Culling::Bvh(leaf_size));
+    }
+
+    // Frustum culling testing the bounding sphere of every instance, without a BVH: cheaper to
+    // set up (and test for few instances), but spheres are looser than boxes. Stats count tested
+    // spheres as nodes.
+    pub fn set_sphere_culling(&mut self) {
+        self.culling = Some(Culling::Spheres);
+    }
+
+    pub fn clear_frustum_culling(&mut self) {
+        self.culling = None;
+    }
+
+    // what frustum culling did since the last clear
+    pub fn culling_stats(&self) -> CullingStats {
+        self.culling_stats
+    }
+
+    // Discard fragments (of triangles and lines) outside of the rectangle, in framebuffer
+    // coordinates (before flipv), without changing how coordinates are mapped to the screen
+    pub fn set_scissor(&mut self, scissor: Option<Rect>) {
+        self.scissor = scissor;
+    }
+
+    // Rasterize render_scene calls in tile_size x tile_size tiles (within the scissor), with the
+    // rayon feature every thread draws its share of the tiles into a copy of the buffers. Every
+    // tile sets up every triangle, so smaller tiles cost more: on a single thread the head at
+    // 1024x1024 takes about 1.2 times as long with 512 pixel tiles, 1.4 with 256, 2.3 with 128
+    // and 6 with 64 ones (see `tinyrenderer bench --tile-size`), tiles of 256 pixels and more
+    // are the sweet spot. None (the default) tiles for bins_per_thread tiles per thread, i.e.
+    // 256 pixel ones at this size with 4 threads, and doesn't tile with a single thread (or
+    // without rayon). Images don't depend on the tile size.
+    pub fn set_tile_size(&mut self, tile_size: Option<usize>) {
+        self.tile_size = tile_size;
+    }
+
+    // More tiles than threads balance the load when parts of the image take longer than others
+    // (e.g. where the model is), see set_tile_size
+    pub fn set_bins_per_thread(&mut self, bins: usize) {
+        self.bins_per_thread = bins.max(1);
+    }
+
+    // Discard fragments on the negative side of the world space plane a * x + b * y + c * z + d
+    // = 0, (a, b, c, d) = plane, and those of every plane added before, e.g. for cross-sections
+    // of models. What's cut off is gone from the image and the depth buffer.
+    // NOTE: it still casts shadows
+    pub fn add_clip_plane(&mut self, plane: Vec4) {
+        self.clip_planes.push(plane);
+    }
+
+    // remove planes added by add_clip_plane
+    pub fn clear_clip_planes(&mut self) {
+        self.clip_planes.clear();
+    }
+
+    // Shift the projection by offset (in pixels, right and up), e.g. by halton_jitter(frame) for
+    // every frame of a temporal accumulation, so edges are covered differently every frame and
+    // the average of the frames is antialiased
+    pub fn set_jitter(&mut self, offset: Vec2) {
+        self.jitter = offset;
+    }
+
+    // Conservative rasterization: triangles cover every pixel they overlap at all, not just those
+    // whose center they contain, e.g. for voxelization. Pixels with the center outside of the
+    // triangle are shaded with its barycentric coordinates clamped to the triangle.
+    pub fn set_conservative(&mut self, conservative: bool) {
+        self.conservative = conservative;
+    }
+
+    // Alpha to coverage: transparent materials are drawn with the opaque geometry, covering
+    // the fraction alpha of pixels in an ordered dither pattern (and writing depth there)
+    // instead of being blended. With supersampling (see resolve) the pattern averages out to
+    // partial coverage of the output pixels, like a mask of MSAA subsamples.
+    pub fn set_alpha_to_coverage(&mut self, alpha_to_coverage: bool) {
+        self.alpha_to_coverage = alpha_to_coverage;
+    }
+
+    // Set up triangles of models with huge coordinates (e.g. CAD ones far from the origin)
+    // precisely: vertices are taken relative to the centroid of their triangle and the camera
+    // in double precision, so they keep their fractional parts instead of being rounded to single
+    // precision first. Vertices on edges of other triangles stay on them, so there are no gaps or
+    // overlaps between them.
+    pub fn set_high_precision(&mut self, enabled: bool) {
+        self.high_precision = enabled;
+    }
+
+    // how the following render calls are combined with what's in the buffers, e.g. to build up
+    // a composite from passes with different settings
+    pub fn set_pass_blend(&mut self, blend: PassBlend) {
+        self.pass_blend = blend;
+    }
+
+    // Start from canvas (in framebuffer coordinates, i.e. before flipv) instead of the empty
+    // image, e.g. to paint over a previous render. It counts as background for depth tests.
+    pub fn set_canvas(&mut self, canvas: &Image) -> Result<()> {
+        if canvas.dimensions() != self.target.dimensions() {
+            bail!(
+                "Canvas is {}x{}, expected {}x{}",
+                canvas.width(),
+                canvas.height(),
+                self.target.width(),
+                self.target.height()
+            );
+        }
+
+        self.clear_all();
+        self.target = canvas.clone();
+        for (hdr, &pixel) in self.hdr.iter_mut().zip(self.target.pixels()) {
+            *hdr = to_linear(pixel);
+        }
+        Ok(())
+    }
+
+    pub fn set_front_face(&mut self, front_face: FrontFace) {
+        self.front_face = front_face;
+    }
+
+    // Draw back faces instead of culling them, lit from the other side and with the back
+    // material of their material (see Material::back) if it has one, e.g. for leaves
+    pub fn set_double_sided(&mut self, double_sided: bool) {
+        self.double_sided = double_sided;
+    }
+
+    pub fn set_shading_mode(&mut self, shading: ShadingMode) {
+        self.shading = shading;
+    }
+
+    // space of normals drawn with ShadingMode::Normals, view space by default
+    pub fn set_normal_space(&mut self, space: NormalSpace) {
+        self.normal_space = space;
+    }
+
+    pub fn set_grouping_mode(&mut self, grouping: GroupingMode) {
+        self.grouping = grouping;
+    }
+
+    // draw only some `g` groups, e.g. GroupFilter::Only(vec!["wheels".to_owned()])
+    pub fn set_group_filter(&mut self, filter: GroupFilter) {
+        self.group_filter = filter;
+    }
+
+    // Recolor shapes of group: color replaces the diffuse color of their material (and
+    // MaterialOverride). Shapes in several groups take the color of the first one with it.
+    pub fn set_group_color(&mut self, group: &str, color: Vec3) {
+        self.group_colors.insert(group.to_owned(), color);
+    }
+
+    pub fn clear_group_colors(&mut self) {
+        self.group_colors.clear();
+    }
+
+    // shorthand for switching between ShadingMode::TriangleColors and ShadingMode::Lit
+    pub fn set_triangle_id_colors(&mut self, enabled: bool) {
+        self.shading = if enabled {
+            ShadingMode::TriangleColors
+        } else {
+            ShadingMode::Lit
+        };
+    }
+
+    pub fn flipv(&mut self) {
+        image::imageops::flip_vertical_in_place(&mut self.target);
+
+        // keep per-pixel buffers in sync with target
+        let (width, height) = self.size();
+        fn flip<T>(buffer: &mut [T], width: usize, height: usize) {
+            for y in 0..height / 2 {
+                for x in 0..width {
+                    buffer.swap(x + y * width, x + (height - 1 - y) * width);
+                }
+            }
+        }
+        flip(&mut self.zbuffer, width, height);
+        flip(&mut self.hdr, width, height);
+        flip(&mut self.ids, width, height);
+        if let Some(uvw) = &mut self.uvw {
+            flip(uvw, width, height);
+        }
+        if let Some(triangles) = &mut self.triangles {
+            flip(triangles, width, height);
+        }
+        if let Some(keys) = &mut self.tie_keys {
+            flip(keys, width, height);
+        }
+        if let Some(ssr) = &mut self.ssr {
+            flip(&mut ssr.surfaces, width, height);
+        }
+        if let Some(contact) = &mut self.contact_shadows {
+            flip(&mut contact.kept, width, height);
+        }
+    }
+
+    // Post-process the rendered image: f is called with coordinates, color and depth (larger is
+    // nearer, -inf for the background) of every pixel, linear colors of changed pixels follow
+    pub fn for_each_pixel<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u32, u32, &mut Color, f32),
+    {
+        let width = self.target.width() as usize;
+        for (x, y, pixel) in self.target.enumerate_pixels_mut() {
+            let position = x as usize + y as usize * width;
+            let color = *pixel;
+            f(x, y, pixel, self.zbuffer[position]);
+            if *pixel != color {
+                self.hdr[position] = to_linear(*pixel);
+            }
+        }
+    }
+
+    // the rendered image (without color grading)
+    pub fn image(&self) -> &Image {
+        &self.target
+    }
+
+    // keep texture coordinates of the visible fragment of every pixel (from now on), see uvw
+    pub fn set_uvw_buffer(&mut self, enabled: bool) {
+        self.uvw = if enabled {
+            Some(vec![None; self.zbuffer.len()])
+        } else {
+            None
+        };
+    }
+
+    // Interpolated (u, v, w) texture coordinates of the textured fragment visible at (x, y), e.g.
+    // for 3D texture lookups. None for other pixels or if the buffer is off.
+    // NOTE: fragments resolved by render_depth_peeled aren't recorded
+    pub fn uvw(&self, x: u32, y: u32) -> Option<Vec3> {
+        let uvw = self.uvw.as_ref()?;
+        let position = x as usize + y as usize * self.target.width() as usize;
+        uvw.get(position).copied().flatten()
+    }
+
+    // Screen-space contact shadows: a ray from every lit fragment towards the key light is
+    // marched through the depth buffer for length (in world units) in steps samples, and the
+    // fragment loses the diffuse light of the key light if the ray passes behind something
+    // closer than length to it. This darkens small-scale contacts (e.g. where the nose meets the
+    // cheek) too fine for shadow maps. Only what's on the screen casts contact shadows.
+    // NOTE: applied at the end of every render call, to what it rendered
+    pub fn set_contact_shadows(&mut self, length: f32, steps: usize) {
+        self.contact_shadows = Some(ContactShadows {
+            length,
+            steps,
+            kept: vec![1.0; self.zbuffer.len()],
+        });
+    }
+
+    pub fn clear_contact_shadows(&mut self) {
+        self.contact_shadows = None;
+    }
+
+    // Screen-space reflections on shiny materials (with specular color), e.g. glossy floors:
+    // the view ray mirrored at every fragment is marched through the depth buffer in steps
+    // samples and what it hits is added, blurred a bit and times the specular color. Only
+    // what's on the screen can be reflected.
+    // NOTE: applied at the end of every render call, to what it rendered
+    pub fn set_ssr(&mut self, enabled: bool, steps: usize) {
+        self.ssr = if enabled {
+            Some(Ssr {
+                steps,
+                surfaces: vec![None; self.zbuffer.len()],
+            })
+        } else {
+            None
+        };
+    }
+
+    // keep the index of the visible triangle of every pixel (from now on), see pick
+    pub fn set_triangle_id_buffer(&mut self, enabled: bool) {
+        self.triangles = if enabled {
+            Some(vec![None; self.zbuffer.len()])
+        } else {
+            None
+        };
+    }
+
+    // Fragments at exactly the same depth (of coplanar triangles) normally go to the one drawn
+    // last. With the tie break on, the winner is decided by a hash of the triangles' screen
+    // space vertices instead, so the image doesn't depend on the order triangles are drawn in.
+    // NOTE: identical triangles still tie, the last one drawn wins
+    pub fn set_depth_tie_break(&mut self, enabled: bool) {
+        self.tie_keys = if enabled {
+            Some(vec![0; self.zbuffer.len()])
+        } else {
+            None
+        };
+    }
+
+    // What is visible at pixel (x, y), e.g. to select objects with the mouse. None for the
+    // background or if the triangle id buffer is off.
+    pub fn pick(&self, x: u32, y: u32) -> Option<PickResult> {
+        let triangles = self.triangles.as_ref()?;
+        let position = x as usize + y as usize * self.target.width() as usize;
+        let triangle = triangles.get(position).copied().flatten()?;
+        Some(PickResult {
+            object: self.objects[self.ids[position] as usize - 1].clone(),
+            triangle: triangle as usize,
+            depth: self.zbuffer[position],
+        })
+    }
+
+    // target with post effects and color grading applied
+    fn graded(&self) -> Cow<'_, Image> {
+        if self.lut.is_none()
+            && self.auto_exposure.is_none()
+            && self.vignette.is_none()
+            && self.dof.is_none()
+            && self.bloom.is_none()
+            && self.tint.is_none()
+        {
+            return Cow::Borrowed(&self.target);
+        }
+
+        let mut graded = self.target.clone();
+        if let Some(exposure) = self.exposure() {
+            // lighting is gamma corrected (and the background is left as is)
+            let gain = exposure.sqrt();
+            for (i, pixel) in graded.pixels_mut().enumerate() {
+                if self.zbuffer[i] != f32::NEG_INFINITY {
+                    // NOTE: float to int casts saturate
+                    let expose = |c: u8| (c as f32 * gain).round() as u8;
+                    let [r, g, b] = pixel.0;
+                    *pixel = Color::from([expose(r), expose(g), expose(b)]);
+                }
+            }
+        }
+        if let Some(dof) = &self.dof {
+            dof.apply(&mut graded, &self.linear_depth());
+        }
+        if let Some(bloom) = &self.bloom {
+            bloom.apply(&mut graded, &self.hdr);
+        }
+        if let Some(vignette) = &self.vignette {
+            vignette.apply(&mut graded);
+        }
+        if let Some(gains) = self.tint {
+            // NOTE: float to int casts saturate
+            let tint = |c: u8, gain: f32| (c as f32 * gain).round() as u8;
+            for pixel in graded.pixels_mut() {
+                let [r, g, b] = pixel.0;
+                *pixel = Color::from([tint(r, gains.x()), tint(g, gains.y()), tint(b, gains.z())]);
+            }
+        }
+        if let Some(lut) = &self.lut {
+            for pixel in graded.pixels_mut() {
+                *pixel = lut.apply_color(*pixel);
+            }
+        }
+        Cow::Owned(graded)
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        self.graded().save(path)?;
+        Ok(())
+    }
+
+    // Pixels of the final image (what save writes) in format, row by row from the top
+    pub fn export_as(&self, format: PixelFormat) -> Vec<u8> {
+        let image = self.graded();
+        let mut pixels = Vec::with_capacity(image.pixels().len() * format.bytes_per_pixel());
+        for &image::Rgb([r, g, b]) in image.pixels() {
+            match format {
+                PixelFormat::Rgb => pixels.extend_from_slice(&[r, g, b]),
+                PixelFormat::Rgba => pixels.extend_from_slice(&[r, g, b, 0xff]),
+                PixelFormat::Bgra => pixels.extend_from_slice(&[b, g, r, 0xff]),
+            }
+        }
+        pixels
+    }
+
+    // Final image (what save writes) downsampled factor times with filter, for rendering at a
+    // multiple of the output size (supersampling). Its size has to be a multiple of factor.
+    pub fn resolve(&self, factor: u32, filter: ResolveFilter) -> Result<Image> {
+        let (width, height) = self.target.dimensions();
+        if factor == 0 || !width.is_multiple_of(factor) || !height.is_multiple_of(factor) {
+            bail!(
+                "Can't resolve {}x{} framebuffer {} times",
+                width,
+                height,
+                factor
+            );
+        }
+        Ok(filter.resolve(self.graded().as_ref(), factor))
+    }
+
+    // save only the (x, y, width, height) sub-rectangle of the framebuffer
+    pub fn save_crop(&self, path: &str, x: u32, y: u32, width: u32, height: u32) -> Result<()> {
+        let fits = |start: u32, len: u32, max: u32| {
+            len != 0 && start.checked_add(len).is_some_and(|end| end <= max)
+        };
+
+        if !fits(x, width, self.target.width()) || !fits(y, height, self.target.height()) {
+            bail!(
+                "Crop {}x{}+{}+{} is out of {}x{} framebuffer bounds",
+                width,
+                height,
+                x,
+                y,
+                self.target.width(),
+                self.target.height()
+            );
+        }
+
+        image::imageops::crop_imm(self.graded().as_ref(), x, y, width, height)
+            .to_image()
+            .save(path)?;
+        Ok(())
+    }
+
+    // Save the object (or group, see set_grouping_mode) id buffer with a distinct color for
+    // every object and black background, e.g. to check segmentation
+    pub fn save_ids(&self, path: &str) -> Result<()> {
+        let width = self.target.width();
+        let ids = Image::from_fn(width, self.target.height(), |x, y| {
+            match self.ids[(x + y * width) as usize] {
+                0 => Color::from([0, 0, 0]),
+                id => triangle_color(id as usize),
+            }
+        });
+        ids.save(path)?;
+        Ok(())
+    }
+
+    // save the linear color buffer as a 32-bit float OpenEXR image, with values above 1 (e.g.
+    // where lights add up) kept as they are
+    #[cfg(feature = "exr")]
+    pub fn save_hdr(&self, path: &str) -> Result<()> {
+        let width = self.target.width() as usize;
+        let height = self.target.height() as usize;
+        exr::prelude::write_rgb_file(path, width, height, |x, y| {
+            let color = self.hdr[x + y * width];
+            (color.x(), color.y(), color.z())
+        })?;
+        Ok(())
+    }
+
+    // number of pixels still at the clear value, i.e. without any fragment
+    pub fn uncovered_pixels(&self) -> usize {
+        self.zbuffer
+            .iter()
+            .filter(|&&z| z == f32::NEG_INFINITY)
+            .count()
+    }
+
+    // fraction of pixels covered by fragments, 1 once nothing else can be seen behind them
+    pub fn coverage(&self) -> f32 {
+        1.0 - self.uncovered_pixels() as f32 / self.zbuffer.len() as f32
+    }
+
+    // Number of covered pixels in each of bins equal parts of the range of their z-buffer values,
+    // from the farthest to the nearest one, e.g. to see how much of the depth precision is used.
+    // Everything is in the first bin if all the fragments are at the same depth (up to rounding).
+    pub fn depth_histogram(&self, bins: usize) -> Vec<usize> {
+        let mut histogram = vec![0; bins];
+        let covered = || self.zbuffer.iter().filter(|z| z.is_finite());
+        let min = covered().fold(f32::INFINITY, |min, &z| min.min(z));
+        let max = covered().fold(f32::NEG_INFINITY, |max, &z| max.max(z));
+        if bins == 0 || min > max {
+            return histogram;
+        }
+
+        // values interpolated across a plane at constant depth still differ in the last bits
+        let rounding = 16.0 * f32::EPSILON * min.abs().max(max.abs()).max(1.0);
+        let scale = if max - min > rounding {
+            bins as f32 / (max - min)
+        } else {
+            0.0
+        };
+        for &z in covered() {
+            histogram[(((z - min) * scale) as usize).min(bins - 1)] += 1;
+        }
+        histogram
+    }
+
+    // Number of covered pixels in each of bins equal parts of the range of log2 luminance of
+    // their linear colors (LUMINANCE_STOPS), from the darkest to the brightest one, e.g. to see
+    // how a scene is exposed. Pixels beyond the range are counted in the bins at its ends.
+    pub fn luminance_histogram(&self, bins: usize) -> Vec<usize> {
+        let mut histogram = vec![0; bins];
+        if bins == 0 {
+            return histogram;
+        }
+        let covered = self.zbuffer.iter().map(|&z| z != f32::NEG_INFINITY);
+        for (&c, covered) in self.hdr.iter().zip(covered) {
+            if covered {
+                histogram[luminance_bin(c, bins)] += 1;
+            }
+        }
+        histogram
+    }
+
+    // distance (in world units, along the view direction) from the viewer to the nearest fragment
+    // of every pixel, None for the background
+    pub fn linear_depth(&self) -> Vec<Option<f32>> {
+        let width = self.target.width() as usize;
+        self.zbuffer
+            .iter()
+            .enumerate()
+            .map(|(position, &z)| {
+                if z == f32::NEG_INFINITY {
+                    return None;
+                }
+                // undo the rounding offset of the rasterizer
+                let screen = vec3(
+                    (position % width) as f32,
+                    (position / width) as f32,
+                    z - 0.5,
+                );
+                let v = self.camera.unproject_camera(self.scale_from_screen(screen));
+                Some(self.camera.camera_depth(v))
+            })
+            .collect()
+    }
+
+    // Save linear_depth as 32-bit float OpenEXR (for .exr paths, needs the exr feature) with the
+    // distance in every channel and infinity for the background, or as 16-bit grayscale image
+    // scaled so the farthest fragment is white (the scale is lost) and the background is black
+    pub fn save_linear_depth(&self, path: &str) -> Result<()> {
+        let (width, height) = self.target.dimensions();
+        let depth = self.linear_depth();
+        if path.to_lowercase().ends_with(".exr") {
+            #[cfg(feature = "exr")]
+            {
+                exr::prelude::write_rgb_file(path, width as usize, height as usize, |x, y| {
+                    let d = depth[x + y * width as usize].unwrap_or(f32::INFINITY);
+                    (d, d, d)
+                })?;
+                return Ok(());
+            }
+            #[cfg(not(feature = "exr"))]
+            bail!("Saving {:?} requires the exr feature", path);
+        }
+
+        let far = depth.iter().flatten().fold(0.0f32, |far, &d| far.max(d));
+        let image = image::ImageBuffer::from_fn(width, height, |x, y| {
+            let d = depth[(x + y * width) as usize];
+            let d = d.map_or(0.0, |d| (d / far).clamp(0.0, 1.0) * 65535.0);
+            image::Luma([d.round() as u16])
+        });
+        image.save(path)?;
+        Ok(())
+    }
+
+    // Save linear_depth in false colors: the nearest fragment gets the start color of colormap,
+    // the farthest one its end color and the background is black
+    pub fn save_depth_colored(&self, path: &str, colormap: Colormap) -> Result<()> {
+        let (width, height) = self.target.dimensions();
+        let depth = self.linear_depth();
+        let (near, far) = depth
+            .iter()
+            .flatten()
+            .fold((f32::INFINITY, 0.0f32), |(near, far), &d| {
+                (near.min(d), far.max(d))
+            });
+        let image = Image::from_fn(width, height, |x, y| {
+            match depth[(x + y * width) as usize] {
+                Some(d) => colormap.color((d - near) / (far - near).max(f32::EPSILON)),
+                None => Color::from([0, 0, 0]),
+            }
+        });
+        image.save(path)?;
+        Ok(())
+    }
+
+    // draw a border of given thickness (in pixels) around the silhouette of every visible object
+    // (or group, see set_grouping_mode) named object_name
+    pub fn draw_outline(&mut self, object_name: &str, color: Color, thickness: u32) -> Result<()> {
+        let ids: Vec<u32> = (0..self.objects.len())
+            .filter(|&i| self.objects[i] == object_name)
+            .map(|i| i as u32 + 1)
+            .collect();
+        if ids.is_empty() {
+            bail!("Object {:?} was not rendered", object_name);
+        }
+
+        let width = self.target.width() as i64;
+        let height = self.target.height() as i64;
+        let selected = |x: i64, y: i64| ids.contains(&self.ids[(x + y * width) as usize]);
+        let t = thickness as i64;
+
+        let mut border = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if selected(x, y) {
+                    continue;
+                }
+
+                let near = (-t..=t).any(|dy| {
+                    (-t..=t).any(|dx| {
+                        let (sx, sy) = (x + dx, y + dy);
+                        dx * dx + dy * dy <= t * t
+                            && sx >= 0
+                            && sy >= 0
+                            && sx < width
+                            && sy < height
+                            && selected(sx, sy)
+                    })
+                });
+
+                if near {
+                    border.push((x, y));
+                }
+            }
+        }
+
+        for (x, y) in border {
+            self.target.put_pixel(x as u32, y as u32, color);
+            self.hdr[(x + y * width) as usize] = to_linear(color);
+        }
+        Ok(())
+    }
+
+    // mix in wireframe overlay color for fragments (with barycentric coordinates bc) near edges
+    #[inline(always)]
+    fn wireframe(&self, bc: Vec3, color: Color, linear: Vec3) -> (Color, Vec3) {
+        let (edge_color, width) = match self.wireframe {
+            Some(wireframe) => wireframe,
+            None => return (color, linear),
+        };
+
+        // distance (in pixels) to the nearest edge of the original triangle, the line is
+        // centered on the edge and anti-aliased over 1 pixel
+        let (weights, altitudes) = self.edges;
+        let distance = (weights * bc * altitudes).min_element();
+        let coverage = (width / 2.0 - distance + 0.5).clamp(0.0, 1.0);
+        if coverage <= 0.0 {
+            return (color, linear);
+        }
+
+        let mix = |c: u8, e: u8| (c as f32 * (1.0 - coverage) + e as f32 * coverage) as u8;
+        (
+            Color::from([
+                mix(color[0], edge_color[0]),
+                mix(color[1], edge_color[1]),
+                mix(color[2], edge_color[2]),
+            ]),
+            linear.lerp(to_linear(edge_color), coverage),
+        )
+    }
+
+    // z-test fragment at position and update depth if it passes
+    #[inline(always)]
+    fn depth_test(&mut self, position: usize, z: f32) -> bool {
+        let width = self.target.width() as usize;
+        check_depth(z, (position % width, position / width));
+        if !self.clip_planes.is_empty() && self.clipped(position, z) {
+            return false;
+        }
+        if self.coverage < 1.0 && self.coverage <= dither(position % width, position / width) {
+            return false;
+        }
+        if let Some(peel) = &mut self.peel {
+            // nearest fragment behind the previous layer (and in front of opaque geometry)
+            if z > peel.depth[position] && z < peel.front[position] {
+                peel.depth[position] = z;
+                return true;
+            }
+            return false;
+        }
+
+        let prev_z = self.zbuffer[position];
+        if self.background {
+            return prev_z == f32::NEG_INFINITY;
+        }
+        if prev_z < z {
+            if self.depth_write {
+                self.zbuffer[position] = z;
+                if let Some(keys) = &mut self.tie_keys {
+                    keys[position] = self.tie_key;
+                }
+            }
+            true
+        } else if prev_z == z {
+            match &mut self.tie_keys {
+                Some(keys) if keys[position] > self.tie_key => false,
+                Some(keys) => {
+                    if self.depth_write {
+                        keys[position] = self.tie_key;
+                    }
+                    true
+                }
+                None => true,
+            }
+        } else {
+            false
+        }
+    }
+
+    // whether fragment at position with depth z is cut off by a clip plane, see add_clip_plane
+    fn clipped(&self, position: usize, z: f32) -> bool {
+        let width = self.target.width() as usize;
+        // undo the rounding offset of the rasterizer
+        let screen = vec3(
+            (position % width) as f32,
+            (position / width) as f32,
+            z - 0.5,
+        );
+        let v = self.camera.unproject_camera(self.scale_from_screen(screen));
+        let p = (self.camera.to_world * v + self.camera.lookat).extend(1.0);
+        self.clip_planes.iter().any(|plane| plane.dot(p) < 0.0)
+    }
+
+    #[inline(always)]
+    fn set(&mut self, x: usize, y: usize, bc: Vec3, color: Color, linear: Vec3) {
+        check_pixel((x, y), self.size());
+        let position = x + y * self.target.width() as usize;
+        let (color, linear) = self.wireframe(bc, color, linear);
+        let alpha = self.alpha;
+        if let Some(peel) = &mut self.peel {
+            peel.color[position] = vec3(color[0] as f32, color[1] as f32, color[2] as f32);
+            peel.linear[position] = linear;
+            peel.alpha[position] = alpha;
+            peel.id[position] = self.id;
+            peel.triangle[position] = self.triangle;
+            return;
+        }
+
+        if alpha < 1.0 {
+            // blend over what's already there
+            let prev = *self.target.get_pixel(x as u32, y as u32);
+            let blend = |c: u8, p: u8| (c as f32 * alpha + p as f32 * (1.0 - alpha)) as u8;
+            let color = Color::from([
+                blend(color[0], prev[0]),
+                blend(color[1], prev[1]),
+                blend(color[2], prev[2]),
+            ]);
+            self.target.put_pixel(x as u32, y as u32, color);
+            self.hdr[position] = linear * alpha + self.hdr[position] * (1.0 - alpha);
+        } else {
+            self.target.put_pixel(x as u32, y as u32, color);
+            self.hdr[position] = linear;
+        }
+        if self.background {
+            // no fragment data, see render_background
+            return;
+        }
+        self.ids[position] = self.id;
+        if let Some(triangles) = &mut self.triangles {
+            triangles[position] = Some(self.triangle);
+        }
+        if let Some(uvw) = &mut self.uvw {
+            // until triangle_texture sets it
+            uvw[position] = None;
+        }
+        if let Some(ssr) = &mut self.ssr {
+            ssr.surfaces[position] = None;
+        }
+        if let Some(contact) = &mut self.contact_shadows {
+            // until triangle_texture sets it
+            contact.kept[position] = 1.0;
+        }
+    }
+
+    // Fill::Textured triangle lit by the key light and extra_lights (generic, so that an empty
+    // array compiles their loop away)
+    fn triangle_texture<L: AsRef<[Light]>>(
+        &mut self,
+        triangle: &Deferred,
+        texture: Option<&Texture>,
+        extra_lights: L,
+    ) {
+        let [a, b, c] = triangle.vertices;
+        let (uv, normals, ao, tangents, world) = match triangle.fill {
+            Fill::Textured {
+                uv,
+                normals,
+                ao,
+                tangents,
+                world,
+                ..
+            } => (uv, normals, ao, tangents, world),
+            Fill::Flat(_) | Fill::Gradient(_) => unreachable!("triangle isn't textured"),
+        };
+        let [uv0, uv1, uv2] = uv;
+        let material = &triangle.material;
+        let bounds = self.raster_bounds();
+        let normal_map = self.normal_map.take();
+        let shadow_maps = std::mem::take(&mut self.shadow_maps);
+        // of the key light and then of extra_lights
+        let shadow_map = |i: usize| shadow_maps.get(i).and_then(Option::as_ref);
+        let light_direction = self.light_direction;
+        let pcf_kernel_size = self.pcf_kernel_size;
+        let shiny = material.specular != Vec3::zero();
+        // uv is in texture pixels, normal map can be of different size
+        let map_scale = normal_map.as_ref().zip(texture).map(|((map, _), texture)| {
+            let scale = |map: u32, texture: u32| (map - 1) as f32 / (texture - 1).max(1) as f32;
+            vec2(
+                scale(map.width(), texture.width()),
+                scale(map.height(), texture.height()),
+            )
+        });
+        let object_to_camera = self.camera.translation * self.normal_transform;
+        let ambient = self.ambient;
+        let environment = self.environment.take();
+        let ambient_strength = self.ambient_strength;
+        let camera_to_world = self.camera.to_world;
+        // from texture pixels back to [0; 1]
+        let uv_scale = texture.map(|texture| {
+            vec3(
+                1.0 / (texture.width() - 1).max(1) as f32,
+                1.0 / (texture.height() - 1).max(1) as f32,
+                1.0,
+            )
+        });
+        // texture-less triangles have all uv at 0, so they sample a single white pixel
+        let white = self.white_texture.clone();
+        let texture = texture.unwrap_or(&white);
+        // UVs interpolated between ones inside the texture don't need wrapping
+        let (max_x, max_y) = ((texture.width() - 1) as f32, (texture.height() - 1) as f32);
+        let inside =
+            |uv: &Vec3| uv.x() >= 0.0 && uv.y() >= 0.0 && uv.x() <= max_x && uv.y() <= max_y;
+        let wrap = Some(self.texture_wrap).filter(|_| !uv.iter().all(inside));
+        in_triangle(
+            a.truncate(),
+            b.truncate(),
+            c.truncate(),
+            bounds,
+            self.conservative,
+            |x, y, bc| {
+                let position = x + y * self.target.width() as usize;
+                if position >= self.zbuffer.len() {
+                    // this pixel is out of bounds
+                    return;
+                }
+
+                // TODO: WTF?
+                let z = a.z() * bc.x() + b.z() * bc.y() + c.z() * bc.z() + 0.5;
+
+                // if previous pixel put at |x, y| as further away from camera, replace it
+                if self.depth_test(position, z) {
+                    // TODO: WTF?
+                    let uvw = uv0 * bc.x() + uv1 * bc.y() + uv2 * bc.z();
+                    let uv = uvw.truncate();
+                    let color = match wrap {
+                        Some(wrap) => sample(texture, uv, wrap),
+                        None => {
+                            check_texel(uv, texture.dimensions(), (x, y));
+                            *texture.get_pixel(uv.x() as u32, uv.y() as u32)
+                        }
+                    };
+
+                    let mut normal =
+                        normals[0] * bc.x() + normals[1] * bc.y() + normals[2] * bc.z();
+                    if let (Some((map, space)), Some(scale)) = (&normal_map, map_scale) {
+                        let p = uv * scale;
+                        let sample = *map.get_pixel(p.x() as u32, p.y() as u32);
+                        normal = map_normal(sample, *space, normal, tangents, object_to_camera);
+                    }
+                    let normal = normal.normalize();
+                    let fragment = Fragment {
+                        position: world[0] * bc.x() + world[1] * bc.y() + world[2] * bc.z(),
+                        normal,
+                        material,
+                        shiny,
+                        pcf_kernel_size,
+                    };
+                    // every light is shadowed on its own
+                    let (key, mut glow) = fragment.light(light_direction, 1.0, shadow_map(0));
+                    let mut direct = key;
+                    for (i, light) in extra_lights.as_ref().iter().enumerate() {
+                        let (diffuse, specular) =
+                            fragment.light(light.direction, light.intensity, shadow_map(i + 1));
+                        direct += diffuse;
+                        glow += specular;
+                    }
+                    // colored ambient light is folded into the base color of the material
+                    let world_normal = camera_to_world * normal;
+                    let directional = match (&environment, &ambient) {
+                        (Some(environment), _) => Some(environment.sample(world_normal)),
+                        (None, Some(sh)) => Some(sh.irradiance(world_normal)),
+                        (None, None) => None,
+                    };
+                    let (intensity, base, gamma_base, ambient_light) = match directional {
+                        Some(ambient_light) => {
+                            let ambient_light = ambient_light * ambient_strength;
+                            let light = ambient_light.max(Vec3::splat(direct)) * ao.dot(bc);
+                            let gamma = vec3(light.x().sqrt(), light.y().sqrt(), light.z().sqrt());
+                            let ambient_light = ambient_light.dot(Vec3::splat(1.0 / 3.0));
+                            (
+                                1.0,
+                                material.color * light,
+                                material.color * gamma,
+                                ambient_light,
+                            )
+                        }
+                        None => {
+                            let ambient_light = 0.2 * ambient_strength;
+                            let intensity = max(direct, ambient_light) * ao.dot(bc);
+                            (intensity, material.color, material.color, ambient_light)
+                        }
+                    };
+                    let mut linear = to_linear(color) * base * intensity;
+
+                    let intensity = intensity.sqrt(); // gamma correction
+                    let base = gamma_base;
+                    let shade = |color: u8, base: f32| color as f32 * base * intensity;
+                    let mut rgb = vec3(
+                        shade(color[0], base.x()),
+                        shade(color[1], base.y()),
+                        shade(color[2], base.z()),
+                    );
+                    if shiny {
+                        linear += glow;
+                        rgb += vec3(glow.x().sqrt(), glow.y().sqrt(), glow.z().sqrt()) * 255.0;
+                    }
+                    // NOTE: float to int casts saturate
+                    let color = Color::from([rgb.x() as u8, rgb.y() as u8, rgb.z() as u8]);
+                    self.set(x, y, bc, color, linear);
+                    if self.background {
+                        return;
+                    }
+                    if let (Some(buffer), Some(uv_scale), None) =
+                        (&mut self.uvw, uv_scale, &self.peel)
+                    {
+                        buffer[position] = Some(uvw * uv_scale);
+                    }
+                    if let (Some(ssr), true, None) = (&mut self.ssr, shiny, &self.peel) {
+                        ssr.surfaces[position] = Some((normal, material.specular));
+                    }
+                    if let (Some(contact), None) = (&mut self.contact_shadows, &self.peel) {
+                        // without the diffuse light of the key light
+                        let unlit = max(direct - key, ambient_light);
+                        contact.kept[position] = unlit / max(direct, ambient_light);
+                    }
+                }
+            },
+        );
+        self.normal_map = normal_map;
+        self.shadow_maps = shadow_maps;
+        self.environment = environment;
+    }
+
+    fn triangle(&mut self, a: Vec3, b: Vec3, c: Vec3, color: Color) {
+        let bounds = self.raster_bounds();
+        in_triangle(
+            a.truncate(),
+            b.truncate(),
+            c.truncate(),
+            bounds,
+            self.conservative,
+            |x, y, bc| {
+                let z = a.z() * bc.x() + b.z() * bc.y() + c.z() * bc.z() + 0.5;
+                if self.depth_test(x + y * self.target.width() as usize, z) {
+                    self.set(x, y, bc, color, to_linear(color));
+                }
+            },
+        );
+    }
+
+    // triangle with colors of its vertices interpolated across it
+    fn triangle_gradient(&mut self, a: Vec3, b: Vec3, c: Vec3, colors: [Vec3; 3]) {
+        let bounds = self.raster_bounds();
+        in_triangle(
+            a.truncate(),
+            b.truncate(),
+            c.truncate(),
+            bounds,
+            self.conservative,
+            |x, y, bc| {
+                let z = a.z() * bc.x() + b.z() * bc.y() + c.z() * bc.z() + 0.5;
+                if self.depth_test(x + y * self.target.width() as usize, z) {
+                    let rgb = colors[0] * bc.x() + colors[1] * bc.y() + colors[2] * bc.z();
+                    let color = Color::from([rgb.x() as u8, rgb.y() as u8, rgb.z() as u8]);
+                    self.set(x, y, bc, color, rgb / 255.0);
+                }
+            },
+        );
+    }
+
+    // brightness of lines at world space point p, see set_line_depth_fade
+    fn line_fade(&self, p: Vec3) -> f32 {
+        let (near, far) = match self.line_fade {
+            Some(fade) => fade,
+            None => return 1.0,
+        };
+
+        let depth = self.camera.depth(p);
+        if far > near {
+            1.0 - ((depth - near) / (far - near)).clamp(0.0, 1.0)
+        } else if depth > near {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    // z-tested line between a and b (in screen coordinates), its color is scaled by fade
+    // interpolated from a to b
+    fn screen_line(&mut self, a: Vec3, b: Vec3, color: Color, fade: [f32; 2]) {
+        let width = self.target.width() as i64;
+        let bounds = self.raster_bounds();
+
+        let d = b - a;
+        // step only through the part of the line within bounds (up to rounding), not through
+        // the pixels on the way to far away points
+        let (mut t0, mut t1) = (0.0f32, 1.0f32);
+        let axes = [
+            (a.x(), d.x(), bounds.x, bounds.width),
+            (a.y(), d.y(), bounds.y, bounds.height),
+        ];
+        for &(start, delta, first, size) in &axes {
+            let (low, high) = (first as f32 - 0.5, (first + size) as f32 - 0.5);
+            if delta == 0.0 {
+                if start < low || start >= high {
+                    return;
+                }
+                continue;
+            }
+            let (ta, tb) = ((low - start) / delta, (high - start) / delta);
+            t0 = max(t0, min(ta, tb));
+            t1 = min(t1, max(ta, tb));
+        }
+        // NaN if a or b isn't finite
+        if t0.is_nan() || t1.is_nan() || t0 > t1 {
+            return;
+        }
+
+        let clipped = d * (t1 - t0);
+        let steps = max(clipped.x().abs(), clipped.y().abs()).ceil().max(1.0) as i64;
+        for i in 0..=steps {
+            let t = t0 + (t1 - t0) * (i as f32 / steps as f32);
+            let p = a + d * t;
+            let (x, y) = (p.x().round() as i64, p.y().round() as i64);
+            if x < 0 || y < 0 || !bounds.contains(x as usize, y as usize) {
+                continue;
+            }
+
+            let position = (x + y * width) as usize;
+            let z = p.z() + 0.5;
+            if self.zbuffer[position] <= z {
+                let fade = fade[0] + (fade[1] - fade[0]) * t;
+                let color = if fade < 1.0 {
+                    let scale = |c: u8| (c as f32 * fade).round() as u8;
+                    Color::from([scale(color[0]), scale(color[1]), scale(color[2])])
+                } else {
+                    color
+                };
+                self.zbuffer[position] = z;
+                self.target.put_pixel(x as u32, y as u32, color);
+                self.hdr[position] = to_linear(color);
+            }
+        }
+    }
+
+    // z-tested line between world space points from and to
+    pub fn line(&mut self, from: Vec3, to: Vec3, color: Color) {
+        if let (Some(a), Some(b)) = (self.screen_coords(from), self.screen_coords(to)) {
+            let fade = [from, to].map(|p| self.line_fade(self.transform.transform_point3(p)));
+            self.screen_line(a, b, color, fade);
+        }
+    }
+
+    // z-tested line along every triangle edge of model (with the current model transform), e.g.
+    // for wireframe renders. Edges shared by several triangles are drawn once.
+    pub fn draw_wireframe(&mut self, model: &ObjSet, color: Color) {
+        for object in &model.objects {
+            let mut drawn = HashSet::new();
+            let shapes = object.geometry.iter().flat_map(|geometry| &geometry.shapes);
+            for shape in shapes {
+                let (a, b, c) = match shape.primitive {
+                    Primitive::Triangle(a, b, c) => (a.0, b.0, c.0),
+                    _ => continue,
+                };
+                for &(from, to) in &[(a, b), (b, c), (c, a)] {
+                    if drawn.insert((from.min(to), from.max(to))) {
+                        let (from, to) = (object.vertices[from], object.vertices[to]);
+                        self.line(to_vec3(from), to_vec3(to), color);
+                    }
+                }
+            }
+        }
+    }
+
+    // X (red), Y (green) and Z (blue) axes from the origin
+    pub fn draw_axes(&mut self, length: f32) {
+        let origin = Vec3::zero();
+        self.line(origin, vec3(length, 0.0, 0.0), Color::from([0xff, 0, 0]));
+        self.line(origin, vec3(0.0, length, 0.0), Color::from([0, 0xff, 0]));
+        self.line(origin, vec3(0.0, 0.0, length), Color::from([0, 0, 0xff]));
+    }
+
+    // grid on the XZ plane covering [-extent; extent] with a line every step units
+    pub fn draw_grid(&mut self, extent: f32, step: f32, color: Color) {
+        if step <= 0.0 {
+            return;
+        }
+
+        let lines = (extent / step).floor() as i32;
+        for i in -lines..=lines {
+            let offset = i as f32 * step;
+            self.line(vec3(offset, 0.0, -extent), vec3(offset, 0.0, extent), color);
+            self.line(vec3(-extent, 0.0, offset), vec3(extent, 0.0, offset), color);
+        }
+    }
+
+    // Debug overlay: z-tested line of length (in world units) along the normal from every vertex
+    // of model (with the current model transform), wrong or inverted normals stand out
+    pub fn draw_normals(&mut self, model: &ObjSet, length: f32, color: Color) {
+        for object in &model.objects {
+            // vertices shared by several triangles are drawn once (per normal)
+            let mut drawn = HashSet::new();
+            let corners = object
+                .geometry
+                .iter()
+                .flat_map(|geometry| geometry.shapes.iter())
+                .flat_map(|shape| match shape.primitive {
+                    Primitive::Triangle(a, b, c) => vec![a, b, c],
+                    _ => Vec::new(),
+                });
+
+            for (v, _, vn) in corners {
+                let vn = match vn {
+                    Some(vn) if drawn.insert((v, vn)) => vn,
+                    _ => continue,
+                };
+
+                let from = self.transform.transform_point3(to_vec3(object.vertices[v]));
+                let normal = self.normal_transform * to_vec3(object.normals[vn]);
+                if normal.length_squared() == 0.0 {
+                    continue;
+                }
+
+                let to = from + normal.normalize() * length;
+                if let (Some(a), Some(b)) = (self.camera.project(from), self.camera.project(to)) {
+                    let (a, b) = (self.scale_to_screen(a), self.scale_to_screen(b));
+                    let fade = [self.line_fade(from), self.line_fade(to)];
+                    self.screen_line(a, b, color, fade);
+                }
+            }
+        }
+    }
+
+    fn scale_to_screen(&self, v: Vec3) -> Vec3 {
+        // coordinates in obj file are in [-1.0; 1.0] range
+        // NOTE: not really, but it's true for african_head.obj
+        let v = (v + Vec3::splat(1.0)) / 2.0; // [-1; 1] => [0; 1]
+        vec3(
+            v.x() * (self.target.width() - 1) as f32 + self.jitter.x(),
+            v.y() * (self.target.height() - 1) as f32 + self.jitter.y(),
+            // NOTE: this is a hack
+            v.z() * (self.target.width() + self.target.height() - 2) as f32 / 2.0,
+        )
+    }
+
+    // Screen space lines through the edges of a triangle with clip space vertices (see
+    // Camera::clip), opposite a, b and c. They're homogeneous (points p on them have
+    // line.dot((p.x, p.y, 1)) == 0), so they're found even for vertices behind the viewer.
+    fn screen_lines(&self, clip: [Vec4; 3]) -> [Vec3; 3] {
+        let origin = self.scale_to_screen(Vec3::zero());
+        let scale = self.scale_to_screen(Vec3::one()) - origin;
+        let [a, b, c] = clip.map(|p| {
+            vec3(
+                p.x() * scale.x() + p.w() * origin.x(),
+                p.y() * scale.y() + p.w() * origin.y(),
+                p.w(),
+            )
+        });
+        [b.cross(c), c.cross(a), a.cross(b)]
+    }
+
+    fn scale_from_screen(&self, v: Vec3) -> Vec3 {
+        let scale = vec3(
+            (self.target.width() - 1).max(1) as f32,
+            (self.target.height() - 1).max(1) as f32,
+            (self.target.width() + self.target.height() - 2).max(1) as f32 / 2.0,
+        );
+        (v - self.jitter.extend(0.0)) / scale * 2.0 - Vec3::splat(1.0)
+    }
+
+    // None if v is behind the viewer
+    fn screen_coords(&self, v: Vec3) -> Option<Vec3> {
+        let r = self.camera.project(self.transform.transform_point3(v))?;
+        Some(self.scale_to_screen(r))
+    }
+
+    // rotate model space normal n to camera-centric coordinate system
+    fn camera_normal(&self, n: Vec3) -> Vec3 {
+        let n = self.normal_transform * n;
+        self.camera.translate(if n.length_squared() > 0.0 {
+            n.normalize()
+        } else {
+            n
+        })
+    }
+
+    // camera space normals of primitive's vertices facing the viewer, normal (of the face) for
+    // ones without normals
+    fn vertex_normals(
+        &self,
+        primitive: &Primitive,
+        object: &Object,
+        normal: Vec3,
+        back: bool,
+    ) -> [Vec3; 3] {
+        let normals = match primitive {
+            Primitive::Triangle((_, _, Some(nx)), (_, _, Some(ny)), (_, _, Some(nz))) => [
+                self.camera_normal(to_vec3(object.normals[*nx])),
+                self.camera_normal(to_vec3(object.normals[*ny])),
+                self.camera_normal(to_vec3(object.normals[*nz])),
+            ],
+            _ => return [normal; 3],
+        };
+        // vertex normals point out of the front face
+        if back {
+            normals.map(|n| -n)
+        } else {
+            normals
+        }
+    }
+
+    // returns UV coordinates for v (with the scale and offset of material) in texture pixels, w
+    // is kept as is
+    fn texture_coords(&self, v: TVertex, texture: &Texture, material: &Material) -> Vec3 {
+        let uv = if self.pixel_uvs {
+            vec2(
+                (v.u / texture.width() as f64) as f32,
+                (v.v / texture.height() as f64) as f32,
+            )
+        } else {
+            vec2(v.u as f32, v.v as f32)
+        };
+        let uv = uv * material.uv_scale + material.uv_offset;
+        vec3(
+            uv.x() * (texture.width() - 1) as f32,
+            uv.y() * (texture.height() - 1) as f32,
+            v.w as f32,
+        )
+    }
+
+    // point sprite at model space position p, see set_point_size
+    fn point(&mut self, index: usize, p: Vec3, color: Color) {
+        let center = match self.screen_coords(p) {
+            Some(center) => center,
+            None => return,
+        };
+        let mut size = self.point_size;
+        if self.point_attenuation {
+            let depth = self.camera.depth(self.transform.transform_point3(p));
+            size *= (self.camera.lookfrom - self.camera.lookat).length() / depth.max(f32::EPSILON);
+        }
+
+        self.id = self.objects.len() as u32;
+        self.triangle = index as u32;
+        // points have no edges for the wireframe overlay
+        self.edges = (Mat3::identity(), Vec3::splat(f32::INFINITY));
+        // pixels with the center in [center - size / 2; center + size / 2)
+        let (min, max) = (
+            center.truncate() - Vec2::splat(size / 2.0),
+            center.truncate() + Vec2::splat(size / 2.0),
+        );
+        let bounds = self.raster_bounds();
+        let width = self.target.width() as usize;
+        let z = center.z() + 0.5;
+        let (x0, y0) = (
+            min.x().ceil().max(0.0) as usize,
+            min.y().ceil().max(0.0) as usize,
+        );
+        for y in y0..(max.y().ceil().max(0.0) as usize) {
+            for x in x0..(max.x().ceil().max(0.0) as usize) {
+                if !bounds.contains(x, y) {
+                    continue;
+                }
+                let color = match &self.point_sprite {
+                    Some(sprite) => {
+                        let uv = (vec2(x as f32, y as f32) - min) / size;
+                        let texel = sample(
+                            sprite,
+                            uv * vec2(sprite.width() as f32, sprite.height() as f32),
+                            TextureWrap::Clamp,
+                        );
+                        if texel.0 == [0, 0, 0] {
+                            continue;
+                        }
+                        let modulate = |c: u8, t: u8| (c as u32 * t as u32 / 255) as u8;
+                        Color::from([
+                            modulate(color[0], texel[0]),
+                            modulate(color[1], texel[1]),
+                            modulate(color[2], texel[2]),
+                        ])
+                    }
+                    None => color,
+                };
+                if self.depth_test(x + y * width, z) {
+                    self.set(x, y, Vec3::splat(1.0 / 3.0), color, to_linear(color));
+                }
+            }
+        }
+    }
+
+    // world space positions of model space vertices of a triangle relative to the camera's lookat,
+    // in double precision up to the offsets of vertices from the centroid of the triangle
+    fn precise_offsets(&self, vertices: [Vertex; 3]) -> [Vec3; 3] {
+        let centroid = [
+            (vertices[0].x + vertices[1].x + vertices[2].x) / 3.0,
+            (vertices[0].y + vertices[1].y + vertices[2].y) / 3.0,
+            (vertices[0].z + vertices[1].z + vertices[2].z) / 3.0,
+        ];
+        // transform * centroid - lookat
+        let m = self.transform.to_cols_array().map(|c| c as f64);
+        let lookat = self.camera.lookat;
+        let lookat = [lookat.x() as f64, lookat.y() as f64, lookat.z() as f64];
+        let center = |i: usize| {
+            let c = m[i] * centroid[0] + m[4 + i] * centroid[1] + m[8 + i] * centroid[2];
+            (c + m[12 + i] - lookat[i]) as f32
+        };
+        let center = vec3(center(0), center(1), center(2));
+
+        vertices.map(|v| {
+            let local = vec3(
+                (v.x - centroid[0]) as f32,
+                (v.y - centroid[1]) as f32,
+                (v.z - centroid[2]) as f32,
+            );
+            center + self.transform.transform_vector3(local)
+        })
+    }
+
+    // normal of triangle abc (in screen coordinates) facing the viewer and whether it's a back
+    // face, None for culled back faces
+    fn front_normal(&self, a: Vec3, b: Vec3, c: Vec3) -> Option<(Vec3, bool)> {
+        // scale edges down first, so the cross product doesn't overflow for huge triangles
+        let edge = |v: Vec3| v / v.abs().max_element();
+        // the screen is mirrored in left-handed coordinates, so is the winding
+        let (b, c) = match self.camera.handedness {
+            Handedness::RightHanded => (b, c),
+            Handedness::LeftHanded => (c, b),
+        };
+        let normal = edge(b - a).cross(edge(c - a)).normalize();
+        let normal = match self.front_face {
+            FrontFace::Ccw => normal,
+            FrontFace::Cw => -normal,
+        };
+
+        // z axis points towards the viewer
+        if normal.z() > 0.0 {
+            Some((normal, false))
+        } else if self.double_sided && normal.z() < 0.0 {
+            Some((-normal, true))
+        } else {
+            None
+        }
+    }
+
+    // directions of growing u and v over the triangle with model space positions p and
+    // texture coordinates uv, in camera space (zero for degenerate UV mapping)
+    fn tangents(&self, p: [Vec3; 3], uv: [Vec2; 3]) -> [Vec3; 2] {
+        let (e1, e2) = (p[1] - p[0], p[2] - p[0]);
+        let (d1, d2) = (uv[1] - uv[0], uv[2] - uv[0]);
+        let det = d1.perp_dot(d2);
+        if det == 0.0 {
+            return [Vec3::zero(); 2];
+        }
+
+        let t = (e1 * d2.y() - e2 * d1.y()) / det;
+        let b = (e2 * d1.x() - e1 * d2.x()) / det;
+        let to_camera = |v: Vec3| self.camera.translate(self.transform.transform_vector3(v));
+        [to_camera(t), to_camera(b)]
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn primitive(
+        &mut self,
+        index: usize,
+        primitive: &Primitive,
+        object: &Object,
+        texture: Option<(usize, &Texture)>,
+        material: &Material,
+        ao: Vec3,
+        colors: Option<[Vec3; 3]>,
+    ) {
+        let vertices = &object.vertices;
+        let texture_vertices = &object.tex_vertices;
+
+        // points and lines are unlit
+        let color = || match self.shading {
+            ShadingMode::TriangleColors => triangle_color(index),
+            _ => {
+                let rgb = (material.color * 255.0).round();
+                Color::from([rgb.x() as u8, rgb.y() as u8, rgb.z() as u8])
+            }
+        };
+        let points = match primitive {
+            Primitive::Triangle((x, _, _), (y, _, _), (z, _, _)) => {
+                [vertices[*x], vertices[*y], vertices[*z]]
+            }
+            Primitive::Point((v, _, _)) => {
+                let color = color();
+                self.point(index, to_vec3(vertices[*v]), color);
+                return;
+            }
+            Primitive::Line((a, _, _), (b, _, _)) => {
+                let color = color();
+                self.line(to_vec3(vertices[*a]), to_vec3(vertices[*b]), color);
+                return;
+            }
+        };
+        // world space positions relative to lookat
+        let offsets = if self.high_precision {
+            self.precise_offsets(points)
+        } else {
+            points.map(|v| self.transform.transform_point3(to_vec3(v)) - self.camera.lookat)
+        };
+        let clip = offsets.map(|v| self.camera.clip(v));
+
+        // triangles reaching out of the frustum are clipped to it, and drawn as the triangle fan
+        // of what's left
+        let near = self.camera.near_w();
+        let inside = |p: &Vec4| {
+            p.w() >= near
+                && p.x().abs() <= GUARD_BAND * p.w()
+                && p.y().abs() <= GUARD_BAND * p.w()
+                && self.camera.far_distance(*p) >= 0.0
+        };
+        let clipped = !clip.iter().all(inside);
+        let (polygon, screen) = if !clipped {
+            let screen = offsets
+                .iter()
+                .filter_map(|&v| self.camera.project_camera(self.camera.translate(v)));
+            (
+                vec![
+                    vec3(1.0, 0.0, 0.0),
+                    vec3(0.0, 1.0, 0.0),
+                    vec3(0.0, 0.0, 1.0),
+                ],
+                screen.map(|p| self.scale_to_screen(p)).collect(),
+            )
+        } else {
+            let polygon = frustum_clip(clip, &self.camera);
+            let screen = polygon.iter().map(|&w| {
+                let p = clip[0] * w.x() + clip[1] * w.y() + clip[2] * w.z();
+                self.scale_to_screen(p.truncate() / p.w())
+            });
+            let screen: Vec<Vec3> = screen.collect();
+            (polygon, screen)
+        };
+        let fan: Vec<[usize; 3]> = (1..polygon.len().saturating_sub(1))
+            .map(|i| [0, i, i + 1])
+            .collect();
+        // both of the whole triangle, so the parts of a clipped one are drawn as the same one
+        let tie_key = match (&self.tie_keys, clipped) {
+            (None, _) => 0,
+            (Some(_), false) => tie_key([screen[0], screen[1], screen[2]]),
+            (Some(_), true) => tie_key(clip.map(Vec4::truncate)),
+        };
+        let lines = match (&self.wireframe, clipped) {
+            (Some(_), true) => Some(self.screen_lines(clip)),
+            _ => None,
+        };
+        // facing of the largest part, the others are (nearly) degenerate or the same
+        let area = |[a, b, c]: [usize; 3]| {
+            let (a, b, c) = (screen[a], screen[b], screen[c]);
+            (b - a).truncate().perp_dot((c - a).truncate()).abs() / 2.0
+        };
+        let largest = match fan.iter().max_by(|&&x, &&y| area(x).total_cmp(&area(y))) {
+            Some(&[a, b, c]) => (screen[a], screen[b], screen[c]),
+            None => return,
+        };
+
+        let (normal, back) = match self.front_normal(largest.0, largest.1, largest.2) {
+            Some(facing) => facing,
+            None => return,
+        };
+        let back_material = match (back, &material.back) {
+            (true, Some(name)) => {
+                let mut back = self.materials.get(name).cloned().unwrap_or_default();
+                self.material_override.apply(&mut back);
+                Some(back)
+            }
+            _ => None,
+        };
+        let material = back_material.as_ref().unwrap_or(material);
+
+        if let Some(lod) = &self.lod {
+            let area: f32 = fan.iter().map(|&part| area(part)).sum();
+            if area < lod.min_triangle_area {
+                self.lod_stats.triangles += 1;
+                return;
+            }
+        }
+
+        let material_texture = material.texture.as_ref().map(|path| {
+            self.material_textures
+                .get(path)
+                .unwrap_or(&self.missing_texture)
+                .clone()
+        });
+        let fill = match (self.shading, primitive, texture, colors) {
+            (ShadingMode::TriangleColors, _, _, _) => Fill::Flat(triangle_color(index)),
+            (ShadingMode::VertexColors, _, _, Some(colors)) => {
+                let normals = self.vertex_normals(primitive, object, normal, back);
+                let light = self.light_direction;
+                if normals.iter().all(|n| !n.dot(light).is_sign_positive()) {
+                    return;
+                }
+
+                // the key light of lit triangles at every vertex, by its normal
+                let shade = |color: Vec3, normal: Vec3, ao: f32| {
+                    let intensity = normal.dot(light).max(0.0);
+                    let glow = highlight(material, normal, light);
+                    let glow = vec3(glow.x().sqrt(), glow.y().sqrt(), glow.z().sqrt()) * 255.0;
+                    color * material.color * (255.0 * intensity * ao) + glow
+                };
+                Fill::Gradient([
+                    shade(colors[0], normals[0], ao.x()),
+                    shade(colors[1], normals[1], ao.y()),
+                    shade(colors[2], normals[2], ao.z()),
+                ])
+            }
+            (ShadingMode::Normals, Primitive::Triangle(..), _, _) => {
+                let normals = self.vertex_normals(primitive, object, normal, back);
+                let to_world = self.camera.to_world;
+                Fill::Gradient(normals.map(|n| {
+                    let n = match self.normal_space {
+                        NormalSpace::View => n,
+                        NormalSpace::World => (to_world * n).normalize(),
+                    };
+                    (n + Vec3::one()) * 127.5
+                }))
+            }
+            (_, Primitive::Triangle((x, tx, _), (y, ty, _), (z, tz, _)), texture, _) => {
+                let normals = self.vertex_normals(primitive, object, normal, back);
+                let positions = [vertices[*x], vertices[*y], vertices[*z]].map(to_vec3);
+
+                // the material's texture replaces the mesh one, without a texture (or UVs to
+                // sample it) the material color is lit on its own
+                let texture = match &material_texture {
+                    Some(texture) => Some((TextureRef::Material(texture.clone()), &**texture)),
+                    None => texture.map(|(index, texture)| (TextureRef::Mesh(index), texture)),
+                };
+                let (texture, uv) = match (texture, tx, ty, tz) {
+                    (Some((texture_ref, texture)), Some(tx), Some(ty), Some(tz)) => (
+                        Some(texture_ref),
+                        [
+                            self.texture_coords(texture_vertices[*tx], texture, material),
+                            self.texture_coords(texture_vertices[*ty], texture, material),
+                            self.texture_coords(texture_vertices[*tz], texture, material),
+                        ],
+                    ),
+                    _ => (None, [Vec3::zero(); 3]),
+                };
+                let tangents = match (&self.normal_map, &texture) {
+                    (Some((_, NormalMapSpace::Tangent)), Some(_)) => {
+                        self.tangents(positions, uv.map(Vec3::truncate))
+                    }
+                    _ => [Vec3::zero(); 2],
+                };
+                let world = if self.shadow_maps.iter().any(Option::is_some) {
+                    positions.map(|p| self.transform.transform_point3(p))
+                } else {
+                    [Vec3::zero(); 3]
+                };
+                Fill::Textured {
+                    texture,
+                    uv,
+                    normals,
+                    ao,
+                    tangents,
+                    world,
+                }
+            }
+            // points and lines are drawn above
+            (_, Primitive::Point(_) | Primitive::Line(..), _, _) => unreachable!(),
+        };
+
+        // with alpha to coverage transparent triangles are drawn like opaque ones
+        let transparent = material.is_transparent() && !self.alpha_to_coverage;
+        // the texture is resolved already
+        let material = Material {
+            alpha: if material.is_transparent() {
+                material.alpha
+            } else {
+                1.0
+            },
+            color: material.color,
+            specular: material.specular,
+            shininess: material.shininess,
+            texture: None,
+            uv_scale: material.uv_scale,
+            uv_offset: material.uv_offset,
+            back: None,
+        };
+        for [a, b, c] in fan {
+            let vertices = [screen[a], screen[b], screen[c]];
+            let edges = match &lines {
+                Some(lines) => Mat3::from_cols(
+                    edge_distances(lines, vertices[0]),
+                    edge_distances(lines, vertices[1]),
+                    edge_distances(lines, vertices[2]),
+                ),
+                None if self.wireframe.is_some() => Mat3::from_scale(altitudes(vertices)),
+                None => Mat3::identity(),
+            };
+            let triangle = Deferred {
+                vertices,
+                fill: fill.lerp([polygon[a], polygon[b], polygon[c]]),
+                material: material.clone(),
+                id: self.objects.len() as u32,
+                triangle: index as u32,
+                edges,
+                tie_key,
+            };
+            if transparent {
+                self.transparent.push(triangle);
+            } else if self.sort_opaque {
+                self.opaque.push(triangle);
+            } else {
+                let texture = match &material_texture {
+                    Some(texture) => Some(&**texture),
+                    None => texture.map(|(_, texture)| texture),
+                };
+                self.fill(&triangle, texture);
+            }
+        }
+    }
+
+    fn fill(&mut self, triangle: &Deferred, texture: Option<&Texture>) {
+        let [a, b, c] = triangle.vertices;
+        self.tie_key = triangle.tie_key;
+        self.edges = (triangle.edges, Vec3::one());
+
+        let polygon = match guard_band_clip([a.truncate(), b.truncate(), c.truncate()], self.size())
+        {
+            Some(polygon) => polygon,
+            None => return self.fill_clipped(triangle, texture),
+        };
+
+        // triangle fan of the clipped polygon
+        for i in 1..polygon.len().saturating_sub(1) {
+            let weights = [polygon[0], polygon[i], polygon[i + 1]];
+            let fill = triangle.fill.lerp(weights);
+
+            self.edges.0 = triangle.edges * Mat3::from_cols(weights[0], weights[1], weights[2]);
+            let vertices = triangle.vertices;
+            let part = Deferred {
+                vertices: [
+                    lerp3(vertices, weights[0]),
+                    lerp3(vertices, weights[1]),
+                    lerp3(vertices, weights[2]),
+                ],
+                fill,
+                material: triangle.material.clone(),
+                id: triangle.id,
+                triangle: triangle.triangle,
+                edges: triangle.edges,
+                tie_key: triangle.tie_key,
+            };
+            self.fill_clipped(&part, texture);
+        }
+    }
+
+    // fill triangle that is known to fit in the guard band
+    fn fill_clipped(&mut self, triangle: &Deferred, texture: Option<&Texture>) {
+        let [a, b, c] = triangle.vertices;
+        self.id = triangle.id;
+        self.triangle = triangle.triangle;
+        if self.alpha_to_coverage {
+            self.coverage = triangle.material.alpha;
+        } else {
+            self.alpha = triangle.material.alpha;
+        }
+        match (&triangle.fill, texture) {
+            (Fill::Textured { texture: index, .. }, texture) => {
+                let lights = std::mem::take(&mut self.lights);
+                let texture = index.as_ref().and(texture);
+                if lights.is_empty() {
+                    self.triangle_texture(triangle, texture, [])
+                } else {
+                    self.triangle_texture(triangle, texture, lights.as_slice())
+                }
+                self.lights = lights;
+            }
+            (Fill::Flat(color), _) => self.triangle(a, b, c, *color),
+            (Fill::Gradient(colors), _) => self.triangle_gradient(a, b, c, *colors),
+        }
+        self.alpha = 1.0;
+        self.coverage = 1.0;
+    }
+
+    // Opaque pass: draw opaque triangles deferred for sorting (see set_sort_opaque) from the
+    // nearest to the farthest one, so hidden fragments fail the z-test before being shaded.
+    // textures are indexed by Fill::Textured::texture
+    fn draw_opaque(&mut self, textures: &[Option<&Texture>]) {
+        let mut opaque = std::mem::take(&mut self.opaque);
+        opaque.sort_by(|a, b| b.depth().total_cmp(&a.depth()));
+
+        for triangle in &opaque {
+            self.fill(triangle, triangle.texture(textures));
+        }
+    }
+
+    // Transparent pass: draw transparent triangles collected so far from the farthest to the
+    // nearest one. They are z-tested against everything drawn so far, but don't write depth,
+    // so they never hide each other.
+    fn draw_transparent(&mut self, textures: &[Option<&Texture>]) {
+        let mut transparent = std::mem::take(&mut self.transparent);
+        transparent.sort_by(|a, b| a.depth().total_cmp(&b.depth()));
+
+        self.depth_write = false;
+        for triangle in &transparent {
+            self.fill(triangle, triangle.texture(textures));
+        }
+        self.depth_write = true;
+    }
+
+    pub fn obj(&mut self, model: &ObjSet, texture: &Texture) {
+        self.pass(|renderer| {
+            renderer.shadow_pass(&[(model, renderer.transform)]);
+            renderer.displace_models(std::iter::once(model));
+            renderer.draw(model, Some((0, texture)));
+            renderer.draw_opaque(&[Some(texture)]);
+            renderer.draw_transparent(&[Some(texture)]);
+        });
+    }
+
+    // Render model blended between morph targets a (at t = 0) and b (at t = 1) of the same
+    // topology, see morph
+    pub fn render_morph(
+        &mut self,
+        a: &ObjSet,
+        b: &ObjSet,
+        t: f32,
+        texture: Option<&Texture>,
+    ) -> Result<()> {
+        let model = morph(a, b, t)?;
+        self.pass(|renderer| {
+            renderer.shadow_pass(&[(&model, renderer.transform)]);
+            renderer.displace_models(std::iter::once(&model));
+            renderer.draw(&model, texture.map(|texture| (0, texture)));
+            renderer.draw_opaque(&[texture]);
+            renderer.draw_transparent(&[texture]);
+        });
+        Ok(())
+    }
+
+    // render every instance of the scene
+    pub fn render_scene(&mut self, scene: &Scene) {
+        self.pass(|renderer| {
+            let textures = scene_textures(scene);
+            renderer.shadow_pass(&scene_models(scene));
+            // before tiling, so the renderer copies drawing the tiles share them
+            renderer.displace_models(scene.meshes.iter().map(|mesh| &mesh.model));
+            renderer.tiled(|renderer| {
+                renderer.draw_instances(scene);
+                renderer.draw_opaque(&textures);
+                renderer.draw_transparent(&textures);
+            });
+        });
+    }
+
+    // Draw scene (e.g. a skybox) behind everything drawn so far: only into pixels nothing
+    // covers yet whatever its depth, and not at all once the whole image is covered. It neither
+    // writes depth nor casts shadows, so its pixels still count as background afterwards.
+    // NOTE: drawn over the previous contents regardless of set_pass_blend
+    pub fn render_background(&mut self, scene: &Scene) {
+        if self.uncovered_pixels() == 0 {
+            return;
+        }
+        let textures = scene_textures(scene);
+        self.background = true;
+        self.draw_instances(scene);
+        self.draw_opaque(&textures);
+        self.draw_transparent(&textures);
+        self.background = false;
+    }
+
+    // add reflections of the pixels the mirrored view rays of reflective surfaces hit (see
+    // set_ssr), marching the rays in screen space where their depth is linear
+    fn reflect(&mut self) {
+        let (steps, surfaces) = match &mut self.ssr {
+            Some(ssr) => {
+                let empty = vec![None; ssr.surfaces.len()];
+                (ssr.steps, std::mem::replace(&mut ssr.surfaces, empty))
+            }
+            None => return,
+        };
+
+        let (width, height) = self.size();
+        let (target, hdr) = (self.target.clone(), self.hdr.clone());
+        let to_world = self.camera.to_world;
+        let reach = 4.0
+            * (self.camera.lookfrom - self.camera.lookat)
+                .length()
+                .max(1.0);
+        let inside = |x: f32, y: f32| {
+            x >= 0.0 && y >= 0.0 && x <= (width - 1) as f32 && y <= (height - 1) as f32
+        };
+        for (position, surface) in surfaces.into_iter().enumerate() {
+            let (normal, reflectance) = match surface {
+                Some(surface) => surface,
+                None => continue,
+            };
+            let start = vec3(
+                (position % width) as f32,
+                (position / width) as f32,
+                self.zbuffer[position],
+            );
+            let v = self.camera.unproject_camera(self.scale_from_screen(start));
+            // camera-centric coordinates aren't orthonormal unless up is perpendicular to the
+            // view direction, so the view ray is mirrored in world space
+            let view = (to_world * self.camera.view_direction(v)).normalize();
+            let normal = (to_world * normal).normalize();
+            let ray = self.camera.translation * (view - normal * (2.0 * view.dot(normal)));
+
+            // far end of the ray that's still in front of the viewer
+            let mut length = reach;
+            let end = loop {
+                if let Some(end) = self.camera.project_camera(v + ray * length) {
+                    break Some(self.scale_to_screen(end));
+                }
+                length /= 2.0;
+                if length < 1e-3 {
+                    break None;
+                }
+            };
+            let delta = match end {
+                Some(end) => end - start,
+                None => continue,
+            };
+            // part of the ray on the screen
+            let exit = |p: f32, d: f32, size: usize| match d {
+                d if d > 0.0 => ((size - 1) as f32 - p) / d,
+                d if d < 0.0 => -p / d,
+                _ => f32::INFINITY,
+            };
+            let on_screen = exit(start.x(), delta.x(), width)
+                .min(exit(start.y(), delta.y(), height))
+                .min(1.0);
+            let pixels = delta.truncate().length() * on_screen;
+            if pixels < 1.0 {
+                continue;
+            }
+
+            // at least a pixel per step, and thick enough for the ray not to step over surfaces
+            let stride = (pixels / steps as f32).max(1.0) / pixels * on_screen;
+            let thickness = (delta.z() * stride).abs() * 2.0 + 1.0;
+            // depth of the ray below the surface at t along it (None off the screen)
+            let below = |t: f32| {
+                let p = start + delta * t;
+                if !inside(p.x(), p.y()) {
+                    return None;
+                }
+                let (x, y) = (p.x().round() as usize, p.y().round() as usize);
+                Some(((x, y), self.zbuffer[x + y * width] - p.z()))
+            };
+            let mut hit = None;
+            for i in 1..=steps {
+                let t = stride * i as f32;
+                match below(t) {
+                    Some((_, depth)) if depth > 0.0 && depth < thickness => {
+                        // the crossing is somewhere within the last step
+                        let (mut front, mut back) = (t - stride, t);
+                        for _ in 0..4 {
+                            let middle = (front + back) / 2.0;
+                            match below(middle) {
+                                Some((_, depth)) if depth > 0.0 => back = middle,
+                                _ => front = middle,
+                            }
+                        }
+                        hit = below(back).map(|(pixel, _)| pixel);
+                        break;
+                    }
+                    Some(_) if t < on_screen => {}
+                    _ => break,
+                }
+            }
+            let (x, y) = match hit {
+                Some(hit) => hit,
+                None => continue,
+            };
+
+            // average of the covered pixels around the hit
+            let (mut color, mut linear, mut n) = (Vec3::zero(), Vec3::zero(), 0.0);
+            for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                    let i = nx + ny * width;
+                    if self.zbuffer[i] != f32::NEG_INFINITY {
+                        let c = target.get_pixel(nx as u32, ny as u32);
+                        color += vec3(c[0] as f32, c[1] as f32, c[2] as f32);
+                        linear += hdr[i];
+                        n += 1.0;
+                    }
+                }
+            }
+            let (color, linear) = (color * reflectance / n, linear * reflectance / n);
+
+            let (px, py) = ((position % width) as u32, (position / width) as u32);
+            let c = self.target.get_pixel(px, py);
+            let rgb = vec3(c[0] as f32, c[1] as f32, c[2] as f32) + color;
+            // NOTE: float to int casts saturate
+            let rgb = Color::from([rgb.x() as u8, rgb.y() as u8, rgb.z() as u8]);
+            self.target.put_pixel(px, py, rgb);
+            self.hdr[position] += linear;
+        }
+    }
+
+    // darken fragments in contact shadows, see set_contact_shadows
+    fn shade_contacts(&mut self) {
+        let (length, steps, kept) = match &mut self.contact_shadows {
+            Some(contact) => {
+                let empty = vec![1.0; contact.kept.len()];
+                (
+                    contact.length,
+                    contact.steps,
+                    std::mem::replace(&mut contact.kept, empty),
+                )
+            }
+            None => return,
+        };
+        if steps == 0 {
+            return;
+        }
+
+        let (width, height) = self.size();
+        let inside = |x: f32, y: f32| {
+            x >= 0.0 && y >= 0.0 && x <= (width - 1) as f32 && y <= (height - 1) as f32
+        };
+        // camera space position of the fragment at pixel (the rounding offset of the
+        // rasterizer undone)
+        let unproject = |x: usize, y: usize| {
+            let screen = vec3(x as f32, y as f32, self.zbuffer[x + y * width] - 0.5);
+            self.camera.unproject_camera(self.scale_from_screen(screen))
+        };
+        let step = self.light_direction * (length / steps as f32);
+        // samples closer than that may hit the surface the ray starts from
+        let bias = step.length();
+        let occluded = |position: usize| {
+            let v = unproject(position % width, position / width);
+            (1..=steps).any(|i| {
+                let p = v + step * i as f32;
+                let screen = match self.camera.project_camera(p) {
+                    Some(p) => self.scale_to_screen(p),
+                    None => return false,
+                };
+                if !inside(screen.x(), screen.y()) {
+                    return false;
+                }
+                let (x, y) = (screen.x().round() as usize, screen.y().round() as usize);
+                if self.zbuffer[x + y * width] == f32::NEG_INFINITY {
+                    return false;
+                }
+                // the occluder is in front of the ray, but not too far
+                let above = unproject(x, y).z() - p.z();
+                above > bias && above < length
+            })
+        };
+        let shadowed: Vec<(usize, f32)> = kept
+            .into_iter()
+            .enumerate()
+            .filter(|&(position, kept)| kept < 1.0 && occluded(position))
+            .collect();
+
+        for (position, kept) in shadowed {
+            let (x, y) = ((position % width) as u32, (position / width) as u32);
+            let c = self.target.get_pixel(x, y);
+            // the target is gamma corrected
+            let rgb = vec3(c[0] as f32, c[1] as f32, c[2] as f32) * kept.sqrt();
+            let rgb = Color::from([rgb.x() as u8, rgb.y() as u8, rgb.z() as u8]);
+            self.target.put_pixel(x, y, rgb);
+            self.hdr[position] *= kept;
+        }
+    }
+
+    // run render followed by the screen-space effects, blended as a single pass
+    fn pass<F: FnOnce(&mut Self)>(&mut self, render: F) {
+        self.blended(|renderer| {
+            render(renderer);
+            renderer.shade_contacts();
+            renderer.reflect();
+        });
+    }
+
+    // tiles covering raster_bounds, see set_tile_size
+    fn tiles(&self) -> Vec<Rect> {
+        let bounds = self.raster_bounds();
+        #[cfg(feature = "rayon")]
+        let threads = rayon::current_num_threads();
+        #[cfg(not(feature = "rayon"))]
+        let threads = 1;
+        let size = match self.tile_size {
+            Some(size) => size.max(1),
+            None if threads == 1 => bounds.width.max(bounds.height),
+            None => {
+                let tiles = (threads * self.bins_per_thread) as f32;
+                let area = (bounds.width * bounds.height) as f32;
+                ((area / tiles).sqrt().ceil() as usize).max(16)
+            }
+        };
+
+        let mut tiles = Vec::new();
+        for y in (bounds.y..bounds.y + bounds.height).step_by(size) {
+            for x in (bounds.x..bounds.x + bounds.width).step_by(size) {
+                let tile = Rect {
+                    x,
+                    y,
+                    width: size,
+                    height: size,
+                };
+                tiles.push(tile.intersect(&bounds));
+            }
+        }
+        tiles
+    }
+
+    // Run render for every tile with the scissor set to it, see set_tile_size
+    fn tiled<F: Fn(&mut Self) + Sync>(&mut self, render: F) {
+        let tiles = self.tiles();
+        if tiles.len() <= 1 {
+            return render(self);
+        }
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            // every thread draws every threads-th tile into its own copy of the renderer
+            let threads = rayon::current_num_threads().min(tiles.len());
+            if threads > 1 {
+                let share = |thread: usize| tiles.iter().copied().skip(thread).step_by(threads);
+                let copies: Vec<Renderer> = (0..threads)
+                    .into_par_iter()
+                    .map(|thread| {
+                        let mut copy = self.clone();
+                        copy.render_tiles(share(thread), &render);
+                        copy
+                    })
+                    .collect();
+                for (thread, copy) in copies.iter().enumerate() {
+                    for tile in share(thread) {
+                        self.copy_tile(copy, tile);
+                    }
+                }
+                let first = &copies[0];
+                self.objects = first.objects.clone();
+                self.lod_stats = first.lod_stats;
+                self.culling_stats = first.culling_stats;
+                return;
+            }
+        }
+
+        self.render_tiles(tiles.into_iter(), &render);
+    }
+
+    fn render_tiles<F: Fn(&mut Self)>(&mut self, tiles: impl Iterator<Item = Rect>, render: &F) {
+        // every tile draws the whole scene, its objects and stats count once
+        let (objects, lod_stats, culling_stats) =
+            (self.objects.len(), self.lod_stats, self.culling_stats);
+        let scissor = self.scissor;
+        for tile in tiles {
+            self.objects.truncate(objects);
+            self.lod_stats = lod_stats;
+            self.culling_stats = culling_stats;
+            self.scissor = Some(tile);
+            render(self);
+        }
+        self.scissor = scissor;
+    }
+
+    // copy pixels of tile from every per-pixel buffer of other (of the same size)
+    #[cfg(feature = "rayon")]
+    fn copy_tile(&mut self, other: &Renderer, tile: Rect) {
+        fn copy<T: Clone>(to: &mut [T], from: &[T], rows: &[std::ops::Range<usize>]) {
+            for row in rows {
+                to[row.clone()].clone_from_slice(&from[row.clone()]);
+            }
+        }
+
+        let width = self.target.width() as usize;
+        let rows: Vec<_> = (tile.y..tile.y + tile.height)
+            .map(|y| y * width + tile.x..y * width + tile.x + tile.width)
+            .collect();
+        let pixels: Vec<_> = rows.iter().map(|row| row.start * 3..row.end * 3).collect();
+        copy(&mut self.target, &other.target, &pixels);
+        copy(&mut self.zbuffer, &other.zbuffer, &rows);
+        copy(&mut self.hdr, &other.hdr, &rows);
+        copy(&mut self.ids, &other.ids, &rows);
+        if let (Some(to), Some(from)) = (&mut self.uvw, &other.uvw) {
+            copy(to, from, &rows);
+        }
+        if let (Some(to), Some(from)) = (&mut self.triangles, &other.triangles) {
+            copy(to, from, &rows);
+        }
+        if let (Some(to), Some(from)) = (&mut self.tie_keys, &other.tie_keys) {
+            copy(to, from, &rows);
+        }
+        if let (Some(to), Some(from)) = (&mut self.ssr, &other.ssr) {
+            copy(&mut to.surfaces, &from.surfaces, &rows);
+        }
+        if let (Some(to), Some(from)) = (&mut self.contact_shadows, &other.contact_shadows) {
+            copy(&mut to.kept, &from.kept, &rows);
+        }
+    }
+
+    // Run render, with PassBlend::Depth directly in the buffers, otherwise in empty ones and
+    // blend the covered pixels with the previous contents afterwards
+    fn blended<F: FnOnce(&mut Self)>(&mut self, render: F) {
+        let blend = self.pass_blend;
+        if blend == PassBlend::Depth {
+            return render(self);
+        }
+
+        let (width, height) = self.size();
+        let size = width * height;
+        let empty = Image::new(width as u32, height as u32);
+        let target = std::mem::replace(&mut self.target, empty);
+        let zbuffer = std::mem::replace(&mut self.zbuffer, vec![f32::NEG_INFINITY; size]);
+        let hdr = std::mem::replace(&mut self.hdr, vec![Vec3::zero(); size]);
+        let ids = std::mem::replace(&mut self.ids, vec![0; size]);
+        render(self);
+
+        let pass = std::mem::replace(&mut self.target, target);
+        let pass_zbuffer = std::mem::replace(&mut self.zbuffer, zbuffer);
+        let pass_hdr = std::mem::replace(&mut self.hdr, hdr);
+        let pass_ids = std::mem::replace(&mut self.ids, ids);
+        for (i, (x, y, &color)) in pass.enumerate_pixels().enumerate() {
+            if pass_zbuffer[i] == f32::NEG_INFINITY {
+                // not covered by the pass
+                continue;
+            }
+
+            let previous = *self.target.get_pixel(x, y);
+            let to_vec = |c: Color| vec3(c[0] as f32, c[1] as f32, c[2] as f32);
+            let rgb = blend.apply(to_vec(previous), to_vec(color), 255.0);
+            // NOTE: float to int casts saturate
+            let color = Color::from([rgb.x() as u8, rgb.y() as u8, rgb.z() as u8]);
+            self.target.put_pixel(x, y, color);
+            self.hdr[i] = blend.apply(self.hdr[i], pass_hdr[i], 1.0);
+            self.zbuffer[i] = self.zbuffer[i].max(pass_zbuffer[i]);
+            self.ids[i] = pass_ids[i];
+        }
+    }
+
+    // Render scene from center into size x size faces of a cubemap (+X, -X, +Y, -Y, +Z, -Z) with
+    // 90 degree perspective cameras, e.g. to bake an environment map. Faces are seen from the
+    // inside with the top row first, Y is up on the side faces (so the horizontal cross is -X,
+    // -Z, +X, +Z) and -Z is down on +Y (up on -Y). Lights keep their world space directions.
+    // NOTE: the renderer's buffers and camera are restored afterwards, colors aren't graded.
+    // Triangles reaching behind a face's viewer are dropped, so tessellate geometry near center.
+    pub fn render_cubemap(&mut self, scene: &Scene, center: Vec3, size: usize) -> [Image; 6] {
+        let camera = self.camera.clone();
+        let light = self.light_direction;
+        let world_light = camera.to_world * light;
+        let lights = self.lights.clone();
+        let world_lights: Vec<Light> = lights
+            .iter()
+            .map(|light| Light {
+                direction: camera.to_world * light.direction,
+                ..*light
+            })
+            .collect();
+        let (width, height) = self.size();
+        let target = std::mem::replace(&mut self.target, Image::new(0, 0));
+        let zbuffer = std::mem::take(&mut self.zbuffer);
+        let hdr = std::mem::take(&mut self.hdr);
+        let ids = std::mem::take(&mut self.ids);
+        let objects = std::mem::take(&mut self.objects);
+        let uvw = self.uvw.take();
+        let triangles = self.triangles.take();
+        let tie_keys = self.tie_keys.take();
+        let ssr = self.ssr.take();
+        let contact_shadows = self.contact_shadows.take();
+        let pass_blend = std::mem::take(&mut self.pass_blend);
+
+        let mut face = |direction: Vec3, up: Vec3| -> Image {
+            // Camera::translation has the camera axes as columns, so to look along direction
+            // from center its rows have to be (right, up, -direction) instead
+            let right = direction.cross(up);
+            let rows = Mat3::from_cols(right, up, -direction).transpose();
+            let lookat = center + direction;
+            self.camera = Camera::perspective(
+                lookat - rows.z_axis(),
+                lookat,
+                rows.y_axis(),
+                std::f32::consts::FRAC_PI_2,
+            );
+            self.light_direction = self.camera.translate(world_light);
+            self.lights = world_lights
+                .iter()
+                .map(|light| Light {
+                    direction: self.camera.translate(light.direction),
+                    ..*light
+                })
+                .collect();
+            self.target = Image::new(size as u32, size as u32);
+            self.zbuffer = vec![f32::NEG_INFINITY; size * size];
+            self.hdr = vec![Vec3::zero(); size * size];
+            self.ids = vec![0; size * size];
+            self.tie_keys = tie_keys.as_ref().map(|_| vec![0; size * size]);
+            self.objects.clear();
+
+            self.render_scene(scene);
+            let mut face = std::mem::replace(&mut self.target, Image::new(0, 0));
+            image::imageops::flip_vertical_in_place(&mut face);
+            face
+        };
+
+        let faces = cubemap_faces().map(|(direction, up)| face(direction, up));
+
+        self.camera = camera;
+        self.light_direction = light;
+        self.lights = lights;
+        self.target = target;
+        self.zbuffer = zbuffer;
+        self.hdr = hdr;
+        self.ids = ids;
+        self.objects = objects;
+        self.uvw = uvw;
+        self.triangles = triangles;
+        self.tie_keys = tie_keys;
+        self.ssr = ssr;
+        self.contact_shadows = contact_shadows;
+        self.pass_blend = pass_blend;
+        debug_assert_eq!(self.size(), (width, height));
+        faces
+    }
+
+    // Render scene resolving transparency with depth peeling: transparent triangles are
+    // rasterized up to layers times, every time keeping the nearest fragment behind those from
+    // the previous pass, and the layers are composited front to back. Unlike sorting this
+    // handles intersecting triangles correctly.
+    pub fn render_depth_peeled(&mut self, scene: &Scene, layers: usize) {
+        self.pass(|renderer| renderer.depth_peeled(scene, layers));
+    }
+
+    fn depth_peeled(&mut self, scene: &Scene, layers: usize) {
+        let textures = scene_textures(scene);
+        self.shadow_pass(&scene_models(scene));
+        self.draw_instances(scene);
+        self.draw_opaque(&textures);
+        let transparent = std::mem::take(&mut self.transparent);
+        if transparent.is_empty() {
+            return;
+        }
+
+        // front to back compositing: color += transmittance * alpha * layer color
+        let size = self.zbuffer.len();
+        let mut color = vec![Vec3::zero(); size];
+        let mut linear = vec![Vec3::zero(); size];
+        let mut transmittance = vec![1.0f32; size];
+        // id and triangle index of the nearest layer
+        let mut id: Vec<Option<(u32, u32)>> = vec![None; size];
+
+        let mut front = vec![f32::INFINITY; size];
+        for _ in 0..layers {
+            self.peel = Some(Peel {
+                front,
+                depth: self.zbuffer.clone(),
+                color: vec![Vec3::zero(); size],
+                linear: vec![Vec3::zero(); size],
+                alpha: vec![0.0; size],
+                id: vec![0; size],
+                triangle: vec![0; size],
+            });
+            for triangle in &transparent {
+                self.fill(triangle, triangle.texture(&textures));
+            }
+
+            let peel = self.peel.take().unwrap();
+            let mut empty = true;
+            for i in 0..size {
+                let alpha = peel.alpha[i];
+                if peel.depth[i] == self.zbuffer[i] {
+                    // no fragment in this layer
+                    continue;
+                }
+
+                empty = false;
+                color[i] += peel.color[i] * (transmittance[i] * alpha);
+                linear[i] += peel.linear[i] * (transmittance[i] * alpha);
+                transmittance[i] *= 1.0 - alpha;
+                id[i].get_or_insert((peel.id[i], peel.triangle[i]));
+            }
+
+            front = peel.depth;
+            if empty {
+                break;
+            }
+        }
+
+        // and finally whatever is left over opaque geometry
+        let width = self.target.width() as usize;
+        for i in 0..size {
+            if let Some((id, triangle)) = id[i] {
+                let (x, y) = ((i % width) as u32, (i / width) as u32);
+                let prev = *self.target.get_pixel(x, y);
+                let blend = |c: f32, p: u8| (c + p as f32 * transmittance[i]) as u8;
+                let blended = Color::from([
+                    blend(color[i].x(), prev[0]),
+                    blend(color[i].y(), prev[1]),
+                    blend(color[i].z(), prev[2]),
+                ]);
+                self.target.put_pixel(x, y, blended);
+                self.hdr[i] = linear[i] + self.hdr[i] * transmittance[i];
+                self.ids[i] = id;
+                if let Some(triangles) = &mut self.triangles {
+                    triangles[i] = Some(triangle);
+                }
+            }
+        }
+    }
+
+    // render shadow maps of models (with their model to world transforms) if shadows are on
+    fn shadow_pass(&mut self, models: &[(&ObjSet, Mat4)]) {
+        self.shadow_maps.clear();
+        let size = match self.shadow_resolution {
+            Some(size) => size,
+            None => return,
+        };
+
+        // light directions are in camera space
+        let to_world = self.camera.to_world;
+        let (filter, mode) = (&self.group_filter, self.shadow_mode);
+        let key = ShadowMap::build(models, filter, to_world * self.light_direction, size, mode);
+        let others = self.lights.iter().map(|light| {
+            if light.casts_shadows {
+                ShadowMap::build(models, filter, to_world * light.direction, size, mode)
+            } else {
+                None
+            }
+        });
+        self.shadow_maps = std::iter::once(key).chain(others).collect();
+    }
+
+    // Displace objects of models if displacement is on, keeping the ones displaced for the
+    // previous models (until the displacement map or subdivision level changes) instead of
+    // displacing them again. The rest are dropped.
+    fn displace_models<'a>(&mut self, models: impl Iterator<Item = &'a ObjSet>) {
+        let (map, scale) = match &self.displacement {
+            Some(displacement) => displacement,
+            None => return,
+        };
+        let mut displaced = Vec::new();
+        for object in models.flat_map(|model| &model.objects) {
+            let previous = self
+                .displaced
+                .iter()
+                .find(|displaced| displaced.0 == *object);
+            displaced.push(match previous {
+                Some(previous) => previous.clone(),
+                None => {
+                    let level = self.subdivision_level;
+                    let object = (
+                        object.clone(),
+                        displace::displace(object, map, *scale, level),
+                    );
+                    Arc::new(object)
+                }
+            });
+        }
+        self.displaced = displaced;
+    }
+
+    // opaque triangles of every instance, transparent ones are deferred
+    fn draw_instances(&mut self, scene: &Scene) {
+        self.displace_models(scene.meshes.iter().map(|mesh| &mesh.model));
+        let visible: Vec<usize> = match self.culling {
+            Some(culling) => {
+                // displacement can move vertices out of the original bounds by up to its scale
+                let margin = self
+                    .displacement
+                    .as_ref()
+                    .map_or(0.0, |(_, scale)| scale.abs());
+                let frustum = Frustum::new(&self.camera);
+                let stats = &mut self.culling_stats;
+                let visible = match culling {
+                    Culling::Bvh(leaf_size) => {
+                        Bvh::build(scene, leaf_size, margin).visible(&frustum, stats)
+                    }
+                    Culling::Spheres => visible_spheres(scene, margin, &frustum, stats),
+                };
+                self.culling_stats.visible += visible.len();
+                self.culling_stats.culled += scene.instances.len() - visible.len();
+                visible
+            }
+            None => (0..scene.instances.len()).collect(),
+        };
+
+        let max_distance = self.lod.and_then(|lod| lod.max_distance);
+        for instance in visible.iter().map(|&i| &scene.instances[i]) {
+            if let Some(max_distance) = max_distance {
+                let origin = instance.transform.w_axis().truncate();
+                if (origin - self.camera.lookfrom).length() > max_distance {
+                    self.lod_stats.instances += 1;
+                    continue;
+                }
+            }
+
+            let mesh = &scene.meshes[instance.mesh];
+            self.set_model_transform(instance.transform);
+            let texture = mesh
+                .texture
+                .as_ref()
+                .map(|texture| (instance.mesh, texture));
+            self.draw(&mesh.model, texture);
+        }
+        self.set_model_transform(Mat4::identity());
+    }
+
+    fn draw(&mut self, model: &ObjSet, texture: Option<(usize, &Texture)>) {
+        let vertex_ao = std::mem::take(&mut self.vertex_ao);
+        let vertex_colors = std::mem::take(&mut self.vertex_colors);
+        let mut index = 0;
+        let mut group = None;
+        for object in &model.objects {
+            // see displace_models
+            let displaced = match self.displacement {
+                Some(_) => self
+                    .displaced
+                    .iter()
+                    .find(|displaced| displaced.0 == *object),
+                None => None,
+            }
+            .cloned();
+            let ao = match displaced {
+                Some(_) => None,
+                None => vertex_ao.get(&object.name).map(Vec::as_slice),
+            };
+            let colors = match (&displaced, self.shading) {
+                (None, ShadingMode::VertexColors) => {
+                    vertex_colors.get(&object.name).map(Vec::as_slice)
+                }
+                _ => None,
+            };
+            let object = displaced.as_ref().map_or(object, |displaced| &displaced.1);
+            self.pixel_uvs = match self.uv_interpretation {
+                UvInterpretation::Auto => object
+                    .tex_vertices
+                    .iter()
+                    .any(|v| v.u.max(v.v) > PIXEL_UV_THRESHOLD),
+                UvInterpretation::Normalized => false,
+                UvInterpretation::Pixels => true,
+            };
+
+            if self.grouping == GroupingMode::Objects {
+                self.objects.push(object.name.clone());
+            }
+            for geometry in &object.geometry {
+                let mut material = geometry
+                    .material_name
+                    .as_ref()
+                    .and_then(|name| self.materials.get(name))
+                    .cloned()
+                    .unwrap_or_default();
+                self.material_override.apply(&mut material);
+
+                for shape in &geometry.shapes {
+                    if !self.group_filter.includes(&shape.groups) {
+                        // keep triangle colors of the rest
+                        index += 1;
+                        continue;
+                    }
+                    if self.grouping == GroupingMode::Groups {
+                        // consecutive shapes of the same group share the id
+                        let name = match shape.groups.as_slice() {
+                            [] => "default".to_owned(),
+                            groups => groups.join(" "),
+                        };
+                        if group.as_ref() != Some(&name) {
+                            self.objects.push(name.clone());
+                            group = Some(name);
+                        }
+                    }
+
+                    // vertices missing AO (e.g. it's been read from another file) are not occluded
+                    let ao = match (&shape.primitive, ao) {
+                        (Primitive::Triangle((x, _, _), (y, _, _), (z, _, _)), Some(ao)) => {
+                            let ao = |i: usize| ao.get(i).copied().unwrap_or(1.0);
+                            vec3(ao(*x), ao(*y), ao(*z))
+                        }
+                        _ => Vec3::splat(1.0),
+                    };
+                    // and ones missing a color are white
+                    let colors = match (&shape.primitive, colors) {
+                        (Primitive::Triangle((x, _, _), (y, _, _), (z, _, _)), Some(colors)) => {
+                            let color = |i: usize| colors.get(i).copied().unwrap_or(Vec3::one());
+                            Some([color(*x), color(*y), color(*z)])
+                        }
+                        _ => None,
+                    };
+                    let recolored = shape
+                        .groups
+                        .iter()
+                        .find_map(|group| self.group_colors.get(group))
+                        .map(|&color| Material {
+                            color,
+                            ..material.clone()
+                        });
+                    self.primitive(
+                        index,
+                        &shape.primitive,
+                        object,
+                        texture,
+                        recolored.as_ref().unwrap_or(&material),
+                        ao,
+                        colors,
+                    );
+                    index += 1;
+                }
+            }
+        }
+        self.vertex_ao = vertex_ao;
+        self.vertex_colors = vertex_colors;
+    }
+}
+
+// textures of scene meshes, indexed the same way as Fill::Textured::texture
+fn scene_textures(scene: &Scene) -> Vec<Option<&Texture>> {
+    scene
+        .meshes
+        .iter()
+        .map(|mesh| mesh.texture.as_ref())
+        .collect()
+}
+
+// models of scene instances with their transforms
+fn scene_models(scene: &Scene) -> Vec<(&ObjSet, Mat4)> {
+    scene
+        .instances
+        .iter()
+        .map(|instance| (&scene.meshes[instance.mesh].model, instance.transform))
+        .collect()
+}
+
+// Processing applied to models by read_model
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoadOptions {
+    pub axes: AxisConvention,
+    // weld vertices closer than this (in model units) before computing normals
+    pub weld_tolerance: Option<f32>,
+    // levels of Loop subdivision (after welding), every level makes 4 times more triangles
+    pub loop_subdivisions: u32,
+    // of faces in computed (missing) normals
+    pub normal_weighting: NormalWeighting,
+    // Models with more triangles (after loop subdivision) and textures with more pixels than
+    // these fail to load before memory is allocated for them, e.g. to guard a server against
+    // malicious files. Textures of materials are replaced like missing ones, see
+    // Renderer::set_max_texture_pixels
+    pub max_triangles: Option<usize>,
+    pub max_texture_pixels: Option<u64>,
+}
+
+// Fail if model with triangles (before loop subdivision) would exceed options.max_triangles
+fn check_triangle_count(triangles: usize, options: &LoadOptions) -> Result<()> {
+    let max = match options.max_triangles {
+        Some(max) => max,
+        None => return Ok(()),
+    };
+    let levels = options.loop_subdivisions;
+    let subdivided = triangles.saturating_mul(4usize.saturating_pow(levels));
+    if subdivided > max {
+        if levels > 0 {
+            bail!(
+                "Model has {} triangles ({} after {} levels of subdivision), more than the limit of {}",
+                triangles,
+                subdivided,
+                levels,
+                max
+            );
+        }
+        bail!(
+            "Model has {} triangles, more than the limit of {}",
+            triangles,
+            max
+        );
+    }
+    Ok(())
+}
+
+// number of triangles faces of OBJ source are split into
+fn obj_triangle_count(source: &str) -> usize {
+    source
+        .lines()
+        .map(|line| {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("f") => words.count().saturating_sub(2),
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+fn prepare_model(model: &mut ObjSet, options: &LoadOptions) {
+    convert_axes(model, options.axes);
+    if let Some(tolerance) = options.weld_tolerance {
+        weld_vertices(model, tolerance);
+    }
+    loop_subdivide(model, options.loop_subdivisions);
+    compute_weighted_normals(model, options.normal_weighting);
+}
+
+// Point elements (`p 1 2 3`) as faces with a single corner each (`f 1`, `f 2` and `f 3` on
+// lines of their own), which wavefront_obj reads as points (but not `p` elements). Also returns
+// the number of the input line every output line comes from, see source_line.
+fn points_as_faces(input: &str) -> (String, Vec<usize>) {
+    let mut output = String::with_capacity(input.len());
+    let mut line_numbers = Vec::new();
+    for (number, line) in input.lines().enumerate() {
+        let mut words = line.split_whitespace();
+        if words.next() == Some("p") {
+            for point in words {
+                output.push_str(&format!("f {}\n", point));
+                line_numbers.push(number + 1);
+            }
+        } else {
+            output.push_str(line);
+            output.push('\n');
+            line_numbers.push(number + 1);
+        }
+    }
+    (output, line_numbers)
+}
+
+// number of the input line of points_as_faces output line number
+fn source_line(line_numbers: &[usize], number: usize) -> usize {
+    line_numbers
+        .get(number.wrapping_sub(1))
+        .copied()
+        .unwrap_or(number)
+}
+
+pub fn read_model(path: &str, options: &LoadOptions) -> Result<ObjSet> {
+    let model = ao::strip_vertex_colors(&std::fs::read_to_string(path)?);
+    // counted before points become (single corner) faces
+    check_triangle_count(obj_triangle_count(&model), options)?;
+    let (model, line_numbers) = points_as_faces(&model);
+    let mut model = obj::parse(&model).map_err(|e| {
+        let line = source_line(&line_numbers, e.line_number);
+        anyhow!("Failed to parse line #{}: {}", line, e.message)
+    })?;
+    prepare_model(&mut model, options);
+    Ok(model)
+}
+
+// Line that was skipped by read_model_lenient
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedLine {
+    pub line_number: usize,
+    pub content: String,
+    pub message: String,
+}
+
+// Like read_model, but skips (up to max_errors) lines that fail to parse instead of rejecting
+// the whole file. The OBJ parser can't resume after an error, so the file is parsed again after
+// every skipped line: max_errors bounds the time it takes too, (max_errors + 1) times that of
+// read_model.
+pub fn read_model_lenient(
+    path: &str,
+    options: &LoadOptions,
+    max_errors: usize,
+) -> Result<(ObjSet, Vec<SkippedLine>)> {
+    let mut source = ao::strip_vertex_colors(&std::fs::read_to_string(path)?);
+    check_triangle_count(obj_triangle_count(&source), options)?;
+    if !source.ends_with('\n') {
+        source.push('\n');
+    }
+    // byte ranges of lines (without line breaks)
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for (end, _) in source.match_indices('\n') {
+        lines.push(start..end);
+        start = end + 1;
+    }
+    let mut skipped = Vec::new();
+
+    loop {
+        let (faces, line_numbers) = points_as_faces(&source);
+        let mut e = match obj::parse(&faces) {
+            Ok(mut model) => {
+                prepare_model(&mut model, options);
+                return Ok((model, skipped));
+            }
+            Err(e) => e,
+        };
+        e.line_number = source_line(&line_numbers, e.line_number);
+
+        // blank out the offending line (to keep line numbers and offsets intact) and try again
+        let line = match lines.get(e.line_number.wrapping_sub(1)) {
+            Some(line) if !source[line.clone()].trim().is_empty() => line.clone(),
+            _ => bail!("Failed to parse line #{}: {}", e.line_number, e.message),
+        };
+
+        if skipped.len() == max_errors {
+            bail!(
+                "Too many parse errors ({} lines skipped), line #{}: {}",
+                skipped.len(),
+                e.line_number,
+                e.message
+            );
+        }
+
+        skipped.push(SkippedLine {
+            line_number: e.line_number,
+            content: source[line.clone()].trim_end_matches('\r').to_owned(),
+            message: e.message,
+        });
+        let blank = " ".repeat(line.len());
+        source.replace_range(line, &blank);
+    }
+}
+
+pub fn read_texture(path: &str) -> Result<Texture> {
+    let mut texture = image::open(path)?.to_rgb8();
+    image::imageops::flip_vertical_in_place(&mut texture);
+    Ok(texture)
+}
+
+// Error of read_texture_limited for textures with more pixels than the limit, which can be told
+// apart from other errors by downcasting to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureTooLarge {
+    pub width: u32,
+    pub height: u32,
+    pub max_pixels: u64,
+}
+
+impl std::fmt::Display for TextureTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Texture is {}x{} ({} pixels), more than the limit of {}",
+            self.width,
+            self.height,
+            self.width as u64 * self.height as u64,
+            self.max_pixels
+        )
+    }
+}
+
+impl std::error::Error for TextureTooLarge {}
+
+// Material texture replaced by the missing texture fallback, see Renderer::set_materials
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaterialWarning {
+    // couldn't be read, e.g. there's no such file or it's not an image
+    Unreadable {
+        material: String,
+        path: String,
+        message: String,
+    },
+    // has more pixels than allowed, see Renderer::set_max_texture_pixels
+    TooLarge {
+        material: String,
+        path: String,
+        size: TextureTooLarge,
+    },
+}
+
+impl std::fmt::Display for MaterialWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MaterialWarning::Unreadable {
+                material,
+                path,
+                message,
+            } => write!(
+                f,
+                "Could not read texture {:?} of material {:?}, using the fallback: {}",
+                path, material, message
+            ),
+            MaterialWarning::TooLarge {
+                material,
+                path,
+                size,
+            } => write!(
+                f,
+                "Texture {:?} of material {:?} is too large, using the fallback: {}",
+                path, material, size
+            ),
+        }
+    }
+}
+
+// Like read_texture, but fails with TextureTooLarge (before decoding it) if the image has more
+// than max_pixels pixels
+pub fn read_texture_limited(path: &str, max_pixels: Option<u64>) -> Result<Texture> {
+    if let Some(max_pixels) = max_pixels {
+        let (width, height) = image::image_dimensions(path)?;
+        if width as u64 * height as u64 > max_pixels {
+            return Err(TextureTooLarge {
+                width,
+                height,
+                max_pixels,
+            }
+            .into());
+        }
+    }
+    read_texture(path)
+}
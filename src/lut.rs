@@ -0,0 +1,128 @@
+use anyhow::{anyhow, bail, Result};
+use glam::{vec3, Vec3};
+
+use crate::Color;
+
+// 3D color lookup table, maps colors in [domain_min; domain_max] to graded ones
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ColorLut {
+    size: usize,
+    // size^3 entries, red index is changing the fastest
+    table: Vec<Vec3>,
+    domain_min: Vec3,
+    domain_max: Vec3,
+}
+
+impl ColorLut {
+    // read LUT in Adobe/Resolve .cube format
+    pub(crate) fn read(path: &str) -> Result<Self> {
+        let lut = std::fs::read_to_string(path)?;
+        Self::parse(&lut)
+    }
+
+    fn parse(input: &str) -> Result<Self> {
+        let mut size = None;
+        let mut table = Vec::new();
+        let mut domain_min = Vec3::zero();
+        let mut domain_max = Vec3::splat(1.0);
+
+        for (i, line) in input.lines().enumerate() {
+            let error = |message: &str| anyhow!("Failed to parse line #{}: {}", i + 1, message);
+            let line = line.split('#').next().unwrap_or("").trim();
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let keyword = match words.first() {
+                Some(&keyword) => keyword,
+                None => continue,
+            };
+
+            // three numbers starting with words[start]
+            let triple = |start: usize| -> Result<Vec3> {
+                let number = |i: usize| -> Result<f32> {
+                    words
+                        .get(i)
+                        .and_then(|word| word.parse().ok())
+                        .ok_or_else(|| error("Expected a number"))
+                };
+                Ok(vec3(number(start)?, number(start + 1)?, number(start + 2)?))
+            };
+
+            match keyword {
+                "TITLE" => {}
+                "LUT_3D_SIZE" => {
+                    let n: usize = words
+                        .get(1)
+                        .and_then(|word| word.parse().ok())
+                        .ok_or_else(|| error("Expected LUT size"))?;
+                    if !(2..=256).contains(&n) {
+                        return Err(error("LUT size should be in [2; 256] range"));
+                    }
+                    size = Some(n);
+                }
+                "LUT_1D_SIZE" => return Err(error("1D LUTs are not supported")),
+                "DOMAIN_MIN" => domain_min = triple(1)?,
+                "DOMAIN_MAX" => domain_max = triple(1)?,
+                _ if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                    // unknown keyword, skip it
+                }
+                _ => {
+                    if size.is_none() {
+                        return Err(error("LUT data before LUT_3D_SIZE"));
+                    }
+                    table.push(triple(0)?);
+                }
+            }
+        }
+
+        let size = match size {
+            Some(size) => size,
+            None => bail!("LUT_3D_SIZE is missing"),
+        };
+        if table.len() != size * size * size {
+            bail!(
+                "Expected {} LUT entries, got {}",
+                size * size * size,
+                table.len()
+            );
+        }
+        if (domain_max - domain_min).min_element() <= 0.0 {
+            bail!("DOMAIN_MAX should be greater than DOMAIN_MIN");
+        }
+
+        Ok(ColorLut {
+            size,
+            table,
+            domain_min,
+            domain_max,
+        })
+    }
+
+    fn entry(&self, r: usize, g: usize, b: usize) -> Vec3 {
+        self.table[r + (g + b * self.size) * self.size]
+    }
+
+    // trilinearly interpolated graded color
+    pub(crate) fn apply(&self, color: Vec3) -> Vec3 {
+        let max = (self.size - 1) as f32;
+        let p = (color - self.domain_min) / (self.domain_max - self.domain_min);
+        let p = p.max(Vec3::zero()).min(Vec3::splat(1.0)) * max;
+
+        // lower corner of the cell p is in, and position within it
+        let cell = |x: f32| (x.floor() as usize).min(self.size - 2);
+        let (r, g, b) = (cell(p.x()), cell(p.y()), cell(p.z()));
+        let t = p - vec3(r as f32, g as f32, b as f32);
+
+        let lerp = |a: Vec3, b: Vec3, t: f32| a + (b - a) * t;
+        let plane = |b: usize| {
+            let g0 = lerp(self.entry(r, g, b), self.entry(r + 1, g, b), t.x());
+            let g1 = lerp(self.entry(r, g + 1, b), self.entry(r + 1, g + 1, b), t.x());
+            lerp(g0, g1, t.y())
+        };
+        lerp(plane(b), plane(b + 1), t.z())
+    }
+
+    pub(crate) fn apply_color(&self, color: Color) -> Color {
+        let graded = self.apply(vec3(color[0] as f32, color[1] as f32, color[2] as f32) / 255.0);
+        let to_u8 = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Color::from([to_u8(graded.x()), to_u8(graded.y()), to_u8(graded.z())])
+    }
+}
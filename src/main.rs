@@ -1,282 +1,736 @@
+use std::convert::TryInto;
 use std::time::Instant;
 
-use anyhow::{anyhow, Context, Result};
-use glam::{vec2, vec3, Mat3, Vec2, Vec3};
-use wavefront_obj::obj::{self, ObjSet, Primitive, TVertex, Vertex};
-
-type Image = image::RgbImage;
-type Color = image::Rgb<u8>;
-type Texture = Image;
+use anyhow::{anyhow, bail, Context, Result};
+use glam::{vec3, Vec3, Vec4};
+
+use tinyrenderer::{
+    morph, read_materials, read_model, read_model_materials, read_texture_limited, read_vertex_ao,
+    read_vertex_colors, render_uv_layout, AmbientSh, AoSource, Bloom, Colormap, DepthOfField,
+    EnvironmentMap, GroupFilter, GroupingMode, Handedness, Light, Lod, NormalMapSpace, NormalSpace,
+    NormalWeighting, PassBlend, RenderConfig, Renderer, ResolveFilter, Scene, ShadingMode,
+    ShadowMode, Texture, TextureExt, UvInterpretation, Vignette,
+};
+
+const USAGE: &str = "Usage: tinyrenderer [bench [--frames N]]
+                    [--config FILE] [--save-config FILE] [--model FILE]
+                    [--texture FILE] [--no-texture] [--materials FILE] [--output FILE]
+                    [--lut FILE] [--size WIDTHxHEIGHT]
+                    [--shading lit|triangle_colors|vertex_colors|normals]
+                    [--normal-space view|world]
+                    [--vertex-ao alpha|red|green|blue] [--normal-map FILE]
+                    [--normal-map-space tangent|object] [--shadows SIZE] [--pcf SIZE]
+                    [--variance-shadows BLUR]
+                    [--override-color R,G,B] [--override-specular R,G,B]
+                    [--override-shininess EXPONENT] [--weld TOLERANCE]
+                    [--displacement FILE] [--displacement-scale SCALE]
+                    [--subdivisions LEVEL] [--loop-subdivisions LEVELS]
+                    [--grouping objects|groups] [--vignette STRENGTH]
+                    [--bloom INTENSITY] [--bloom-threshold THRESHOLD]
+                    [--bloom-radius PIXELS] [--ambient-sky R,G,B]
+                    [--ambient-ground R,G,B] [--min-triangle-area PIXELS]
+                    [--max-distance DISTANCE] [--depth-peeling LAYERS]
+                    [--frustum-culling LEAF_SIZE] [--sphere-culling]
+                    [--tile-size PIXELS] [--bins-per-thread COUNT]
+                    [--paint-over FILE]
+                    [--blend depth|over|add|multiply] [--blend-opacity OPACITY]
+                    [--morph-target FILE] [--morph-weight T] [--tint R,G,B]
+                    [--light X,Y,Z] [--only-group NAME] [--hide-group NAME]
+                    [--group-color NAME=R,G,B] [--missing-texture-color R,G,B]
+                    [--ssr STEPS] [--save-ids FILE] [--save-depth FILE] [--double-sided]
+                    [--dilate TEXELS] [--supersampling FACTOR]
+                    [--resolve-filter box|tent] [--alpha-to-coverage]
+                    [--dof FOCUS,APERTURE]
+                    [--normal-weighting uniform|area|angle]
+                    [--sizes WIDTHxHEIGHT,WIDTHxHEIGHT...] [--handedness right|left]
+                    [--auto-exposure LUMINANCE] [--max-triangles COUNT]
+                    [--max-texture-pixels COUNT] [--contact-shadows LENGTH,STEPS]
+                    [--uvs auto|normalized|pixels] [--clip-plane A,B,C,D]
+                    [--high-precision] [--save-uv-layout FILE] [--point-size PIXELS]
+                    [--point-attenuation] [--point-sprite FILE]
+                    [--ambient-environment +X,-X,+Y,-Y,+Z,-Z] [--ambient-strength SCALE]
+                    [--depth-colormap viridis|jet]";
+
+// R,G,B color with components in [0; 255] range
+fn parse_color(color: &str) -> Result<Vec3> {
+    let components: Vec<&str> = color.split(',').collect();
+    let component = |i: usize| -> Result<f32> {
+        let c: u8 = components[i].trim().parse()?;
+        Ok(c as f32 / 255.0)
+    };
+
+    if components.len() != 3 {
+        bail!("Invalid color {:?}, expected R,G,B", color);
+    }
+    Ok(vec3(component(0)?, component(1)?, component(2)?))
+}
 
-#[inline(always)]
-fn min(a: f32, b: f32) -> f32 {
-    if a > b {
-        b
-    } else {
-        a
+// X,Y,Z vector of floats, e.g. gains 1.1,1.0,0.9 or a direction
+fn parse_vec3(v: &str) -> Result<Vec3> {
+    let components = v
+        .split(',')
+        .map(|c| c.trim().parse())
+        .collect::<Result<Vec<f32>, _>>()
+        .with_context(|| format!("Invalid vector {:?}", v))?;
+    match components[..] {
+        [x, y, z] => Ok(vec3(x, y, z)),
+        _ => bail!("Invalid vector {:?}, expected X,Y,Z", v),
     }
 }
 
-#[inline(always)]
-fn max(a: f32, b: f32) -> f32 {
-    if a > b {
-        a
-    } else {
-        b
+// A,B,C,D plane a * x + b * y + c * z + d = 0
+fn parse_plane(plane: &str) -> Result<Vec4> {
+    let components = plane
+        .split(',')
+        .map(|c| c.trim().parse())
+        .collect::<Result<Vec<f32>, _>>()
+        .with_context(|| format!("Invalid plane {:?}", plane))?;
+    match components[..] {
+        [a, b, c, d] => Ok(Vec4::new(a, b, c, d)),
+        _ => bail!("Invalid plane {:?}, expected A,B,C,D", plane),
     }
 }
 
-#[inline(always)]
-fn barycentric(a: Vec2, b: Vec2, c: Vec2, p: Vec2) -> Vec3 {
-    let xs = vec3(c.x() - a.x(), b.x() - a.x(), a.x() - p.x());
-    let ys = vec3(c.y() - a.y(), b.y() - a.y(), a.y() - p.y());
-    let u = xs.cross(ys);
-    // (u.y/u.z, u.x/u.z) are coordinates in (a, ab, ac) basis
-    // TODO: why are we dividing by z here?
-    vec3(1.0 - (u.x() + u.y()) / u.z(), u.y() / u.z(), u.x() / u.z())
+// WIDTHxHEIGHT
+fn parse_size(size: &str) -> Result<(usize, usize)> {
+    let (width, height) = size
+        .trim()
+        .split_once('x')
+        .ok_or_else(|| anyhow!("Invalid size {:?}, expected WIDTHxHEIGHT", size))?;
+    Ok((
+        width.parse().context("Invalid width")?,
+        height.parse().context("Invalid height")?,
+    ))
 }
 
-// Invoke function f for every point in triangle abc
-fn in_triangle<F>(a: Vec2, b: Vec2, c: Vec2, mut f: F)
-where
-    F: FnMut(usize, usize, Vec3),
-{
-    let min_x = min(a.x(), min(b.x(), c.x())) as usize;
-    let min_y = min(a.y(), min(b.y(), c.y())) as usize;
+// path with the suffix appended to the file name, e.g. target_256x256.png
+fn suffixed_path(path: &str, suffix: &str) -> String {
+    let path = std::path::Path::new(path);
+    let mut name = format!(
+        "{}_{}",
+        path.file_stem().unwrap_or_default().to_string_lossy(),
+        suffix
+    );
+    if let Some(extension) = path.extension() {
+        name = format!("{}.{}", name, extension.to_string_lossy());
+    }
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
 
-    let max_x = max(a.x(), max(b.x(), c.x())) as usize;
-    let max_y = max(a.y(), max(b.y(), c.y())) as usize;
+struct Args {
+    config: RenderConfig,
+    save_config: Option<String>,
+    // where to save the colorized object ids and linear depth next to the image
+    save_ids: Option<String>,
+    save_depth: Option<String>,
+    // false colors of the saved depth instead of grayscale
+    depth_colormap: Option<Colormap>,
+    // where to save the UV layout of the model, texture sized
+    save_uv_layout: Option<String>,
+    // number of frames to render with `bench` instead of saving a single one
+    bench_frames: Option<usize>,
+}
 
-    for y in min_y..(max_y + 1) {
-        for x in min_x..(max_x + 1) {
-            let p = vec2(x as f32, y as f32);
-            let bc = barycentric(a, b, c, p);
-            if bc.x() < 0.0 || bc.y() < 0.0 || bc.z() < 0.0 {
+// --config is applied first, so the rest of flags override values from the file
+fn parse_args() -> Result<Args> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let value = |i: usize| {
+        args.get(i + 1)
+            .cloned()
+            .ok_or_else(|| anyhow!("Missing value for {}\n{}", args[i], USAGE))
+    };
+
+    let mut config = match args.iter().position(|arg| arg == "--config") {
+        Some(i) => RenderConfig::load(&value(i)?).context("Could not read config")?,
+        None => RenderConfig::default(),
+    };
+
+    let mut save_config = None;
+    let mut save_ids = None;
+    let mut save_depth = None;
+    let mut depth_colormap = None;
+    let mut save_uv_layout = None;
+    let (mut sky, mut ground) = (None, None);
+    let mut bench_frames = None;
+    let mut i = 0;
+    if args.first().is_some_and(|arg| arg == "bench") {
+        bench_frames = Some(100);
+        i = 1;
+    }
+    while i < args.len() {
+        match args[i].as_str() {
+            "--config" => {}
+            "--save-config" => save_config = Some(value(i)?),
+            "--save-ids" => save_ids = Some(value(i)?),
+            "--save-depth" => save_depth = Some(value(i)?),
+            "--depth-colormap" => {
+                depth_colormap = Some(match value(i)?.as_str() {
+                    "viridis" => Colormap::Viridis,
+                    "jet" => Colormap::Jet,
+                    colormap => bail!("Unknown colormap {:?}", colormap),
+                })
+            }
+            "--save-uv-layout" => save_uv_layout = Some(value(i)?),
+            "--frames" if bench_frames.is_some() => {
+                bench_frames = Some(value(i)?.parse().context("Invalid number of frames")?)
+            }
+            "--model" => config.model = value(i)?,
+            "--texture" => config.texture = Some(value(i)?),
+            "--dilate" => {
+                config.texture_dilation = value(i)?.parse().context("Invalid texture dilation")?
+            }
+            "--no-texture" => {
+                config.texture = None;
+                i += 1;
                 continue;
             }
-
-            f(x, y, bc);
+            "--materials" => config.materials = Some(value(i)?),
+            "--output" => config.output = value(i)?,
+            "--lut" => config.color_lut = Some(value(i)?),
+            "--bloom" => {
+                let intensity = value(i)?.parse().context("Invalid bloom intensity")?;
+                config.bloom.get_or_insert_with(Bloom::default).intensity = intensity;
+            }
+            "--bloom-threshold" => {
+                let threshold = value(i)?.parse().context("Invalid bloom threshold")?;
+                config.bloom.get_or_insert_with(Bloom::default).threshold = threshold;
+            }
+            "--bloom-radius" => {
+                let radius = value(i)?.parse().context("Invalid bloom radius")?;
+                config.bloom.get_or_insert_with(Bloom::default).radius = radius;
+            }
+            "--dof" => {
+                let (focus_distance, aperture) = value(i)?
+                    .split_once(',')
+                    .and_then(|(f, a)| Some((f.trim().parse().ok()?, a.trim().parse().ok()?)))
+                    .ok_or_else(|| anyhow!("Invalid depth of field, expected FOCUS,APERTURE"))?;
+                config.dof = Some(DepthOfField {
+                    focus_distance,
+                    aperture,
+                });
+            }
+            "--contact-shadows" => {
+                let contact_shadows = value(i)?
+                    .split_once(',')
+                    .and_then(|(l, s)| Some((l.trim().parse().ok()?, s.trim().parse().ok()?)))
+                    .ok_or_else(|| anyhow!("Invalid contact shadows, expected LENGTH,STEPS"))?;
+                config.contact_shadows = Some(contact_shadows);
+            }
+            "--vignette" => {
+                let strength = value(i)?.parse().context("Invalid vignette strength")?;
+                let vignette = config.vignette.get_or_insert_with(Vignette::default);
+                vignette.strength = strength;
+            }
+            "--auto-exposure" => {
+                let target = value(i)?.parse().context("Invalid auto exposure target")?;
+                config.auto_exposure = Some(target);
+            }
+            "--tint" => config.tint = Some(parse_vec3(&value(i)?).context("Invalid tint")?),
+            "--light" => {
+                let direction = parse_vec3(&value(i)?).context("Invalid light direction")?;
+                config.lights.push(Light {
+                    direction,
+                    intensity: 1.0,
+                    casts_shadows: true,
+                });
+            }
+            "--clip-plane" => config.clip_planes.push(parse_plane(&value(i)?)?),
+            "--min-triangle-area" => {
+                let area = value(i)?.parse().context("Invalid triangle area")?;
+                config
+                    .lod
+                    .get_or_insert_with(Lod::default)
+                    .min_triangle_area = area;
+            }
+            "--max-distance" => {
+                let distance = value(i)?.parse().context("Invalid distance")?;
+                config.lod.get_or_insert_with(Lod::default).max_distance = Some(distance);
+            }
+            "--depth-peeling" => {
+                let layers = value(i)?.parse().context("Invalid number of layers")?;
+                config.depth_peeling_layers = Some(layers);
+            }
+            "--frustum-culling" => {
+                let leaf_size = value(i)?.parse().context("Invalid BVH leaf size")?;
+                config.frustum_culling = Some(leaf_size);
+            }
+            "--sphere-culling" => {
+                config.sphere_culling = true;
+                i += 1;
+                continue;
+            }
+            "--tile-size" => {
+                config.tile_size = Some(value(i)?.parse().context("Invalid tile size")?)
+            }
+            "--bins-per-thread" => {
+                config.bins_per_thread = value(i)?
+                    .parse()
+                    .context("Invalid number of bins per thread")?
+            }
+            "--paint-over" => config.paint_over = Some(value(i)?),
+            "--blend" => {
+                config.blend = match value(i)?.as_str() {
+                    "depth" => PassBlend::Depth,
+                    "over" => PassBlend::Over { opacity: 0.5 },
+                    "add" => PassBlend::Add,
+                    "multiply" => PassBlend::Multiply,
+                    mode => bail!("Unknown blend mode {:?}", mode),
+                }
+            }
+            "--blend-opacity" => {
+                let opacity = value(i)?.parse().context("Invalid opacity")?;
+                config.blend = PassBlend::Over { opacity };
+            }
+            "--morph-target" => config.morph_target = Some(value(i)?),
+            "--morph-weight" => {
+                config.morph_weight = value(i)?.parse().context("Invalid morph weight")?
+            }
+            "--size" => (config.width, config.height) = parse_size(&value(i)?)?,
+            "--sizes" => {
+                config.sizes = value(i)?
+                    .split(',')
+                    .map(parse_size)
+                    .collect::<Result<_>>()?
+            }
+            "--supersampling" => {
+                config.supersampling = value(i)?.parse().context("Invalid supersampling")?
+            }
+            "--resolve-filter" => {
+                config.resolve_filter = match value(i)?.as_str() {
+                    "box" => ResolveFilter::Box,
+                    "tent" => ResolveFilter::Tent,
+                    filter => bail!("Unknown resolve filter {:?}", filter),
+                }
+            }
+            "--shading" => {
+                config.shading = match value(i)?.as_str() {
+                    "lit" => ShadingMode::Lit,
+                    "triangle_colors" => ShadingMode::TriangleColors,
+                    "vertex_colors" => ShadingMode::VertexColors,
+                    "normals" => ShadingMode::Normals,
+                    mode => bail!("Unknown shading mode {:?}", mode),
+                }
+            }
+            "--normal-space" => {
+                config.normal_space = match value(i)?.as_str() {
+                    "view" => NormalSpace::View,
+                    "world" => NormalSpace::World,
+                    space => bail!("Unknown normal space {:?}", space),
+                }
+            }
+            "--grouping" => {
+                config.grouping = match value(i)?.as_str() {
+                    "objects" => GroupingMode::Objects,
+                    "groups" => GroupingMode::Groups,
+                    mode => bail!("Unknown grouping mode {:?}", mode),
+                }
+            }
+            // both can be repeated, but not mixed
+            "--only-group" | "--hide-group" => {
+                let group = value(i)?;
+                match (&mut config.groups, args[i].as_str()) {
+                    (GroupFilter::All, "--only-group") => {
+                        config.groups = GroupFilter::Only(vec![group])
+                    }
+                    (GroupFilter::All, _) => config.groups = GroupFilter::Except(vec![group]),
+                    (GroupFilter::Only(groups), "--only-group")
+                    | (GroupFilter::Except(groups), "--hide-group") => groups.push(group),
+                    _ => bail!("--only-group and --hide-group can't be combined"),
+                }
+            }
+            "--group-color" => {
+                let arg = value(i)?;
+                let (group, color) = arg
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("Invalid group color {:?}, expected NAME=R,G,B", arg))?;
+                config
+                    .group_colors
+                    .insert(group.to_owned(), parse_color(color)?);
+            }
+            "--vertex-ao" => {
+                config.vertex_ao = Some(match value(i)?.as_str() {
+                    "alpha" => AoSource::Alpha,
+                    "red" => AoSource::Red,
+                    "green" => AoSource::Green,
+                    "blue" => AoSource::Blue,
+                    source => bail!("Unknown AO source {:?}", source),
+                })
+            }
+            "--normal-map" => config.normal_map = Some(value(i)?),
+            "--normal-map-space" => {
+                config.normal_map_space = match value(i)?.as_str() {
+                    "tangent" => NormalMapSpace::Tangent,
+                    "object" => NormalMapSpace::Object,
+                    space => bail!("Unknown normal map space {:?}", space),
+                }
+            }
+            "--displacement" => config.displacement_map = Some(value(i)?),
+            "--displacement-scale" => {
+                config.displacement_scale =
+                    value(i)?.parse().context("Invalid displacement scale")?
+            }
+            "--subdivisions" => {
+                config.subdivision_level = value(i)?.parse().context("Invalid subdivision level")?
+            }
+            "--shadows" => {
+                config.shadow_map_size = Some(value(i)?.parse().context("Invalid shadow map size")?)
+            }
+            "--double-sided" => {
+                config.double_sided = true;
+                i += 1;
+                continue;
+            }
+            "--alpha-to-coverage" => {
+                config.alpha_to_coverage = true;
+                i += 1;
+                continue;
+            }
+            "--high-precision" => {
+                config.high_precision = true;
+                i += 1;
+                continue;
+            }
+            "--point-size" => {
+                config.point_size = value(i)?.parse().context("Invalid point size")?
+            }
+            "--point-attenuation" => {
+                config.point_attenuation = true;
+                i += 1;
+                continue;
+            }
+            "--point-sprite" => config.point_sprite = Some(value(i)?),
+            "--ssr" => config.ssr_steps = Some(value(i)?.parse().context("Invalid SSR steps")?),
+            "--pcf" => config.pcf_kernel_size = value(i)?.parse().context("Invalid PCF size")?,
+            "--variance-shadows" => {
+                let blur = value(i)?
+                    .parse()
+                    .context("Invalid variance shadow map blur")?;
+                config.shadow_mode = ShadowMode::Variance { blur };
+            }
+            "--override-color" => config.material_override.color = Some(parse_color(&value(i)?)?),
+            "--missing-texture-color" => {
+                config.missing_texture_color = Some(parse_color(&value(i)?)?)
+            }
+            "--override-specular" => {
+                config.material_override.specular = Some(parse_color(&value(i)?)?)
+            }
+            "--override-shininess" => {
+                let shininess = value(i)?.parse().context("Invalid shininess")?;
+                config.material_override.shininess = Some(shininess)
+            }
+            "--ambient-sky" => sky = Some(parse_color(&value(i)?)?),
+            "--ambient-ground" => ground = Some(parse_color(&value(i)?)?),
+            "--ambient-environment" => {
+                let faces: Vec<String> = value(i)?.split(',').map(String::from).collect();
+                if faces.len() != 6 {
+                    bail!("Invalid environment {:?}, expected 6 faces", faces);
+                }
+                config.ambient_environment = Some(faces)
+            }
+            "--ambient-strength" => {
+                config.ambient_strength = value(i)?.parse().context("Invalid ambient strength")?
+            }
+            "--weld" => {
+                config.load.weld_tolerance = Some(value(i)?.parse().context("Invalid tolerance")?)
+            }
+            "--loop-subdivisions" => {
+                config.load.loop_subdivisions =
+                    value(i)?.parse().context("Invalid subdivision level")?
+            }
+            "--max-triangles" => {
+                config.load.max_triangles =
+                    Some(value(i)?.parse().context("Invalid triangle count")?)
+            }
+            "--max-texture-pixels" => {
+                config.load.max_texture_pixels =
+                    Some(value(i)?.parse().context("Invalid pixel count")?)
+            }
+            "--normal-weighting" => {
+                config.load.normal_weighting = match value(i)?.as_str() {
+                    "uniform" => NormalWeighting::Uniform,
+                    "area" => NormalWeighting::Area,
+                    "angle" => NormalWeighting::Angle,
+                    weighting => bail!("Unknown normal weighting {:?}", weighting),
+                }
+            }
+            "--handedness" => {
+                config.camera.handedness = match value(i)?.as_str() {
+                    "right" => Handedness::RightHanded,
+                    "left" => Handedness::LeftHanded,
+                    handedness => bail!("Unknown handedness {:?}", handedness),
+                }
+            }
+            "--uvs" => {
+                config.uv_interpretation = match value(i)?.as_str() {
+                    "auto" => UvInterpretation::Auto,
+                    "normalized" => UvInterpretation::Normalized,
+                    "pixels" => UvInterpretation::Pixels,
+                    uvs => bail!("Unknown UV interpretation {:?}", uvs),
+                }
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            arg => bail!("Unknown argument {:?}\n{}", arg, USAGE),
         }
+        i += 2;
+    }
+
+    // hemisphere light, the missing half gets the default ambient level
+    if sky.is_some() || ground.is_some() {
+        let default = Vec3::splat(0.2);
+        let sh = AmbientSh::hemisphere(sky.unwrap_or(default), ground.unwrap_or(default));
+        config.ambient_sh = Some(sh);
     }
+
+    Ok(Args {
+        config,
+        save_config,
+        save_ids,
+        save_depth,
+        depth_colormap,
+        save_uv_layout,
+        bench_frames,
+    })
 }
 
-struct Camera {
-    translation: Mat3,
+// start from the image to paint over, if any
+fn paint_over(renderer: &mut Renderer, config: &RenderConfig) -> Result<()> {
+    if let Some(path) = &config.paint_over {
+        // the image is flipped the same way as textures, i.e. the way it's rendered
+        let canvas = read_texture_limited(path, config.load.max_texture_pixels)
+            .context("Could not read the image to paint over")?;
+        renderer.set_canvas(&canvas)?;
+    }
+    Ok(())
 }
 
-impl Camera {
-    fn new(lookfrom: Vec3, lookat: Vec3, up: Vec3) -> Self {
-        // z axis points from the camera
-        let z_axis = (lookat - lookfrom).normalize();
-        // y axis points up
-        let y_axis = up.normalize();
-        // x axis points to the left
-        let x_axis = y_axis.cross(z_axis).normalize();
-        // translation to camera-centric coordinate system (rotation part)
-        let translation = Mat3::from_cols(x_axis, y_axis, z_axis);
+// Render frames (clearing the renderer before every one of them) and report frame rate and
+// times, nothing is saved
+fn bench<F: Fn(&mut Renderer)>(
+    renderer: &mut Renderer,
+    frames: usize,
+    load_time: f64,
+    mip_time: Option<f64>,
+    render: F,
+) -> Result<()> {
+    if frames == 0 {
+        bail!("Nothing to benchmark, expected at least 1 frame");
+    }
 
-        Camera { translation }
+    let mut times = Vec::with_capacity(frames);
+    for _ in 0..frames {
+        let start = Instant::now();
+        renderer.clear_all();
+        render(renderer);
+        times.push(start.elapsed().as_secs_f64());
     }
 
-    // translate point p to camera-centric coordinate system
-    fn translate(&self, point: Vec3) -> Vec3 {
-        self.translation * point //+ self.lookfrom
+    let total: f64 = times.iter().sum();
+    let min = times.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = times.iter().copied().fold(0.0, f64::max);
+    println!(
+        "Rendered {} frames in {:.3} ms: {:.1} FPS, {:.3} ms per frame (min {:.3} ms, max {:.3} ms)",
+        frames,
+        total * 1e3,
+        frames as f64 / total,
+        total * 1e3 / frames as f64,
+        min * 1e3,
+        max * 1e3
+    );
+    // parsing and generating normals, what the rayon feature speeds up
+    println!("Loaded the model in {:.3} ms", load_time * 1e3);
+    // box filtering of the texture, the rayon feature speeds that up too
+    if let Some(mip_time) = mip_time {
+        println!("Built the texture mip chain in {:.3} ms", mip_time * 1e3);
     }
+    Ok(())
 }
 
-struct Renderer {
-    camera: Camera,
-    target: Image,
-    zbuffer: Vec<f32>,
-}
+fn main() -> Result<()> {
+    let Args {
+        config,
+        save_config,
+        save_ids,
+        save_depth,
+        depth_colormap,
+        save_uv_layout,
+        bench_frames,
+    } = parse_args()?;
+    if let Some(path) = save_config {
+        config.save(&path).context("Could not save config")?;
+    }
 
-impl Renderer {
-    fn new(camera: Camera, (width, height): (usize, usize)) -> Self {
-        Renderer {
-            camera,
-            target: Image::new(width as u32, height as u32),
-            zbuffer: vec![std::f32::NEG_INFINITY; width * height],
+    let mut renderer = config.renderer();
+    let load_start = Instant::now();
+    let mut model =
+        read_model(&config.model, &config.load).context("Could not read object model")?;
+    let load_time = load_start.elapsed().as_secs_f64();
+    if let Some(path) = &config.morph_target {
+        let target = read_model(path, &config.load).context("Could not read morph target")?;
+        model = morph(&model, &target, config.morph_weight)?;
+    }
+    let texture = match &config.texture {
+        Some(path) => {
+            let mut texture = read_texture_limited(path, config.load.max_texture_pixels)
+                .context("Could not read texture")?;
+            texture.dilate(config.texture_dilation);
+            Some(texture)
         }
+        None => None,
+    };
+    let materials = match &config.materials {
+        Some(path) => read_materials(path),
+        None => read_model_materials(&config.model, &model),
+    };
+    for warning in renderer.set_materials(materials.context("Could not read materials")?) {
+        eprintln!("Warning: {}", warning);
     }
-
-    fn flipv(&mut self) {
-        image::imageops::flip_vertical_in_place(&mut self.target);
+    if let Some(path) = &config.normal_map {
+        let map = read_texture_limited(path, config.load.max_texture_pixels)
+            .context("Could not read normal map")?;
+        renderer.set_normal_map(map, config.normal_map_space);
     }
-
-    fn save(&self, path: &str) -> Result<()> {
-        self.target.save(path)?;
-        Ok(())
+    if let Some(path) = &config.point_sprite {
+        let sprite = read_texture_limited(path, config.load.max_texture_pixels)
+            .context("Could not read point sprite")?;
+        renderer.set_point_sprite(sprite);
     }
-
-    #[inline(always)]
-    fn set(&mut self, x: usize, y: usize, color: Color) {
-        self.target.put_pixel(x as u32, y as u32, color);
+    if let Some(paths) = &config.ambient_environment {
+        let faces = paths
+            .iter()
+            .map(|path| read_texture_limited(path, config.load.max_texture_pixels))
+            .collect::<Result<Vec<_>>>()
+            .context("Could not read ambient environment")?;
+        let faces: [Texture; 6] = faces
+            .try_into()
+            .map_err(|_| anyhow!("Ambient environment needs 6 faces"))?;
+        renderer.set_ambient_environment(EnvironmentMap::new(&faces));
     }
-
-    fn triangle_texture(
-        &mut self,
-        a: Vec3,
-        b: Vec3,
-        c: Vec3,
-        uv0: Vec2,
-        uv1: Vec2,
-        uv2: Vec2,
-        texture: &Texture,
-        intensity: f32,
-    ) {
-        let intensity = intensity.sqrt(); // gamma correction
-        let shade = |color: u8| -> u8 { (color as f32 * intensity) as u8 };
-
-        in_triangle(a.truncate(), b.truncate(), c.truncate(), |x, y, bc| {
-            let position = x + y * self.target.width() as usize;
-            if position >= self.zbuffer.len() {
-                // this pixel is out of bounds
-                return;
-            }
-
-            // TODO: WTF?
-            let z = a.z() * bc.x() + b.z() * bc.y() + c.z() * bc.z() + 0.5;
-
-            // if previous pixel put at |x, y| as further away from camera, replace it
-            let prev_z = &mut self.zbuffer[position];
-            if *prev_z <= z {
-                *prev_z = z;
-
-                // TODO: WTF?
-                let uv = uv0 * bc.x() + uv1 * bc.y() + uv2 * bc.z();
-                let color = *texture.get_pixel(uv.x() as u32, uv.y() as u32);
-                let color = Color::from([shade(color[0]), shade(color[1]), shade(color[2])]);
-                self.set(x, y, color);
-            }
-        });
+    if let Some(path) = &config.displacement_map {
+        let map = read_texture_limited(path, config.load.max_texture_pixels)
+            .context("Could not read displacement map")?;
+        renderer.set_displacement_map(map, config.displacement_scale);
     }
-
-    fn triangle(&mut self, a: Vec3, b: Vec3, c: Vec3, color: Color) {
-        in_triangle(a.truncate(), b.truncate(), c.truncate(), |x, y, bc| {
-            let z = a.z() * bc.x() + b.z() * bc.y() + c.z() * bc.z() + 0.5;
-            let prev_z = &mut self.zbuffer[x + y * self.target.width() as usize];
-            if *prev_z <= z {
-                *prev_z = z;
-                self.set(x, y, color);
-            }
-        });
+    if let Some(source) = config.vertex_ao {
+        let ao = read_vertex_ao(&config.model, source).context("Could not read vertex AO")?;
+        renderer.set_vertex_ao(ao);
     }
-
-    fn scale_to_screen(&self, v: Vec3) -> Vec3 {
-        // coordinates in obj file are in [-1.0; 1.0] range
-        // NOTE: not really, but it's true for african_head.obj
-        let v = (v + Vec3::splat(1.0)) / 2.0; // [-1; 1] => [0; 1]
-        vec3(
-            v.x() * (self.target.width() - 1) as f32,
-            v.y() * (self.target.height() - 1) as f32,
-            // NOTE: this is a hack
-            v.z() * (self.target.width() + self.target.height() - 2) as f32 / 2.0,
-        )
+    if config.shading == ShadingMode::VertexColors {
+        let colors = read_vertex_colors(&config.model).context("Could not read vertex colors")?;
+        renderer.set_vertex_colors(colors);
     }
-
-    fn screen_coords(&self, v: Vec3) -> Vec3 {
-        let r = self.camera.translate(v);
-        self.scale_to_screen(r)
+    if let Some(path) = &config.color_lut {
+        renderer
+            .set_color_lut(path)
+            .context("Could not read color LUT")?;
     }
-
-    // returns UV coordinates for v
-    fn texture_coords(&self, v: TVertex, texture: &Texture) -> Vec2 {
-        vec2(
-            v.u as f32 * (texture.width() - 1) as f32,
-            v.v as f32 * (texture.height() - 1) as f32,
-        )
+    if let Some(path) = &save_uv_layout {
+        // without a texture at the size the head's textures have
+        let size = texture.as_ref().map_or((1024, 1024), |texture| {
+            (texture.width() as usize, texture.height() as usize)
+        });
+        render_uv_layout(&model, size, path).context("Could not save UV layout")?;
+    }
+    let mip_time = texture
+        .as_ref()
+        .filter(|_| bench_frames.is_some())
+        .map(|texture| {
+            let start = Instant::now();
+            texture.mip_chain();
+            start.elapsed().as_secs_f64()
+        });
+    let scene = Scene::single(model, texture);
+    let render = |renderer: &mut Renderer| match config.depth_peeling_layers {
+        Some(layers) => renderer.render_depth_peeled(&scene, layers),
+        None => renderer.render_scene(&scene),
+    };
+
+    // every frame starts from a cleared renderer, so --paint-over has no effect here
+    if let Some(frames) = bench_frames {
+        return bench(&mut renderer, frames, load_time, mip_time, render);
     }
 
-    fn primitive(
-        &mut self,
-        primitive: &Primitive,
-        vertices: &[Vertex],
-        texture_vertices: &[TVertex],
-        texture: &Texture,
-    ) {
-        let to_vec3 = |v: Vertex| vec3(v.x as f32, v.y as f32, v.z as f32);
-
-        let light_direction = vec3(0.0, 0.0, 1.0);
-        match primitive {
-            Primitive::Triangle((x, Some(tx), _), (y, Some(ty), _), (z, Some(tz), _)) => {
-                let a = self.screen_coords(to_vec3(vertices[*x]));
-                let b = self.screen_coords(to_vec3(vertices[*y]));
-                let c = self.screen_coords(to_vec3(vertices[*z]));
-
-                let normal = (b - a).cross(c - a).normalize();
-                let intensity = max(normal.dot(light_direction), 0.2);
-                if intensity.is_sign_positive() {
-                    let uv0 = self.texture_coords(texture_vertices[*tx], texture);
-                    let uv1 = self.texture_coords(texture_vertices[*ty], texture);
-                    let uv2 = self.texture_coords(texture_vertices[*tz], texture);
-                    self.triangle_texture(a, b, c, uv0, uv1, uv2, texture, intensity);
-                }
+    // the model and textures are read once for all sizes and frames
+    let sizes = if config.sizes.is_empty() {
+        vec![(config.width, config.height)]
+    } else {
+        config.sizes.clone()
+    };
+    let frames: Vec<_> = if config.timeline.keyframes.is_empty() {
+        vec![None]
+    } else {
+        (0..config.timeline.frames()).map(Some).collect()
+    };
+    let camera = config.camera.camera();
+    for (&size, &frame) in sizes
+        .iter()
+        .flat_map(|size| frames.iter().map(move |frame| (size, frame)))
+    {
+        let path = |path: &str| {
+            let mut path = path.to_owned();
+            if !config.sizes.is_empty() {
+                path = suffixed_path(&path, &format!("{}x{}", size.0, size.1));
             }
-            Primitive::Triangle((x, _, _), (y, _, _), (z, _, _)) => {
-                let a = self.screen_coords(to_vec3(vertices[*x]));
-                let b = self.screen_coords(to_vec3(vertices[*y]));
-                let c = self.screen_coords(to_vec3(vertices[*z]));
-
-                let normal = (b - a).cross(c - a).normalize();
-                let intensity = normal.dot(light_direction);
-
-                let shade = (0xff as f32 * intensity) as u8;
-                let color = [shade, shade, shade].into();
-
-                if intensity.is_sign_positive() {
-                    self.triangle(a, b, c, color);
-                }
+            if let Some(frame) = frame {
+                path = suffixed_path(&path, &format!("{:04}", frame));
             }
-            _ => todo!(),
+            path
+        };
+        let factor = config.supersampling.max(1) as usize;
+        renderer.resize((size.0 * factor, size.1 * factor));
+        if let Some(frame) = frame {
+            let time = config.timeline.frame_time(frame);
+            *renderer.camera_mut() = config.timeline.camera(time, &camera);
         }
-    }
-
-    fn obj(&mut self, model: &ObjSet, texture: &Texture) {
-        for object in &model.objects {
-            for geometry in &object.geometry {
-                for shape in &geometry.shapes {
-                    self.primitive(
-                        &shape.primitive,
-                        &object.vertices,
-                        &object.tex_vertices,
-                        &texture,
-                    );
+        paint_over(&mut renderer, &config)?;
+
+        let start = Instant::now();
+        render(&mut renderer);
+        println!(
+            "Render took {:.3} ms",
+            start.elapsed().as_micros() as f64 / 1_000.0
+        );
+        if config.frustum_culling.is_some() || config.sphere_culling {
+            let stats = renderer.culling_stats();
+            println!(
+                "Culled {} of {} instances, tested {} {}",
+                stats.culled,
+                stats.culled + stats.visible,
+                stats.nodes_tested,
+                if config.sphere_culling {
+                    "bounding spheres"
+                } else {
+                    "BVH nodes"
                 }
+            );
+        }
+        if config.lod.is_some() {
+            let skipped = renderer.lod_stats();
+            println!(
+                "Skipped {} triangles and {} instances",
+                skipped.triangles, skipped.instances
+            );
+        }
+
+        renderer.flipv();
+        if config.supersampling > 1 {
+            renderer
+                .resolve(config.supersampling, config.resolve_filter)?
+                .save(path(&config.output))?;
+        } else {
+            renderer.save(&path(&config.output))?;
+        }
+        if let Some(ids) = &save_ids {
+            renderer.save_ids(&path(ids))?;
+        }
+        if let Some(depth) = &save_depth {
+            match depth_colormap {
+                Some(colormap) => renderer.save_depth_colored(&path(depth), colormap)?,
+                None => renderer.save_linear_depth(&path(depth))?,
             }
         }
     }
-}
-
-fn read_model(path: &str) -> Result<ObjSet> {
-    let model = std::fs::read_to_string(path)?;
-    let model = obj::parse(&model)
-        .map_err(|e| anyhow!("Failed to parse line #{}: {}", e.line_number, e.message))?;
-    Ok(model)
-}
-
-fn read_texture(path: &str) -> Result<Texture> {
-    let mut texture = image::open(path)?.to_rgb();
-    image::imageops::flip_vertical_in_place(&mut texture);
-    Ok(texture)
-}
-
-fn main() -> Result<()> {
-    let lookfrom = vec3(0.5, 0.0, -0.5);
-    let lookat = vec3(0.0, 0.0, 0.0);
-    let up = vec3(0.0, 1.0, 0.0);
-    let camera = Camera::new(lookfrom, lookat, up);
-    let mut renderer = Renderer::new(camera, (1024, 1024));
-    let model = read_model("obj/african_head.obj").context("Could not read object model")?;
-    let texture = read_texture("obj/african_head_diffuse.png").context("Could not read texture")?;
-
-    let start = Instant::now();
-    renderer.obj(&model, &texture);
-    println!(
-        "Render took {:.3} ms",
-        start.elapsed().as_micros() as f64 / 1_000.0
-    );
-
-    renderer.flipv();
-    renderer.save("target.png")?;
     Ok(())
 }
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use glam::{vec2, vec3, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use wavefront_obj::obj::ObjSet;
+
+// Surface properties shared by all triangles referencing it with `usemtl`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    // opacity, alpha < 1 makes material transparent (blended over the opaque geometry)
+    pub alpha: f32,
+    // base (diffuse) color in [0; 1] range, multiplies the texture
+    pub color: Vec3,
+    // color of specular highlights (none if zero) and their Phong exponent
+    pub specular: Vec3,
+    pub shininess: f32,
+    // path of the diffuse texture (`map_Kd`), replaces the texture of the mesh
+    pub texture: Option<String>,
+    // applied to texture coordinates (uv * uv_scale + uv_offset) of the diffuse texture and the
+    // normal map, e.g. to tile a detail texture (`-s` and `-o` options of `map_Kd`)
+    pub uv_scale: Vec2,
+    pub uv_offset: Vec2,
+    // name of the material of back faces (`back_mtl`, not a standard statement), they use this
+    // one as well if None, see Renderer::set_double_sided
+    pub back: Option<String>,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material {
+            alpha: 1.0,
+            color: Vec3::splat(1.0),
+            specular: Vec3::zero(),
+            shininess: 1.0,
+            texture: None,
+            uv_scale: Vec2::splat(1.0),
+            uv_offset: Vec2::zero(),
+            back: None,
+        }
+    }
+}
+
+impl Material {
+    // transparent materials are drawn in a separate pass after all opaque geometry
+    pub fn is_transparent(&self) -> bool {
+        self.alpha < 1.0
+    }
+}
+
+pub type Materials = HashMap<String, Material>;
+
+// Material properties replacing the ones of every material (including the default one)
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaterialOverride {
+    pub color: Option<Vec3>,
+    pub specular: Option<Vec3>,
+    pub shininess: Option<f32>,
+}
+
+impl MaterialOverride {
+    pub fn apply(&self, material: &mut Material) {
+        if let Some(color) = self.color {
+            material.color = color;
+        }
+        if let Some(specular) = self.specular {
+            material.specular = specular;
+        }
+        if let Some(shininess) = self.shininess {
+            material.shininess = shininess;
+        }
+    }
+}
+
+// Read materials from MTL file (the one referenced by `mtllib` in OBJ), texture paths are
+// relative to its directory (with either kind of slashes, as exported on Windows or not).
+// NOTE: wavefront_obj::mtl insists on a fixed order of statements, which most exporters
+// don't follow, so we parse it ourselves. Unsupported statements are ignored.
+pub fn read_materials(path: &str) -> Result<Materials> {
+    let mut materials = parse_materials(&std::fs::read_to_string(path)?)?;
+    let directory = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+    for material in materials.values_mut() {
+        if let Some(texture) = &mut material.texture {
+            let relative = texture.replace('\\', "/");
+            *texture = directory.join(relative).to_string_lossy().into_owned();
+        }
+    }
+    Ok(materials)
+}
+
+// Materials of the model read from path, from the MTL file its `mtllib` references (relative to
+// the model's directory), or none if it doesn't reference any
+pub fn read_model_materials(path: &str, model: &ObjSet) -> Result<Materials> {
+    match &model.material_library {
+        Some(library) => {
+            let directory = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+            let library = directory.join(library.replace('\\', "/"));
+            read_materials(&library.to_string_lossy())
+                .with_context(|| format!("Could not read {}", library.display()))
+        }
+        None => Ok(Materials::new()),
+    }
+}
+
+fn parse_materials(input: &str) -> Result<Materials> {
+    let mut materials = Materials::new();
+    let mut current: Option<(String, Material)> = None;
+
+    for (i, line) in input.lines().enumerate() {
+        let error = |message: &str| anyhow!("Failed to parse line #{}: {}", i + 1, message);
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut words = line.split_whitespace();
+        let statement = match words.next() {
+            Some(statement) => statement,
+            None => continue,
+        };
+
+        let mut number = || -> Result<f32> {
+            words
+                .next()
+                .and_then(|word| word.parse().ok())
+                .ok_or_else(|| error("Expected a number"))
+        };
+        let mut color = || -> Result<Vec3> { Ok(vec3(number()?, number()?, number()?)) };
+
+        if statement == "newmtl" {
+            let name = line["newmtl".len()..].trim();
+            if name.is_empty() {
+                return Err(error("Expected material name"));
+            }
+
+            if let Some((name, material)) = current.take() {
+                materials.insert(name, material);
+            }
+            current = Some((name.to_owned(), Material::default()));
+            continue;
+        }
+
+        let material = match &mut current {
+            Some((_, material)) => material,
+            None => return Err(error(&format!("{} before newmtl", statement))),
+        };
+
+        match statement {
+            "d" => material.alpha = number()?,
+            // inverse of d
+            "Tr" => material.alpha = 1.0 - number()?,
+            "Kd" => material.color = color()?,
+            "Ks" => material.specular = color()?,
+            "Ns" => material.shininess = number()?,
+            // options (e.g. `-s 2 2 1`) come before the file name, only the scale and offset (of
+            // u and v) are supported
+            "map_Kd" => {
+                let words: Vec<&str> = words.collect();
+                let file = words.last().ok_or_else(|| error("Expected texture file"))?;
+                let option = |name: &str, default: f32| {
+                    let i = words.iter().position(|&word| word == name)?;
+                    let number = |i: usize| {
+                        let word = words[..words.len() - 1].get(i)?;
+                        word.parse().ok()
+                    };
+                    let u = number(i + 1)?;
+                    Some(vec2(u, number(i + 2).unwrap_or(default)))
+                };
+                material.uv_scale = option("-s", 1.0).unwrap_or(material.uv_scale);
+                material.uv_offset = option("-o", 0.0).unwrap_or(material.uv_offset);
+                material.texture = Some((*file).to_owned());
+            }
+            "back_mtl" => {
+                let name = line["back_mtl".len()..].trim();
+                if name.is_empty() {
+                    return Err(error("Expected material name"));
+                }
+                material.back = Some(name.to_owned());
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((name, material)) = current {
+        materials.insert(name, material);
+    }
+
+    Ok(materials)
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use glam::{vec3, Mat3, Vec3};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use wavefront_obj::obj::{ObjSet, Object, Primitive, Vertex};
+
+#[inline(always)]
+pub(crate) fn to_vec3(v: Vertex) -> Vec3 {
+    vec3(v.x as f32, v.y as f32, v.z as f32)
+}
+
+#[inline(always)]
+pub(crate) fn to_vertex(v: Vec3) -> Vertex {
+    Vertex {
+        x: v.x() as f64,
+        y: v.y() as f64,
+        z: v.z() as f64,
+    }
+}
+
+// How much every face sharing a vertex contributes to its smooth normal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalWeighting {
+    // all faces equally
+    Uniform,
+    // by their area, so large faces dominate
+    #[default]
+    Area,
+    // by the angle of the face at the vertex (Thürmer-Wüthrich), which doesn't depend on how
+    // the surface around the vertex is split into triangles
+    Angle,
+}
+
+// Compute vertex normals for every triangle that doesn't reference any (i.e. `vn` is missing).
+// Normals are averaged only across faces sharing a smoothing group, so `s off` faces and
+// boundaries between different groups stay faceted.
+pub fn compute_normals(model: &mut ObjSet) {
+    compute_weighted_normals(model, NormalWeighting::Area);
+}
+
+// compute_normals with face normals weighted by weighting
+pub fn compute_weighted_normals(model: &mut ObjSet, weighting: NormalWeighting) {
+    let compute = |object: &mut Object| compute_object_normals(object, weighting);
+
+    #[cfg(feature = "rayon")]
+    model.objects.par_iter_mut().for_each(compute);
+
+    #[cfg(not(feature = "rayon"))]
+    model.objects.iter_mut().for_each(compute);
+}
+
+fn needs_normals(primitive: &Primitive) -> bool {
+    match primitive {
+        Primitive::Triangle((_, _, Some(_)), (_, _, Some(_)), (_, _, Some(_))) => false,
+        Primitive::Triangle(..) => true,
+        _ => false,
+    }
+}
+
+// normal of every shape missing normals and its weighted contribution to the smooth normal of
+// every corner, in the same order as shapes appear in object
+fn face_normals(object: &Object, weighting: NormalWeighting) -> Vec<Option<(Vec3, [Vec3; 3])>> {
+    let vertices = &object.vertices;
+    let face_normal = |primitive: &Primitive| match primitive {
+        Primitive::Triangle((a, _, _), (b, _, _), (c, _, _)) if needs_normals(primitive) => {
+            let (a, b, c) = (
+                to_vec3(vertices[*a]),
+                to_vec3(vertices[*b]),
+                to_vec3(vertices[*c]),
+            );
+            // twice the area long
+            let normal = (b - a).cross(c - a);
+            let unit = if normal.length_squared() > 0.0 {
+                normal.normalize()
+            } else {
+                normal
+            };
+            let angle = |at: Vec3, p: Vec3, q: Vec3| {
+                let (p, q) = (p - at, q - at);
+                if p.length_squared() > 0.0 && q.length_squared() > 0.0 {
+                    let cos = p.dot(q) / (p.length() * q.length());
+                    cos.clamp(-1.0, 1.0).acos()
+                } else {
+                    0.0
+                }
+            };
+            let corners = match weighting {
+                NormalWeighting::Uniform => [unit; 3],
+                NormalWeighting::Area => [normal; 3],
+                NormalWeighting::Angle => [
+                    unit * angle(a, b, c),
+                    unit * angle(b, c, a),
+                    unit * angle(c, a, b),
+                ],
+            };
+            Some((normal, corners))
+        }
+        _ => None,
+    };
+
+    let primitives: Vec<&Primitive> = object
+        .geometry
+        .iter()
+        .flat_map(|geometry| geometry.shapes.iter().map(|shape| &shape.primitive))
+        .collect();
+
+    #[cfg(feature = "rayon")]
+    let normals = primitives.par_iter().map(|p| face_normal(p)).collect();
+
+    #[cfg(not(feature = "rayon"))]
+    let normals = primitives.iter().map(|p| face_normal(p)).collect();
+
+    normals
+}
+
+fn compute_object_normals(object: &mut Object, weighting: NormalWeighting) {
+    let face_normals = face_normals(object, weighting);
+
+    // sum of face normals for every (vertex, smoothing group) pair
+    let mut smooth: HashMap<(usize, u32), Vec3> = HashMap::new();
+    let shapes = object.geometry.iter().flat_map(|g| g.shapes.iter());
+    for (shape, normal) in shapes.zip(&face_normals) {
+        if let (Primitive::Triangle((a, _, _), (b, _, _), (c, _, _)), Some((_, corners))) =
+            (shape.primitive, normal)
+        {
+            for &group in &shape.smoothing_groups {
+                for (&v, &corner) in [a, b, c].iter().zip(corners) {
+                    *smooth.entry((v, group)).or_insert(Vec3::zero()) += corner;
+                }
+            }
+        }
+    }
+
+    let normals = &mut object.normals;
+    let mut indices: HashMap<(usize, Vec<u32>), usize> = HashMap::new();
+    let mut push = |normal: Vec3| -> usize {
+        let normal = if normal.length_squared() > 0.0 {
+            normal.normalize()
+        } else {
+            normal
+        };
+        normals.push(to_vertex(normal));
+        normals.len() - 1
+    };
+
+    let shapes = object.geometry.iter_mut().flat_map(|g| g.shapes.iter_mut());
+    for (shape, normal) in shapes.zip(face_normals) {
+        if let (Primitive::Triangle(a, b, c), Some((normal, _))) = (&mut shape.primitive, normal) {
+            if shape.smoothing_groups.is_empty() {
+                // flat shaded: every corner gets the face normal
+                let n = push(normal);
+                a.2 = Some(n);
+                b.2 = Some(n);
+                c.2 = Some(n);
+                continue;
+            }
+
+            for corner in [a, b, c].iter_mut() {
+                let key = (corner.0, shape.smoothing_groups.clone());
+                let n = match indices.get(&key) {
+                    Some(&n) => n,
+                    None => {
+                        let sum = shape
+                            .smoothing_groups
+                            .iter()
+                            .map(|&group| smooth[&(corner.0, group)])
+                            .fold(Vec3::zero(), |acc, n| acc + n);
+                        let n = push(sum);
+                        indices.insert(key, n);
+                        n
+                    }
+                };
+                corner.2 = Some(n);
+            }
+        }
+    }
+}
+
+// Make faces of every object that reference vertices closer than tolerance to each other use the
+// same (first) one, so smooth normals are computed across seams with duplicated vertices.
+// Returns the number of welded vertices.
+// NOTE: welded duplicates are kept (unreferenced), so data indexed like Object::vertices stays valid
+pub fn weld_vertices(model: &mut ObjSet, tolerance: f32) -> usize {
+    model
+        .objects
+        .iter_mut()
+        .map(|object| weld_object_vertices(object, tolerance))
+        .sum()
+}
+
+fn weld_object_vertices(object: &mut Object, tolerance: f32) -> usize {
+    // vertices are bucketed into tolerance sized cells, so only neighbouring cells are searched
+    let tolerance = tolerance.max(0.0);
+    let cell = |v: Vec3| -> [i64; 3] {
+        if tolerance > 0.0 {
+            let c = v / tolerance;
+            [
+                c.x().floor() as i64,
+                c.y().floor() as i64,
+                c.z().floor() as i64,
+            ]
+        } else {
+            // exact matches only, + 0.0 turns -0.0 into 0.0
+            let bits = |x: f32| (x + 0.0).to_bits() as i64;
+            [bits(v.x()), bits(v.y()), bits(v.z())]
+        }
+    };
+    let radius = if tolerance > 0.0 { 1 } else { 0 };
+
+    let mut cells: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+    let mut remap = Vec::with_capacity(object.vertices.len());
+    let mut welded = 0;
+    for (i, &v) in object.vertices.iter().enumerate() {
+        let p = to_vec3(v);
+        let [x, y, z] = cell(p);
+        let mut found = None;
+        'search: for dx in -radius..=radius {
+            for dy in -radius..=radius {
+                for dz in -radius..=radius {
+                    let neighbours = match cells.get(&[x + dx, y + dy, z + dz]) {
+                        Some(neighbours) => neighbours,
+                        None => continue,
+                    };
+                    for &j in neighbours {
+                        if (to_vec3(object.vertices[j]) - p).length() <= tolerance {
+                            found = Some(j);
+                            break 'search;
+                        }
+                    }
+                }
+            }
+        }
+
+        match found {
+            Some(j) => {
+                remap.push(j);
+                welded += 1;
+            }
+            None => {
+                remap.push(i);
+                cells.entry([x, y, z]).or_default().push(i);
+            }
+        }
+    }
+
+    if welded == 0 {
+        return 0;
+    }
+
+    let shapes = object.geometry.iter_mut().flat_map(|g| g.shapes.iter_mut());
+    for shape in shapes {
+        match &mut shape.primitive {
+            Primitive::Point(a) => a.0 = remap[a.0],
+            Primitive::Line(a, b) => {
+                a.0 = remap[a.0];
+                b.0 = remap[b.0];
+            }
+            Primitive::Triangle(a, b, c) => {
+                a.0 = remap[a.0];
+                b.0 = remap[b.0];
+                c.0 = remap[c.0];
+            }
+        }
+    }
+    welded
+}
+
+// Up axis (and handedness) of the source model, everything is converted to Y-up on load
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AxisConvention {
+    #[default]
+    YUp,
+    // Z-up (e.g. Blender, 3ds Max exports): (x, y, z) => (x, z, -y)
+    ZUpToYUp,
+    // arbitrary linear transform of (x, y, z), e.g. to swizzle or negate axes
+    Custom(Mat3),
+}
+
+impl AxisConvention {
+    fn matrix(&self) -> Option<Mat3> {
+        match self {
+            AxisConvention::YUp => None,
+            AxisConvention::ZUpToYUp => Some(Mat3::from_cols(
+                vec3(1.0, 0.0, 0.0),
+                vec3(0.0, 0.0, -1.0),
+                vec3(0.0, 1.0, 0.0),
+            )),
+            AxisConvention::Custom(m) => Some(*m),
+        }
+    }
+}
+
+// Transform vertices and normals of model from axes convention to Y-up
+pub fn convert_axes(model: &mut ObjSet, axes: AxisConvention) {
+    let m = match axes.matrix() {
+        Some(m) => m,
+        None => return,
+    };
+    // normals are transformed by inverse transpose to stay perpendicular to surfaces
+    let n = m.inverse().transpose();
+
+    for object in &mut model.objects {
+        for v in &mut object.vertices {
+            *v = to_vertex(m * to_vec3(*v));
+        }
+
+        for normal in &mut object.normals {
+            let t = n * to_vec3(*normal);
+            if t.length_squared() > 0.0 {
+                *normal = to_vertex(t.normalize());
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::{bail, Result};
+use wavefront_obj::obj::{ObjSet, Vertex};
+
+// Blend between morph targets a (t = 0) and b (t = 1) of the same topology: positions and
+// normals are interpolated linearly, everything else (faces, UVs, materials) comes from a
+pub fn morph(a: &ObjSet, b: &ObjSet, t: f32) -> Result<ObjSet> {
+    if a.objects.len() != b.objects.len() {
+        bail!(
+            "Morph targets have {} and {} objects",
+            a.objects.len(),
+            b.objects.len()
+        );
+    }
+
+    let mut morphed = a.clone();
+    for (object, target) in morphed.objects.iter_mut().zip(&b.objects) {
+        if object.vertices.len() != target.vertices.len()
+            || object.normals.len() != target.normals.len()
+        {
+            bail!(
+                "Object {:?} has {} vertices and {} normals, its morph target {} and {}",
+                object.name,
+                object.vertices.len(),
+                object.normals.len(),
+                target.vertices.len(),
+                target.normals.len()
+            );
+        }
+
+        for (vertex, &target) in object.vertices.iter_mut().zip(&target.vertices) {
+            *vertex = lerp(*vertex, target, t);
+        }
+        // the renderer normalizes normals anyway
+        for (normal, &target) in object.normals.iter_mut().zip(&target.normals) {
+            *normal = lerp(*normal, target, t);
+        }
+    }
+    Ok(morphed)
+}
+
+// in f64, so the targets are reproduced exactly at t = 0 and t = 1
+fn lerp(a: Vertex, b: Vertex, t: f32) -> Vertex {
+    let t = t as f64;
+    Vertex {
+        x: a.x * (1.0 - t) + b.x * t,
+        y: a.y * (1.0 - t) + b.y * t,
+        z: a.z * (1.0 - t) + b.z * t,
+    }
+}
@@ -0,0 +1,259 @@
+use glam::{vec3, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::Image;
+
+// Glow around highlights: the part of linear colors above threshold (in the brightest channel)
+// is blurred with a Gaussian reaching radius pixels away and added to the image times intensity
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bloom {
+    pub threshold: f32,
+    pub radius: f32,
+    pub intensity: f32,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Bloom {
+            threshold: 1.0,
+            radius: 16.0,
+            intensity: 1.0,
+        }
+    }
+}
+
+impl Bloom {
+    // hdr is the linear color of every pixel of image
+    pub(crate) fn apply(&self, image: &mut Image, hdr: &[Vec3]) {
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let bright: Vec<Vec3> = hdr
+            .iter()
+            .map(|&color| {
+                let peak = color.max_element();
+                if peak > self.threshold && peak > 0.0 {
+                    color * ((peak - self.threshold) / peak)
+                } else {
+                    Vec3::zero()
+                }
+            })
+            .collect();
+
+        // the kernel covers 3 sigma, so it's practically zero at the radius
+        let radius = self.radius.max(0.0).ceil() as usize;
+        let sigma = (self.radius / 3.0).max(1e-3);
+        let mut kernel: Vec<f32> = (0..=2 * radius)
+            .map(|i| {
+                let x = i as f32 - radius as f32;
+                (-x * x / (2.0 * sigma * sigma)).exp()
+            })
+            .collect();
+        let sum: f32 = kernel.iter().sum();
+        kernel.iter_mut().for_each(|w| *w /= sum);
+
+        // separable blur: rows, then columns, dropping weights outside of the image
+        let blur = |source: &[Vec3], step: usize, length: usize, lines: usize, stride: usize| {
+            let mut blurred = vec![Vec3::zero(); source.len()];
+            for line in 0..lines {
+                for i in 0..length {
+                    let mut sum = Vec3::zero();
+                    for (k, &w) in kernel.iter().enumerate() {
+                        let j = i as i64 + k as i64 - radius as i64;
+                        if j >= 0 && (j as usize) < length {
+                            sum += source[line * stride + j as usize * step] * w;
+                        }
+                    }
+                    blurred[line * stride + i * step] = sum;
+                }
+            }
+            blurred
+        };
+        let rows = blur(&bright, 1, width, height, width);
+        let glow = blur(&rows, width, height, width, 1);
+
+        // glow is linear, the image is gamma corrected (see Renderer::triangle_texture)
+        for (pixel, glow) in image.pixels_mut().zip(glow) {
+            let glow = glow * self.intensity.max(0.0);
+            for (c, g) in pixel
+                .0
+                .iter_mut()
+                .zip([glow.x(), glow.y(), glow.z()].iter())
+            {
+                *c = (*c as f32 + 255.0 * g.sqrt()) as u8;
+            }
+        }
+    }
+}
+
+// Darkening towards the corners. Distances are relative to half of the image diagonal: pixels
+// closer to the center than radius are unchanged, darkening smoothly ramps up over softness and
+// reaches strength (1 is black) beyond radius + softness.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Vignette {
+    pub strength: f32,
+    pub radius: f32,
+    pub softness: f32,
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Vignette {
+            strength: 0.5,
+            radius: 0.5,
+            softness: 0.5,
+        }
+    }
+}
+
+impl Vignette {
+    // brightness multiplier at distance from the center (relative to half of the diagonal)
+    fn factor(&self, distance: f32) -> f32 {
+        let t = if self.softness > 0.0 {
+            ((distance - self.radius) / self.softness).clamp(0.0, 1.0)
+        } else if distance > self.radius {
+            1.0
+        } else {
+            0.0
+        };
+        // smoothstep
+        1.0 - self.strength.clamp(0.0, 1.0) * t * t * (3.0 - 2.0 * t)
+    }
+
+    pub(crate) fn apply(&self, image: &mut Image) {
+        let center_x = (image.width() as f32 - 1.0) / 2.0;
+        let center_y = (image.height() as f32 - 1.0) / 2.0;
+        let half_diagonal = (center_x * center_x + center_y * center_y).sqrt().max(1.0);
+
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            let (dx, dy) = (x as f32 - center_x, y as f32 - center_y);
+            let factor = self.factor((dx * dx + dy * dy).sqrt() / half_diagonal);
+            if factor < 1.0 {
+                for c in pixel.0.iter_mut() {
+                    *c = (*c as f32 * factor).round() as u8;
+                }
+            }
+        }
+    }
+}
+
+// Blur growing with the distance from the focus plane (the circle of confusion): fragments at
+// depth d (see Renderer::linear_depth) blur over aperture * |d - focus_distance| / d pixels,
+// at most aperture (the background)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DepthOfField {
+    pub focus_distance: f32,
+    pub aperture: f32,
+}
+
+impl DepthOfField {
+    fn radius(&self, depth: Option<f32>) -> f32 {
+        let aperture = self.aperture.max(0.0);
+        match depth {
+            Some(d) if d > 0.0 => (aperture * (d - self.focus_distance).abs() / d).min(aperture),
+            _ => aperture,
+        }
+    }
+
+    // depth is the linear depth of every pixel of image
+    pub(crate) fn apply(&self, image: &mut Image, depth: &[Option<f32>]) {
+        let (width, height) = (image.width() as i64, image.height() as i64);
+        let radii: Vec<f32> = depth.iter().map(|&d| self.radius(d)).collect();
+        let source = image.clone();
+
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            let (x, y) = (x as i64, y as i64);
+            let radius = radii[(x + y * width) as usize];
+            if radius < 0.5 {
+                continue;
+            }
+
+            // sharper neighbours don't bleed into blurred pixels (e.g. the background around an
+            // object in focus), a sample counts if it's within the radius of both
+            let reach = radius.floor() as i64;
+            let (mut sum, mut count) = ([0.0f32; 3], 0.0);
+            for sy in (y - reach).max(0)..=(y + reach).min(height - 1) {
+                for sx in (x - reach).max(0)..=(x + reach).min(width - 1) {
+                    let distance = (((sx - x).pow(2) + (sy - y).pow(2)) as f32).sqrt();
+                    if distance > radius || distance > radii[(sx + sy * width) as usize] {
+                        continue;
+                    }
+
+                    let sample = source.get_pixel(sx as u32, sy as u32);
+                    for (s, &c) in sum.iter_mut().zip(sample.0.iter()) {
+                        *s += c as f32;
+                    }
+                    count += 1.0;
+                }
+            }
+            for (c, s) in pixel.0.iter_mut().zip(sum.iter()) {
+                *c = (s / count).round() as u8;
+            }
+        }
+    }
+}
+
+// How images rendered at a multiple of the output size are downsampled, see Renderer::resolve.
+// Box averages the samples of every output pixel, Tent (triangle) weights samples by distance
+// from its center out to the centers of neighbouring pixels, which reduces aliasing further at
+// the cost of some sharpness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolveFilter {
+    #[default]
+    Box,
+    Tent,
+}
+
+impl ResolveFilter {
+    // source samples (within length of them) and their normalized weights of output pixel x
+    fn weights(&self, x: u32, factor: u32, length: u32) -> Vec<(u32, f32)> {
+        let f = factor as f32;
+        let mut weights: Vec<(u32, f32)> = match self {
+            ResolveFilter::Box => (x * factor..(x + 1) * factor).map(|i| (i, 1.0)).collect(),
+            ResolveFilter::Tent => {
+                let center = (x as f32 + 0.5) * f;
+                let first = (x * factor).saturating_sub(factor);
+                (first..((x + 2) * factor).min(length))
+                    .map(|i| (i, 1.0 - ((i as f32 + 0.5 - center).abs() / f).min(1.0)))
+                    .filter(|&(_, w)| w > 0.0)
+                    .collect()
+            }
+        };
+        let sum: f32 = weights.iter().map(|&(_, w)| w).sum();
+        weights.iter_mut().for_each(|(_, w)| *w /= sum);
+        weights
+    }
+
+    // image downsampled factor times, its size has to be a multiple of factor
+    pub(crate) fn resolve(&self, image: &Image, factor: u32) -> Image {
+        let (width, height) = (image.width() / factor, image.height() / factor);
+        let columns: Vec<_> = (0..width)
+            .map(|x| self.weights(x, factor, image.width()))
+            .collect();
+        let rows: Vec<_> = (0..height)
+            .map(|y| self.weights(y, factor, image.height()))
+            .collect();
+
+        // separable: rows first, then columns
+        let mut horizontal = vec![Vec3::zero(); (width * image.height()) as usize];
+        for y in 0..image.height() {
+            for (x, weights) in columns.iter().enumerate() {
+                let mut sum = Vec3::zero();
+                for &(i, w) in weights {
+                    let [r, g, b] = image.get_pixel(i, y).0;
+                    sum += vec3(r as f32, g as f32, b as f32) * w;
+                }
+                horizontal[x + (y * width) as usize] = sum;
+            }
+        }
+        Image::from_fn(width, height, |x, y| {
+            let mut sum = Vec3::zero();
+            for &(i, w) in &rows[y as usize] {
+                sum += horizontal[(x + i * width) as usize] * w;
+            }
+            let channel = |c: f32| c.round() as u8;
+            image::Rgb([channel(sum.x()), channel(sum.y()), channel(sum.z())])
+        })
+    }
+}
@@ -0,0 +1,202 @@
+use glam::Mat4;
+use serde::{Deserialize, Serialize};
+use wavefront_obj::obj::ObjSet;
+
+use crate::Texture;
+
+// Geometry together with the texture it's rendered with
+#[derive(Debug, Clone)]
+pub struct Mesh {
+    pub model: ObjSet,
+    pub texture: Option<Texture>,
+}
+
+// Mesh (index into Scene::meshes) placed in the world
+#[derive(Debug, Clone, Copy)]
+pub struct Instance {
+    pub mesh: usize,
+    pub transform: Mat4,
+}
+
+// Level of detail limits trading accuracy for speed: front facing triangles smaller than
+// min_triangle_area (in pixels) are skipped, as are whole instances whose origin is further than
+// max_distance from the camera. Shadows are still cast by everything.
+// NOTE: skipping small triangles of dense meshes leaves holes in them
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Lod {
+    pub min_triangle_area: f32,
+    pub max_distance: Option<f32>,
+}
+
+// What Lod limits skipped since the renderer was created (or last cleared)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LodStats {
+    pub triangles: usize,
+    pub instances: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Scene {
+    pub meshes: Vec<Mesh>,
+    pub instances: Vec<Instance>,
+}
+
+impl Scene {
+    // scene with a single untransformed mesh
+    pub fn single(model: ObjSet, texture: Option<Texture>) -> Self {
+        Scene {
+            meshes: vec![Mesh { model, texture }],
+            instances: vec![Instance {
+                mesh: 0,
+                transform: Mat4::identity(),
+            }],
+        }
+    }
+}
+
+// Load glTF (or GLB) file. Every mesh primitive becomes a Mesh with its base color texture,
+// and every node of the default scene referencing it an Instance.
+#[cfg(feature = "gltf")]
+pub fn read_gltf(path: &str) -> anyhow::Result<Scene> {
+    use std::collections::HashMap;
+
+    use anyhow::anyhow;
+    use wavefront_obj::obj::{Geometry, Object, Primitive, Shape, TVertex, Vertex};
+
+    let (document, buffers, images) = ::gltf::import(path)?;
+
+    let to_vertex = |p: [f32; 3]| Vertex {
+        x: p[0] as f64,
+        y: p[1] as f64,
+        z: p[2] as f64,
+    };
+
+    let mut scene = Scene::default();
+    // (mesh, primitive) => index of the corresponding Mesh
+    let mut meshes: HashMap<(usize, usize), usize> = HashMap::new();
+    for mesh in document.meshes() {
+        for primitive in mesh.primitives() {
+            if primitive.mode() != ::gltf::mesh::Mode::Triangles {
+                continue;
+            }
+
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+            let vertices: Vec<Vertex> = reader
+                .read_positions()
+                .ok_or_else(|| anyhow!("Mesh primitive without positions"))?
+                .map(to_vertex)
+                .collect();
+            let normals: Vec<Vertex> = reader
+                .read_normals()
+                .map(|normals| normals.map(to_vertex).collect())
+                .unwrap_or_default();
+            // glTF has UV origin at the top left corner, OBJ (and textures we load) at the bottom
+            let tex_vertices: Vec<TVertex> = reader
+                .read_tex_coords(0)
+                .map(|uvs| {
+                    uvs.into_f32()
+                        .map(|[u, v]| TVertex {
+                            u: u as f64,
+                            v: 1.0 - v as f64,
+                            w: 0.0,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let indices: Vec<usize> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().map(|i| i as usize).collect(),
+                None => (0..vertices.len()).collect(),
+            };
+
+            let corner = |i: usize| {
+                (
+                    i,
+                    Some(i).filter(|_| !tex_vertices.is_empty()),
+                    Some(i).filter(|_| !normals.is_empty()),
+                )
+            };
+            let shapes = indices
+                .chunks_exact(3)
+                .map(|t| Shape {
+                    primitive: Primitive::Triangle(corner(t[0]), corner(t[1]), corner(t[2])),
+                    groups: Vec::new(),
+                    smoothing_groups: Vec::new(),
+                })
+                .collect();
+
+            let texture = primitive
+                .material()
+                .pbr_metallic_roughness()
+                .base_color_texture()
+                .map(|info| to_texture(&images[info.texture().source().index()]))
+                .transpose()?;
+
+            let object = Object {
+                name: mesh.name().unwrap_or("mesh").to_owned(),
+                vertices,
+                tex_vertices,
+                normals,
+                geometry: vec![Geometry {
+                    material_name: primitive.material().name().map(str::to_owned),
+                    shapes,
+                }],
+            };
+
+            let mut model = ObjSet {
+                material_library: None,
+                objects: vec![object],
+            };
+            crate::compute_normals(&mut model);
+
+            meshes.insert((mesh.index(), primitive.index()), scene.meshes.len());
+            scene.meshes.push(Mesh { model, texture });
+        }
+    }
+
+    let root = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or_else(|| anyhow!("glTF file has no scenes"))?;
+
+    let mut nodes: Vec<(::gltf::Node, Mat4)> =
+        root.nodes().map(|node| (node, Mat4::identity())).collect();
+    while let Some((node, parent)) = nodes.pop() {
+        let transform = parent * Mat4::from_cols_array_2d(&node.transform().matrix());
+        if let Some(mesh) = node.mesh() {
+            for primitive in mesh.primitives() {
+                if let Some(&mesh) = meshes.get(&(mesh.index(), primitive.index())) {
+                    scene.instances.push(Instance { mesh, transform });
+                }
+            }
+        }
+
+        nodes.extend(node.children().map(|child| (child, transform)));
+    }
+
+    Ok(scene)
+}
+
+#[cfg(feature = "gltf")]
+fn to_texture(data: &::gltf::image::Data) -> anyhow::Result<Texture> {
+    use ::gltf::image::Format;
+
+    let channels = match data.format {
+        Format::R8 => 1,
+        Format::R8G8 => 2,
+        Format::R8G8B8 => 3,
+        Format::R8G8B8A8 => 4,
+        format => anyhow::bail!("Unsupported texture format {:?}", format),
+    };
+
+    let mut texture = Texture::from_fn(data.width, data.height, |x, y| {
+        let i = (x + y * data.width) as usize * channels;
+        let p = &data.pixels[i..i + channels];
+        match channels {
+            1 | 2 => crate::Color::from([p[0], p[0], p[0]]),
+            _ => crate::Color::from([p[0], p[1], p[2]]),
+        }
+    });
+    image::imageops::flip_vertical_in_place(&mut texture);
+    Ok(texture)
+}
@@ -0,0 +1,142 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::{cubemap_faces, to_linear, Image};
+
+// Incoming (ambient) light as 3rd order spherical harmonics: 9 coefficients in L00, L1-1, L10,
+// L11, L2-2, L2-1, L20, L21, L22 order, directions are in world space.
+// See "An Efficient Representation for Irradiance Environment Maps" (Ramamoorthi, Hanrahan).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AmbientSh {
+    pub coefficients: [Vec3; 9],
+}
+
+// real SH basis functions up to l = 2 at unit direction d
+fn basis(d: Vec3) -> [f32; 9] {
+    let (x, y, z) = (d.x(), d.y(), d.z());
+    [
+        0.282_095,
+        0.488_603 * y,
+        0.488_603 * z,
+        0.488_603 * x,
+        1.092_548 * x * y,
+        1.092_548 * y * z,
+        0.315_392 * (3.0 * z * z - 1.0),
+        1.092_548 * x * z,
+        0.546_274 * (x * x - y * y),
+    ]
+}
+
+impl AmbientSh {
+    // sky color from above (+Y) and ground color from below
+    pub fn hemisphere(sky: Vec3, ground: Vec3) -> Self {
+        // every other coefficient integrates to zero over both hemispheres
+        let pi = std::f32::consts::PI;
+        let mut coefficients = [Vec3::zero(); 9];
+        coefficients[0] = (sky + ground) * (0.282_095 * 2.0 * pi);
+        coefficients[1] = (sky - ground) * (0.488_603 * pi);
+        AmbientSh { coefficients }
+    }
+
+    // Project faces of a cubemap (as rendered by Renderer::render_cubemap) onto SH
+    pub fn from_cubemap(faces: &[Image; 6]) -> Self {
+        let mut coefficients = [Vec3::zero(); 9];
+        let mut total = 0.0;
+        for (face, &(direction, up)) in faces.iter().zip(cubemap_faces().iter()) {
+            let right = direction.cross(up);
+            let (width, height) = (face.width(), face.height());
+            for (px, py, &pixel) in face.enumerate_pixels() {
+                // the first and last rows/columns are at the edges of the face
+                let s = px as f32 / (width - 1).max(1) as f32 * 2.0 - 1.0;
+                let t = 1.0 - py as f32 / (height - 1).max(1) as f32 * 2.0;
+                let d = direction + right * s + up * t;
+                // solid angle of a texel is proportional to 1 / |d|^3
+                let weight = 1.0 / (d.length_squared() * d.length());
+                let color = to_linear(pixel);
+                for (c, b) in coefficients.iter_mut().zip(basis(d.normalize()).iter()) {
+                    *c += color * (b * weight);
+                }
+                total += weight;
+            }
+        }
+
+        // weights of all texels add up to the whole sphere
+        if total > 0.0 {
+            let scale = 4.0 * std::f32::consts::PI / total;
+            coefficients.iter_mut().for_each(|c| *c *= scale);
+        }
+        AmbientSh { coefficients }
+    }
+
+    // irradiance (divided by pi, so a uniform environment of color c gives c) arriving at a
+    // surface with world space normal n
+    pub fn irradiance(&self, n: Vec3) -> Vec3 {
+        const C1: f32 = 0.429_043;
+        const C2: f32 = 0.511_664;
+        const C3: f32 = 0.743_125;
+        const C4: f32 = 0.886_227;
+        const C5: f32 = 0.247_708;
+
+        let l = &self.coefficients;
+        let (x, y, z) = (n.x(), n.y(), n.z());
+        let e = l[8] * (C1 * (x * x - y * y)) + l[6] * (C3 * z * z) + l[0] * C4 - l[6] * C5
+            + (l[4] * (x * y) + l[7] * (x * z) + l[5] * (y * z)) * (2.0 * C1)
+            + (l[3] * x + l[1] * y + l[2] * z) * (2.0 * C2);
+        (e / std::f32::consts::PI).max(Vec3::zero())
+    }
+}
+
+// Low resolution cubemap of incoming (ambient) light, faces as rendered by
+// Renderer::render_cubemap, sampled by the surface normal: sharper than AmbientSh, though it's
+// the light from a single direction rather than the irradiance of the whole hemisphere.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvironmentMap {
+    // linear colors of every face by rows along with its width and height
+    faces: Vec<(Vec<Vec3>, usize, usize)>,
+}
+
+impl EnvironmentMap {
+    pub fn new(faces: &[Image; 6]) -> Self {
+        let faces = faces
+            .iter()
+            .map(|face| {
+                let texels = face.pixels().map(|&pixel| to_linear(pixel)).collect();
+                (texels, face.width() as usize, face.height() as usize)
+            })
+            .collect();
+        EnvironmentMap { faces }
+    }
+
+    // linear color in world space direction d, bilinearly filtered within the face it points at
+    pub fn sample(&self, d: Vec3) -> Vec3 {
+        let directions = cubemap_faces();
+        let (index, dot) = directions
+            .iter()
+            .map(|(direction, _)| direction.dot(d))
+            .enumerate()
+            .fold(
+                (0, 0.0),
+                |best, (i, dot)| if dot > best.1 { (i, dot) } else { best },
+            );
+        let (texels, width, height) = &self.faces[index];
+        if dot <= 0.0 || texels.is_empty() {
+            return Vec3::zero();
+        }
+
+        // inverse of the mapping of AmbientSh::from_cubemap
+        let (direction, up) = directions[index];
+        let d = d / dot;
+        let s = d.dot(direction.cross(up));
+        let t = d.dot(up);
+        let x = (s + 1.0) / 2.0 * (width - 1) as f32;
+        let y = (1.0 - t) / 2.0 * (height - 1) as f32;
+
+        let texel = |x: usize, y: usize| texels[y.min(height - 1) * width + x.min(width - 1)];
+        let (x0, y0) = (x.floor().max(0.0), y.floor().max(0.0));
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as usize, y0 as usize);
+        let top = texel(x0, y0) * (1.0 - fx) + texel(x0 + 1, y0) * fx;
+        let bottom = texel(x0, y0 + 1) * (1.0 - fx) + texel(x0 + 1, y0 + 1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+}
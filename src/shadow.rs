@@ -0,0 +1,234 @@
+use glam::{vec2, vec3, Mat3, Mat4, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+use wavefront_obj::obj::{ObjSet, Primitive};
+
+use crate::model::to_vec3;
+use crate::{in_triangle, GroupFilter, Rect};
+
+// How shadow maps are sampled, see Renderer::set_shadow_mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShadowMode {
+    // percentage-closer filtering, see Renderer::set_pcf_kernel_size
+    #[default]
+    Pcf,
+    // Variance shadow maps: mean depth and depth squared are box blurred blur texels around
+    // every texel and sampled bilinearly, the lit fraction of fragments behind the mean is
+    // estimated by Chebyshev's inequality. Edges are soft (more so the larger blur is) for a
+    // single lookup, but some light bleeds where occluders overlap.
+    Variance {
+        blur: usize,
+    },
+}
+
+// Depth of the scene as seen from a directional light, orthographic projection along the light
+// direction fitted to the bounds of the scene. Biases are in texels, so like the extents they
+// follow the size of the scene, and models of any scale are shadowed the same way.
+#[derive(Clone)]
+pub(crate) struct ShadowMap {
+    size: usize,
+    // world to light space rotation, z axis points towards the light
+    rotation: Mat3,
+    // light space point mapped to the center of the map
+    center: Vec3,
+    // size of a texel in world units
+    texel: f32,
+    // the largest light space z (i.e. the nearest to the light) for every texel
+    depth: Vec<f32>,
+    // blurred mean z and z squared for ShadowMode::Variance, empty otherwise
+    moments: Vec<Vec2>,
+}
+
+impl ShadowMap {
+    // Render size x size shadow map of models (with their model to world transforms) lit from
+    // light (world space direction towards the light), skipping shapes filtered out by groups.
+    // None if there is nothing to render.
+    pub(crate) fn build(
+        models: &[(&ObjSet, Mat4)],
+        groups: &GroupFilter,
+        light: Vec3,
+        size: usize,
+        mode: ShadowMode,
+    ) -> Option<Self> {
+        let z_axis = light.normalize();
+        let up = if z_axis.y().abs() < 0.99 {
+            vec3(0.0, 1.0, 0.0)
+        } else {
+            vec3(1.0, 0.0, 0.0)
+        };
+        let x_axis = up.cross(z_axis).normalize();
+        let y_axis = z_axis.cross(x_axis);
+        let rotation = Mat3::from_cols(x_axis, y_axis, z_axis).transpose();
+
+        let mut min = Vec3::splat(f32::INFINITY);
+        let mut max = Vec3::splat(f32::NEG_INFINITY);
+        for (model, transform) in models {
+            for object in &model.objects {
+                for &v in &object.vertices {
+                    let p = rotation * transform.transform_point3(to_vec3(v));
+                    min = min.min(p);
+                    max = max.max(p);
+                }
+            }
+        }
+
+        if size == 0 || min.x() > max.x() {
+            return None;
+        }
+
+        // a bit of margin, so PCF kernels near the edges don't sample outside of the map
+        let extent = (max - min).truncate().max_element().max(1e-6) * 1.05;
+        let mut map = ShadowMap {
+            size,
+            rotation,
+            center: (min + max) / 2.0,
+            texel: extent / size as f32,
+            depth: Vec::new(),
+            moments: Vec::new(),
+        };
+        let mut depth = vec![f32::NEG_INFINITY; size * size];
+        let bounds = Rect {
+            x: 0,
+            y: 0,
+            width: size,
+            height: size,
+        };
+
+        // NOTE: both sides of triangles cast shadows
+        for (model, transform) in models {
+            for object in &model.objects {
+                let vertex = |i: usize| {
+                    map.to_light(transform.transform_point3(to_vec3(object.vertices[i])))
+                };
+                for geometry in &object.geometry {
+                    for shape in &geometry.shapes {
+                        if !groups.includes(&shape.groups) {
+                            continue;
+                        }
+                        let (a, b, c) = match shape.primitive {
+                            Primitive::Triangle((a, _, _), (b, _, _), (c, _, _)) => {
+                                (vertex(a), vertex(b), vertex(c))
+                            }
+                            _ => continue,
+                        };
+
+                        let (a2, b2, c2) = (a.truncate(), b.truncate(), c.truncate());
+                        in_triangle(a2, b2, c2, bounds, false, |x, y, bc| {
+                            let z = a.z() * bc.x() + b.z() * bc.y() + c.z() * bc.z();
+                            let d = &mut depth[x + y * size];
+                            *d = d.max(z);
+                        });
+                    }
+                }
+            }
+        }
+
+        if let ShadowMode::Variance { blur } = mode {
+            // texels without casters are as far from the light as the scene gets
+            let far = min.z() - map.center.z();
+            let moments = depth.iter().map(|&z| {
+                let z = if z == f32::NEG_INFINITY { far } else { z };
+                vec2(z, z * z)
+            });
+            map.moments = box_blur(moments.collect(), size, blur);
+        }
+        map.depth = depth;
+        Some(map)
+    }
+
+    // world space point to shadow map coordinates (x and y in texels, z towards the light)
+    pub(crate) fn to_light(&self, point: Vec3) -> Vec3 {
+        let p = self.rotation * point - self.center;
+        let half = self.size as f32 / 2.0;
+        vec3(p.x() / self.texel + half, p.y() / self.texel + half, p.z())
+    }
+
+    // Fraction of kernel x kernel texels around point (in shadow map coordinates) it's lit in,
+    // cos is the cosine of the angle between the surface normal and the light direction
+    pub(crate) fn visibility(&self, point: Vec3, cos: f32, kernel: usize) -> f32 {
+        if cos <= 0.0 {
+            // facing away from the light, it's not lit anyway
+            return 1.0;
+        }
+        if !self.moments.is_empty() {
+            return self.variance_visibility(point, cos);
+        }
+
+        // depth changes by up to texel * tan(angle) per texel on slopes, wider kernels reach
+        // further away texels
+        let radius = (kernel / 2) as i64;
+        let slope = (1.0 - cos * cos).max(0.0).sqrt() / cos.max(0.1);
+        let bias = self.texel * (1.0 + radius as f32) * (1.0 + slope);
+
+        let center = vec2(point.x().round(), point.y().round());
+        let size = self.size as i64;
+        let mut lit = 0;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let (x, y) = (center.x() as i64 + dx, center.y() as i64 + dy);
+                let outside = x < 0 || y < 0 || x >= size || y >= size;
+                if outside || point.z() + bias >= self.depth[(x + y * size) as usize] {
+                    lit += 1;
+                }
+            }
+        }
+
+        let side = 2 * radius + 1;
+        lit as f32 / (side * side) as f32
+    }
+
+    // upper bound of the lit fraction by Chebyshev's inequality, see ShadowMode::Variance
+    fn variance_visibility(&self, point: Vec3, cos: f32) -> f32 {
+        // bilinear, texel centers are at integer coordinates
+        let max = (self.size - 1) as f32;
+        let (x, y) = (point.x().max(0.0).min(max), point.y().max(0.0).min(max));
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let texel = |dx: f32, dy: f32| {
+            let (x, y) = ((x0 + dx).min(max) as usize, (y0 + dy).min(max) as usize);
+            self.moments[x + y * self.size]
+        };
+        let top = texel(0.0, 0.0).lerp(texel(1.0, 0.0), fx);
+        let bottom = texel(0.0, 1.0).lerp(texel(1.0, 1.0), fx);
+        let moments = top.lerp(bottom, fy);
+
+        // the receiver still covers its own texel on slopes
+        let slope = (1.0 - cos * cos).max(0.0).sqrt() / cos.max(0.1);
+        let z = point.z() + self.texel * (1.0 + slope);
+        let mean = moments.x();
+        if z >= mean {
+            return 1.0;
+        }
+        let variance = (moments.y() - mean * mean).max((self.texel * 0.1).powi(2));
+        variance / (variance + (mean - z) * (mean - z))
+    }
+}
+
+// separable box blur of size x size texels by radius texels, clamped at the edges
+fn box_blur(mut texels: Vec<Vec2>, size: usize, radius: usize) -> Vec<Vec2> {
+    if radius == 0 {
+        return texels;
+    }
+
+    let weight = 1.0 / (2 * radius + 1) as f32;
+    let at = |i: usize, d: isize| (i as isize + d).max(0).min(size as isize - 1) as usize;
+    let mut blurred = vec![Vec2::zero(); texels.len()];
+    for horizontal in [true, false] {
+        for y in 0..size {
+            for x in 0..size {
+                let mut sum = Vec2::zero();
+                for d in -(radius as isize)..=radius as isize {
+                    let (sx, sy) = if horizontal {
+                        (at(x, d), y)
+                    } else {
+                        (x, at(y, d))
+                    };
+                    sum += texels[sx + sy * size];
+                }
+                blurred[x + y * size] = sum * weight;
+            }
+        }
+        std::mem::swap(&mut texels, &mut blurred);
+    }
+    texels
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use glam::{vec3, Vec3};
+use wavefront_obj::obj::{Geometry, ObjSet, Object, Primitive, Shape, TVertex, VTNIndex};
+
+use crate::model::to_vertex;
+use crate::Mesh;
+
+// Built-in geometry for tests and examples, wound counter-clockwise seen from outside (like
+// OBJ files) and with normals and UVs, but without a texture
+impl Mesh {
+    // Sphere of radius 1 around the origin: icosahedron subdivided `subdivisions` times (every
+    // level makes 4 times more triangles), normals point radially, UVs are its latitude and
+    // longitude
+    pub fn sphere(subdivisions: u32) -> Self {
+        let t = (1.0 + 5f32.sqrt()) / 2.0;
+        let mut positions: Vec<Vec3> = [
+            (-1.0, t, 0.0),
+            (1.0, t, 0.0),
+            (-1.0, -t, 0.0),
+            (1.0, -t, 0.0),
+            (0.0, -1.0, t),
+            (0.0, 1.0, t),
+            (0.0, -1.0, -t),
+            (0.0, 1.0, -t),
+            (t, 0.0, -1.0),
+            (t, 0.0, 1.0),
+            (-t, 0.0, -1.0),
+            (-t, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|&(x, y, z)| vec3(x, y, z).normalize())
+        .collect();
+        let mut triangles: Vec<[usize; 3]> = vec![
+            [0, 11, 5],
+            [0, 5, 1],
+            [0, 1, 7],
+            [0, 7, 10],
+            [0, 10, 11],
+            [1, 5, 9],
+            [5, 11, 4],
+            [11, 10, 2],
+            [10, 7, 6],
+            [7, 1, 8],
+            [3, 9, 4],
+            [3, 4, 2],
+            [3, 2, 6],
+            [3, 6, 8],
+            [3, 8, 9],
+            [4, 9, 5],
+            [2, 4, 11],
+            [6, 2, 10],
+            [8, 6, 7],
+            [9, 8, 1],
+        ];
+
+        for _ in 0..subdivisions {
+            // edge => vertex in its middle, shared by both triangles next to it
+            let mut middles: HashMap<(usize, usize), usize> = HashMap::new();
+            let mut middle = |a: usize, b: usize| {
+                *middles.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    positions.push((positions[a] + positions[b]).normalize());
+                    positions.len() - 1
+                })
+            };
+            triangles = triangles
+                .iter()
+                .flat_map(|&[a, b, c]| {
+                    let (ab, bc, ca) = (middle(a, b), middle(b, c), middle(c, a));
+                    vec![[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        // UVs are per corner, so triangles across the seam (and at the poles) get their own
+        let mut tex_vertices = Vec::new();
+        let mut shapes = Vec::with_capacity(triangles.len());
+        for &[a, b, c] in &triangles {
+            let uvs = [a, b, c].map(|i| {
+                let p = positions[i];
+                let pole = p.x().abs() < 1e-6 && p.z().abs() < 1e-6;
+                let u = 0.5 + p.z().atan2(p.x()) / (2.0 * std::f32::consts::PI);
+                let v = 0.5 + p.y().asin() / std::f32::consts::PI;
+                (Some(u).filter(|_| !pole), v)
+            });
+            let us: Vec<f32> = uvs.iter().filter_map(|uv| uv.0).collect();
+            let wraps = us.iter().any(|&u| u > 0.75) && us.iter().any(|&u| u < 0.25);
+            let unwrap = |u: f32| if wraps && u < 0.5 { u + 1.0 } else { u };
+            let mean = us.iter().map(|&u| unwrap(u)).sum::<f32>() / us.len() as f32;
+
+            let mut corner = |i: usize, (u, v): (Option<f32>, f32)| {
+                tex_vertices.push(TVertex {
+                    u: u.map_or(mean, unwrap) as f64,
+                    v: v as f64,
+                    w: 0.0,
+                });
+                (i, Some(tex_vertices.len() - 1), Some(i))
+            };
+            let (a, b, c) = (corner(a, uvs[0]), corner(b, uvs[1]), corner(c, uvs[2]));
+            shapes.push(triangle(a, b, c));
+        }
+
+        let vertices: Vec<_> = positions.into_iter().map(to_vertex).collect();
+        mesh(Object {
+            name: "sphere".to_owned(),
+            normals: vertices.clone(),
+            vertices,
+            tex_vertices,
+            geometry: vec![Geometry {
+                material_name: None,
+                shapes,
+            }],
+        })
+    }
+
+    // Cube from -1 to 1 with flat faces, every one mapped to the whole texture
+    pub fn cube() -> Self {
+        let mut vertices = Vec::with_capacity(24);
+        let mut normals = Vec::with_capacity(6);
+        let mut shapes = Vec::with_capacity(12);
+        for axis in 0..3 {
+            for &sign in &[1.0, -1.0] {
+                // (u, v, normal) is a right-handed basis, so faces wind outwards
+                let mut n = Vec3::zero();
+                n[axis] = sign;
+                let mut u = Vec3::zero();
+                u[(axis + 1) % 3] = sign;
+                let v = n.cross(u);
+
+                let i = vertices.len();
+                for &(du, dv) in &[(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                    vertices.push(to_vertex(n + u * du + v * dv));
+                }
+                normals.push(to_vertex(n));
+                let n = normals.len() - 1;
+                let corner = |k: usize| (i + k, Some(k), Some(n));
+                shapes.push(triangle(corner(0), corner(1), corner(2)));
+                shapes.push(triangle(corner(0), corner(2), corner(3)));
+            }
+        }
+
+        mesh(Object {
+            name: "cube".to_owned(),
+            vertices,
+            tex_vertices: square_uvs(),
+            normals,
+            geometry: vec![Geometry {
+                material_name: None,
+                shapes,
+            }],
+        })
+    }
+
+    // Square of side size in the xz plane around the origin, facing +y
+    pub fn plane(size: f32) -> Self {
+        let s = size / 2.0;
+        let vertices = [(-s, s), (s, s), (s, -s), (-s, -s)]
+            .iter()
+            .map(|&(x, z)| to_vertex(vec3(x, 0.0, z)))
+            .collect();
+        let corner = |k: usize| (k, Some(k), Some(0));
+
+        mesh(Object {
+            name: "plane".to_owned(),
+            vertices,
+            tex_vertices: square_uvs(),
+            normals: vec![to_vertex(Vec3::unit_y())],
+            geometry: vec![Geometry {
+                material_name: None,
+                shapes: vec![
+                    triangle(corner(0), corner(1), corner(2)),
+                    triangle(corner(0), corner(2), corner(3)),
+                ],
+            }],
+        })
+    }
+}
+
+fn square_uvs() -> Vec<TVertex> {
+    [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+        .iter()
+        .map(|&(u, v)| TVertex { u, v, w: 0.0 })
+        .collect()
+}
+
+fn triangle(a: VTNIndex, b: VTNIndex, c: VTNIndex) -> Shape {
+    Shape {
+        primitive: Primitive::Triangle(a, b, c),
+        groups: Vec::new(),
+        smoothing_groups: Vec::new(),
+    }
+}
+
+fn mesh(object: Object) -> Mesh {
+    Mesh {
+        model: ObjSet {
+            material_library: None,
+            objects: vec![object],
+        },
+        texture: None,
+    }
+}
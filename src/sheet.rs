@@ -0,0 +1,156 @@
+use anyhow::{bail, Context, Result};
+use glam::{vec3, Mat4, Vec3};
+use wavefront_obj::obj::ObjSet;
+
+use crate::{read_model, read_model_materials, Camera, Color, Image, LoadOptions, Renderer, Scene};
+
+// Transform moving model into the unit sphere around the origin (its bounding box centered in
+// it), e.g. to frame models of any size and place with the same camera
+pub fn fit_transform(model: &ObjSet) -> Mat4 {
+    let vertices = model.objects.iter().flat_map(|object| &object.vertices);
+    let points: Vec<Vec3> = vertices
+        .map(|v| vec3(v.x as f32, v.y as f32, v.z as f32))
+        .collect();
+    if points.is_empty() {
+        return Mat4::identity();
+    }
+
+    let min = points.iter().fold(points[0], |min, &p| min.min(p));
+    let max = points.iter().fold(points[0], |max, &p| max.max(p));
+    let center = (min + max) / 2.0;
+    let radius = points
+        .iter()
+        .map(|&p| (p - center).length())
+        .fold(0.0, f32::max);
+    let scale = if radius > 0.0 { 1.0 / radius } else { 1.0 };
+    Mat4::from_scale(Vec3::splat(scale)) * Mat4::from_translation(-center)
+}
+
+// Grid of cell_size x cell_size thumbnails of the models (OBJ files with their own materials)
+// in rows of cols, every one fit into its cell (see fit_transform) and labeled with its file
+// name. Cells after the last model are left empty.
+pub fn contact_sheet(models: &[&str], cell_size: usize, cols: usize) -> Result<Image> {
+    if cell_size == 0 || cols == 0 {
+        bail!("Contact sheet needs cells of at least 1x1 pixels in at least 1 column");
+    }
+    let rows = models.len().div_ceil(cols);
+    let mut sheet = Image::new((cols * cell_size) as u32, (rows * cell_size) as u32);
+
+    // the default three-quarter view, with a margin around the bounding sphere
+    let mut camera = Camera::new(
+        vec3(0.5, 0.0, -0.5),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    camera.zoom(0.9);
+    for (i, &path) in models.iter().enumerate() {
+        let model = read_model(path, &LoadOptions::default())
+            .with_context(|| format!("Failed to read model {:?}", path))?;
+        let materials = read_model_materials(path, &model)?;
+        let transform = fit_transform(&model);
+
+        let mut renderer = Renderer::new(camera.clone(), (cell_size, cell_size));
+        // thumbnails just show the fallback for missing textures
+        let _ = renderer.set_materials(materials);
+        let mut scene = Scene::single(model, None);
+        scene.instances[0].transform = transform;
+        renderer.render_scene(&scene);
+        renderer.flipv();
+
+        let mut cell = renderer.image().clone();
+        let name = std::path::Path::new(path)
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        label(&mut cell, &name);
+        let (x, y) = ((i % cols) * cell_size, (i / cols) * cell_size);
+        image::imageops::replace(&mut sheet, &cell, x as u32, y as u32);
+    }
+    Ok(sheet)
+}
+
+// render the contact sheet of models (see contact_sheet) to image file out
+pub fn render_contact_sheet(
+    models: &[&str],
+    cell_size: usize,
+    cols: usize,
+    out: &str,
+) -> Result<()> {
+    contact_sheet(models, cell_size, cols)?.save(out)?;
+    Ok(())
+}
+
+// 3x5 pixel glyphs, the top row first and the leftmost pixel in the highest of 3 bits
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        ' ' => [0b000; 5],
+        _ => [0b111, 0b001, 0b010, 0b000, 0b010],
+    }
+}
+
+// write text in white on black along the bottom of image (cut off where it doesn't fit), glyphs
+// are scaled up with the image so they stay readable
+fn label(image: &mut Image, text: &str) {
+    let scale = (image.width() / 64).max(1);
+    let (advance, height) = (4 * scale, 7 * scale);
+    if image.height() < height {
+        return;
+    }
+
+    let top = image.height() - height;
+    let width = (text.chars().count() as u32 * advance + scale).min(image.width());
+    for y in top..image.height() {
+        for x in 0..width {
+            image.put_pixel(x, y, Color::from([0, 0, 0]));
+        }
+    }
+    for (i, c) in text.chars().enumerate() {
+        let rows = glyph(c);
+        for y in 0..5 * scale {
+            for x in 0..3 * scale {
+                let px = scale + i as u32 * advance + x;
+                if px < image.width() && rows[(y / scale) as usize] & (0b100 >> (x / scale)) != 0 {
+                    image.put_pixel(px, top + scale + y, Color::from([255, 255, 255]));
+                }
+            }
+        }
+    }
+}
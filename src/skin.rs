@@ -0,0 +1,96 @@
+use anyhow::{bail, Result};
+use glam::{Mat4, Vec3};
+use wavefront_obj::obj::{Object, Primitive};
+
+use crate::model::{to_vec3, to_vertex};
+
+// Up to 4 bones influencing a single vertex, unused slots should have zero weight
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BoneWeights {
+    pub bones: [usize; 4],
+    pub weights: [f32; 4],
+}
+
+impl BoneWeights {
+    // vertex fully attached to a single bone
+    pub fn single(bone: usize) -> Self {
+        BoneWeights {
+            bones: [bone, 0, 0, 0],
+            weights: [1.0, 0.0, 0.0, 0.0],
+        }
+    }
+
+    // weighted sum of bone matrices (weights are normalized, so they don't have to sum up to 1)
+    fn blend(&self, bones: &[Mat4]) -> Mat4 {
+        let total: f32 = self.weights.iter().sum();
+        if total <= 0.0 {
+            return Mat4::identity();
+        }
+
+        self.bones
+            .iter()
+            .zip(self.weights.iter())
+            .filter(|(_, &weight)| weight > 0.0)
+            .fold(Mat4::zero(), |acc, (&bone, &weight)| {
+                acc + bones[bone] * (weight / total)
+            })
+    }
+}
+
+// Linear blend skinning: returns a copy of object where every vertex (and normal) is transformed
+// by the weighted sum of its bone matrices. weights are indexed the same way as object.vertices,
+// it fails if their numbers differ or a weighted bone isn't in bones.
+pub fn skin(object: &Object, weights: &[BoneWeights], bones: &[Mat4]) -> Result<Object> {
+    if object.vertices.len() != weights.len() {
+        bail!(
+            "Object {:?} has {} vertices but {} bone weights",
+            object.name,
+            object.vertices.len(),
+            weights.len()
+        );
+    }
+    let weighted = weights.iter().flat_map(|w| {
+        w.bones
+            .iter()
+            .zip(w.weights.iter())
+            .filter(|(_, &weight)| weight > 0.0)
+            .map(|(&bone, _)| bone)
+    });
+    if let Some(bone) = weighted.max().filter(|&bone| bone >= bones.len()) {
+        bail!("Bone {} is out of {} bones", bone, bones.len());
+    }
+
+    let transforms: Vec<Mat4> = weights.iter().map(|w| w.blend(bones)).collect();
+
+    let mut skinned = object.clone();
+    for (vertex, transform) in skinned.vertices.iter_mut().zip(&transforms) {
+        *vertex = to_vertex(transform.transform_point3(to_vec3(*vertex)));
+    }
+
+    // normals are indexed separately, so take the transform of a vertex they're used with
+    let mut normal_transforms = vec![None; object.normals.len()];
+    for geometry in &object.geometry {
+        for shape in &geometry.shapes {
+            if let Primitive::Triangle(a, b, c) = shape.primitive {
+                for &(v, _, n) in &[a, b, c] {
+                    if let Some(n) = n {
+                        normal_transforms[n].get_or_insert(v);
+                    }
+                }
+            }
+        }
+    }
+
+    for (normal, vertex) in skinned.normals.iter_mut().zip(normal_transforms) {
+        if let Some(vertex) = vertex {
+            let n = transforms[vertex].transform_vector3(to_vec3(*normal));
+            *normal = to_vertex(if n.length_squared() > 0.0 {
+                n.normalize()
+            } else {
+                Vec3::zero()
+            });
+        }
+    }
+
+    Ok(skinned)
+}
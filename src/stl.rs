@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use glam::vec3;
+use wavefront_obj::obj::{Geometry, ObjSet, Object, Primitive, Shape};
+
+use crate::model::to_vertex;
+use crate::{check_triangle_count, prepare_model, LoadOptions};
+
+// Load binary STL file as a single object without UVs or materials. STL stores every triangle
+// with its own corners, so equal ones are welded (and closer ones with options.weld_tolerance)
+// to compute smooth normals, stored facet normals are ignored.
+pub fn read_stl(path: &str, options: &LoadOptions) -> Result<ObjSet> {
+    let data = std::fs::read(path)?;
+    // 80 bytes of header, triangle count and 50 bytes per triangle
+    let count = data
+        .get(80..84)
+        .map(|count| u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize)
+        .context("STL file is too short")?;
+    if data.len() != 84 + count * 50 {
+        bail!(
+            "STL file has {} bytes, expected {} for {} triangles (ASCII STL isn't supported)",
+            data.len(),
+            84 + count * 50,
+            count
+        );
+    }
+    check_triangle_count(count, options)?;
+
+    let mut vertices = Vec::new();
+    let mut indices = HashMap::new();
+    let mut shapes = Vec::with_capacity(count);
+    for triangle in data[84..].chunks_exact(50) {
+        let float = |i: usize| {
+            let b = &triangle[i * 4..i * 4 + 4];
+            f32::from_le_bytes([b[0], b[1], b[2], b[3]])
+        };
+        // after the facet normal, equal corners become the same vertex
+        let mut corner = |i: usize| {
+            let p = [float(i * 3), float(i * 3 + 1), float(i * 3 + 2)];
+            // + 0.0 turns -0.0 into 0.0
+            let key = p.map(|x| (x + 0.0).to_bits());
+            let index = *indices.entry(key).or_insert_with(|| {
+                vertices.push(to_vertex(vec3(p[0], p[1], p[2])));
+                vertices.len() - 1
+            });
+            (index, None, None)
+        };
+        shapes.push(Shape {
+            primitive: Primitive::Triangle(corner(1), corner(2), corner(3)),
+            groups: Vec::new(),
+            smoothing_groups: Vec::new(),
+        });
+    }
+
+    let name = std::path::Path::new(path)
+        .file_stem()
+        .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+    let mut model = ObjSet {
+        material_library: None,
+        objects: vec![Object {
+            name,
+            vertices,
+            tex_vertices: Vec::new(),
+            normals: Vec::new(),
+            geometry: vec![Geometry {
+                material_name: None,
+                shapes,
+            }],
+        }],
+    };
+    prepare_model(&mut model, options);
+    Ok(model)
+}
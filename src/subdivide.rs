@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use glam::Vec3;
+use wavefront_obj::obj::{ObjSet, Object, Primitive, Shape, TVertex, VTNIndex};
+
+use crate::model::{to_vec3, to_vertex};
+
+// Smooth model with levels of Loop subdivision: every triangle is split into 4 and vertices are
+// moved towards weighted averages of their neighbours. Vertex normals of subdivided triangles
+// are dropped (so they are computed for the smoothed surface), UV coordinates are interpolated.
+// NOTE: vertices duplicated along seams split the surface, see weld_vertices
+pub fn loop_subdivide(model: &mut ObjSet, levels: u32) {
+    for object in &mut model.objects {
+        for _ in 0..levels {
+            subdivide_object(object);
+        }
+    }
+}
+
+fn edge(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+fn subdivide_object(object: &mut Object) {
+    let triangles: Vec<[VTNIndex; 3]> = object
+        .geometry
+        .iter()
+        .flat_map(|geometry| geometry.shapes.iter())
+        .filter_map(|shape| match shape.primitive {
+            Primitive::Triangle(a, b, c) => Some([a, b, c]),
+            _ => None,
+        })
+        .collect();
+    if triangles.is_empty() {
+        return;
+    }
+
+    // vertices opposite to every edge, one per triangle sharing it
+    let mut opposite: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for t in &triangles {
+        for i in 0..3 {
+            let (a, b, c) = (t[i].0, t[(i + 1) % 3].0, t[(i + 2) % 3].0);
+            opposite.entry(edge(a, b)).or_default().push(c);
+        }
+    }
+
+    let positions: Vec<Vec3> = object.vertices.iter().map(|&v| to_vec3(v)).collect();
+
+    // original vertices are moved, neighbours on boundary edges are tracked separately because
+    // boundaries are smoothed as curves
+    let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); positions.len()];
+    let mut boundary: Vec<Vec<usize>> = vec![Vec::new(); positions.len()];
+    for (&(a, b), faces) in &opposite {
+        neighbours[a].push(b);
+        neighbours[b].push(a);
+        if faces.len() == 1 {
+            boundary[a].push(b);
+            boundary[b].push(a);
+        }
+    }
+
+    let mut vertices: Vec<Vec3> = positions
+        .iter()
+        .enumerate()
+        .map(|(v, &p)| match (neighbours[v].len(), boundary[v].len()) {
+            (0, _) => p,
+            (_, 2) => p * 0.75 + (positions[boundary[v][0]] + positions[boundary[v][1]]) * 0.125,
+            // non-manifold corner, keep it in place
+            (_, b) if b > 0 => p,
+            (n, _) => {
+                let beta = if n == 3 {
+                    3.0 / 16.0
+                } else {
+                    3.0 / (8.0 * n as f32)
+                };
+                let sum = neighbours[v]
+                    .iter()
+                    .fold(Vec3::zero(), |acc, &u| acc + positions[u]);
+                p * (1.0 - n as f32 * beta) + sum * beta
+            }
+        })
+        .collect();
+
+    // new vertex for every edge, shared by the triangles on both sides of it
+    let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut midpoint = |a: usize, b: usize| -> usize {
+        let key = edge(a, b);
+        *midpoints.entry(key).or_insert_with(|| {
+            let p = match opposite[&key].as_slice() {
+                &[c, d] => {
+                    (positions[a] + positions[b]) * 0.375 + (positions[c] + positions[d]) * 0.125
+                }
+                _ => (positions[a] + positions[b]) * 0.5,
+            };
+            vertices.push(p);
+            vertices.len() - 1
+        })
+    };
+
+    let tex_vertices = &mut object.tex_vertices;
+    let mut tex_midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut tex_midpoint = |a: Option<usize>, b: Option<usize>| -> Option<usize> {
+        let key = edge(a?, b?);
+        let index = *tex_midpoints.entry(key).or_insert_with(|| {
+            let (ta, tb) = (tex_vertices[key.0], tex_vertices[key.1]);
+            tex_vertices.push(TVertex {
+                u: (ta.u + tb.u) / 2.0,
+                v: (ta.v + tb.v) / 2.0,
+                w: (ta.w + tb.w) / 2.0,
+            });
+            tex_vertices.len() - 1
+        });
+        Some(index)
+    };
+
+    for geometry in &mut object.geometry {
+        let mut shapes = Vec::with_capacity(geometry.shapes.len() * 4);
+        for shape in geometry.shapes.drain(..) {
+            let [a, b, c] = match shape.primitive {
+                Primitive::Triangle(a, b, c) => [a, b, c],
+                _ => {
+                    shapes.push(shape);
+                    continue;
+                }
+            };
+
+            let mut split = |(v0, t0, _): VTNIndex, (v1, t1, _): VTNIndex| -> VTNIndex {
+                (midpoint(v0, v1), tex_midpoint(t0, t1), None)
+            };
+            let (ab, bc, ca) = (split(a, b), split(b, c), split(c, a));
+
+            let corner = |(v, t, _): VTNIndex| (v, t, None);
+            let (a, b, c) = (corner(a), corner(b), corner(c));
+            for &(x, y, z) in &[(a, ab, ca), (ab, b, bc), (ca, bc, c), (ab, bc, ca)] {
+                shapes.push(Shape {
+                    primitive: Primitive::Triangle(x, y, z),
+                    groups: shape.groups.clone(),
+                    smoothing_groups: shape.smoothing_groups.clone(),
+                });
+            }
+        }
+
+        geometry.shapes = shapes;
+    }
+
+    object.vertices = vertices.into_iter().map(to_vertex).collect();
+}
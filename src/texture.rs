@@ -0,0 +1,150 @@
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{Color, Texture};
+
+// Texture generators and utilities (Texture is a foreign type, hence the extension trait)
+pub trait TextureExt {
+    // tiles x tiles checkerboard, top-left tile is color_a
+    fn checkerboard(width: u32, height: u32, tiles: u32, color_a: Color, color_b: Color) -> Self;
+
+    // u in red, v in green, with white lines between cells x cells grid cells
+    fn uv_grid(width: u32, height: u32, cells: u32) -> Self;
+
+    // mip levels from the texture itself ([0]) down to 1x1, every level is box filtered from the
+    // previous one (rows are filtered in parallel with the rayon feature)
+    fn mip_chain(&self) -> Vec<Self>
+    where
+        Self: Sized;
+
+    // Grow UV islands into the empty (black, there is no alpha) texels around them by one texel
+    // per iteration, every one gets the average of its non-empty neighbours. Keeps filtering and
+    // mip levels from pulling black into island borders.
+    fn dilate(&mut self, iterations: u32);
+}
+
+// half (rounded down, at least 1) sized texture, every texel is the rounded average of the
+// block of texels it covers (2x2, or up to 3 wide along odd sized dimensions)
+fn downsample(texture: &Texture) -> Texture {
+    let (width, height) = texture.dimensions();
+    let (w, h) = ((width / 2).max(1), (height / 2).max(1));
+
+    let row = |y: u32, row: &mut [u8]| {
+        let (y0, y1) = (y * height / h, (y + 1) * height / h);
+        for x in 0..w {
+            let (x0, x1) = (x * width / w, (x + 1) * width / w);
+            let mut sum = [0u32; 3];
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    let p = texture.get_pixel(sx, sy);
+                    for (s, &c) in sum.iter_mut().zip(p.0.iter()) {
+                        *s += c as u32;
+                    }
+                }
+            }
+
+            let count = (x1 - x0) * (y1 - y0);
+            for (c, s) in row[x as usize * 3..][..3].iter_mut().zip(sum.iter()) {
+                *c = ((s + count / 2) / count) as u8;
+            }
+        }
+    };
+
+    let mut buffer = vec![0; w as usize * h as usize * 3];
+    let stride = w as usize * 3;
+
+    #[cfg(feature = "rayon")]
+    buffer
+        .par_chunks_mut(stride)
+        .enumerate()
+        .for_each(|(y, r)| row(y as u32, r));
+
+    #[cfg(not(feature = "rayon"))]
+    buffer
+        .chunks_mut(stride)
+        .enumerate()
+        .for_each(|(y, r)| row(y as u32, r));
+
+    Texture::from_raw(w, h, buffer).expect("buffer size matches dimensions")
+}
+
+impl TextureExt for Texture {
+    fn checkerboard(width: u32, height: u32, tiles: u32, color_a: Color, color_b: Color) -> Self {
+        let tiles = tiles.max(1);
+        Texture::from_fn(width, height, |x, y| {
+            let tx = x * tiles / width;
+            let ty = y * tiles / height;
+            if (tx + ty).is_multiple_of(2) {
+                color_a
+            } else {
+                color_b
+            }
+        })
+    }
+
+    fn uv_grid(width: u32, height: u32, cells: u32) -> Self {
+        let cells = cells.max(1);
+        Texture::from_fn(width, height, |x, y| {
+            let on_line = |p: u32, size: u32| {
+                let cell = size / cells;
+                cell != 0 && (p.is_multiple_of(cell) || p == size - 1)
+            };
+
+            if on_line(x, width) || on_line(y, height) {
+                return Color::from([0xff, 0xff, 0xff]);
+            }
+
+            let u = x * 0xff / (width - 1).max(1);
+            let v = y * 0xff / (height - 1).max(1);
+            Color::from([u as u8, v as u8, 0])
+        })
+    }
+
+    fn mip_chain(&self) -> Vec<Self> {
+        let mut chain = vec![self.clone()];
+        while let Some(last) = chain.last() {
+            if last.width() <= 1 && last.height() <= 1 {
+                break;
+            }
+            chain.push(downsample(last));
+        }
+        chain
+    }
+
+    fn dilate(&mut self, iterations: u32) {
+        let empty = Color::from([0, 0, 0]);
+        let (width, height) = self.dimensions();
+        for _ in 0..iterations {
+            let source = self.clone();
+            let mut changed = false;
+            for (x, y, texel) in self.enumerate_pixels_mut() {
+                if *texel != empty {
+                    continue;
+                }
+
+                let (mut sum, mut count) = ([0u32; 3], 0);
+                for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                    for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                        let neighbour = source.get_pixel(nx, ny);
+                        if *neighbour != empty {
+                            for (s, &c) in sum.iter_mut().zip(neighbour.0.iter()) {
+                                *s += c as u32;
+                            }
+                            count += 1;
+                        }
+                    }
+                }
+                if count != 0 {
+                    let average = |s: u32| ((s + count / 2) / count) as u8;
+                    *texel = Color::from([average(sum[0]), average(sum[1]), average(sum[2])]);
+                    changed = true;
+                }
+            }
+
+            // everything is filled already
+            if !changed {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::{Camera, Projection};
+
+// Camera pose at time (in seconds), fov is the vertical field of view (in radians) of the
+// perspective projection
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Keyframe {
+    pub time: f32,
+    pub lookfrom: Vec3,
+    pub lookat: Vec3,
+    pub fov: f32,
+}
+
+// How poses between two keyframes are interpolated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    // at constant speed from one keyframe to the next
+    #[default]
+    Linear,
+    // easing in and out of every keyframe
+    Smoothstep,
+}
+
+// Camera animation through keyframes (sorted by time), rendered as frame_rate frames per second
+// from the first keyframe to the last one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Timeline {
+    pub interpolation: Interpolation,
+    pub frame_rate: f32,
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Timeline {
+            interpolation: Interpolation::Linear,
+            frame_rate: 24.0,
+            keyframes: Vec::new(),
+        }
+    }
+}
+
+impl Timeline {
+    // number of frames, one at the first keyframe and then every 1 / frame_rate seconds up to
+    // the last one (0 without keyframes)
+    pub fn frames(&self) -> usize {
+        match (self.keyframes.first(), self.keyframes.last()) {
+            (Some(first), Some(last)) if self.frame_rate > 0.0 => {
+                let duration = (last.time - first.time).max(0.0);
+                // NOTE: a frame just short of the end because of rounding still counts
+                (duration * self.frame_rate + 1e-3) as usize + 1
+            }
+            (Some(_), Some(_)) => 1,
+            _ => 0,
+        }
+    }
+
+    // time of frame number frame
+    pub fn frame_time(&self, frame: usize) -> f32 {
+        let start = self.keyframes.first().map_or(0.0, |first| first.time);
+        if self.frame_rate > 0.0 {
+            start + frame as f32 / self.frame_rate
+        } else {
+            start
+        }
+    }
+
+    // pose at time interpolated between the keyframes around it, that of the first (last)
+    // keyframe before (after) them, None without keyframes
+    pub fn pose(&self, time: f32) -> Option<Keyframe> {
+        let first = *self.keyframes.first()?;
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        if next == 0 {
+            return Some(Keyframe { time, ..first });
+        }
+        let from = self.keyframes[next - 1];
+        let to = match self.keyframes.get(next) {
+            Some(&to) => to,
+            None => return Some(Keyframe { time, ..from }),
+        };
+
+        let t = (time - from.time) / (to.time - from.time);
+        let t = match self.interpolation {
+            Interpolation::Linear => t,
+            Interpolation::Smoothstep => t * t * (3.0 - 2.0 * t),
+        };
+        Some(Keyframe {
+            time,
+            lookfrom: from.lookfrom.lerp(to.lookfrom, t),
+            lookat: from.lookat.lerp(to.lookat, t),
+            fov: from.fov + (to.fov - from.fov) * t,
+        })
+    }
+
+    // camera with the perspective projection and pose at time and the up vector, handedness and
+    // far plane of camera (which is returned as is without keyframes)
+    pub fn camera(&self, time: f32, camera: &Camera) -> Camera {
+        let pose = match self.pose(time) {
+            Some(pose) => pose,
+            None => return camera.clone(),
+        };
+        let mut animated = Camera::with_projection(
+            pose.lookfrom,
+            pose.lookat,
+            camera.up,
+            Projection::Perspective { fov: pose.fov },
+        );
+        animated.set_handedness(camera.handedness);
+        animated.far = camera.far;
+        animated
+    }
+}
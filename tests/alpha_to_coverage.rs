@@ -0,0 +1,123 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_materials, read_model, Camera, Image, LoadOptions, Renderer, ResolveFilter, Scene,
+};
+
+const SIZE: u32 = 64;
+
+// square over the whole view, in front of a smaller one
+const QUADS: &str = "
+o leaf
+v -1 -1 0.5
+v 1 -1 0.5
+v 1 1 0.5
+v -1 1 0.5
+usemtl leaf
+f 1 2 3
+f 1 3 4
+o wall
+v -0.5 -0.5 0
+v 0.5 -0.5 0
+v 0.5 0.5 0
+v -0.5 0.5 0
+usemtl wall
+f 5 6 7
+f 5 7 8
+";
+
+// renderer that drew the green leaf with alpha over the red wall
+fn render(alpha: f32, alpha_to_coverage: bool) -> Renderer {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (obj, mtl) = (dir.join("leaf_quads.obj"), dir.join("leaf_quads.mtl"));
+    std::fs::write(&obj, QUADS).unwrap();
+    let leaf = format!(
+        "newmtl leaf\nKd 0 1 0\nd {}\nnewmtl wall\nKd 1 0 0\n",
+        alpha
+    );
+    std::fs::write(&mtl, leaf).unwrap();
+    let model = read_model(obj.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_light_direction(vec3(0.0, 0.0, 1.0));
+    renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+    renderer.set_alpha_to_coverage(alpha_to_coverage);
+    renderer.render_scene(&Scene::single(model, None));
+    renderer
+}
+
+fn is_green(image: &Image, x: u32, y: u32) -> bool {
+    let p = image.get_pixel(x, y);
+    p[1] > 0 && p[0] == 0
+}
+
+#[test]
+fn half_alpha_covers_every_other_pixel() {
+    let renderer = render(0.5, true);
+    let image = renderer.image();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            // a checkerboard of the leaf and what's behind it, unblended
+            let green = is_green(image, x, y);
+            assert_eq!(green, (x + y) % 2 == 0, "({}, {})", x, y);
+            let p = image.get_pixel(x, y);
+            assert!(green || p[1] == 0, "{:?}", p);
+        }
+    }
+    // and the wall is seen through it
+    let center = SIZE / 2;
+    assert!(image.get_pixel(center, center + 1)[0] > 0);
+
+    // covered pixels are opaque, with the leaf's depth
+    let depth = renderer.linear_depth();
+    let at = |x: u32, y: u32| depth[(x + y * SIZE) as usize].unwrap();
+    assert!(at(center, center) < at(center, center + 1));
+}
+
+#[test]
+fn coverage_follows_alpha() {
+    for (alpha, covered) in [(0.25, 4), (0.5, 8), (0.75, 12), (1.0, 16)] {
+        let renderer = render(alpha, true);
+        let image = renderer.image();
+        // of every 4x4 block
+        for by in 0..SIZE / 4 {
+            for bx in 0..SIZE / 4 {
+                let count = (0..16)
+                    .filter(|i| is_green(image, bx * 4 + i % 4, by * 4 + i / 4))
+                    .count();
+                assert_eq!(count, covered, "{} at ({}, {})", alpha, bx, by);
+            }
+        }
+    }
+}
+
+#[test]
+fn supersampled_coverage_is_partial() {
+    let leaf = render(1.0, false).resolve(2, ResolveFilter::Box).unwrap();
+    // without the leaf
+    let wall = render(0.0, true).resolve(2, ResolveFilter::Box).unwrap();
+    let dithered = render(0.5, true).resolve(2, ResolveFilter::Box).unwrap();
+    // not a hard edge between covered and uncovered pixels, every one is half the leaf and half
+    // what's behind it
+    let half = |a: u8, b: u8| (a as i32 + b as i32 + 1) / 2;
+    let mut walls = 0;
+    for (x, y, p) in dithered.enumerate_pixels() {
+        let (leaf, wall) = (leaf.get_pixel(x, y), wall.get_pixel(x, y));
+        for c in 0..3 {
+            assert!(
+                (p[c] as i32 - half(leaf[c], wall[c])).abs() <= 1,
+                "({}, {}) {:?}",
+                x,
+                y,
+                p
+            );
+        }
+        if wall[0] > 0 {
+            walls += 1;
+        }
+    }
+    assert!(walls > 0);
+}
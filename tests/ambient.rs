@@ -0,0 +1,154 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{
+    read_model, AmbientSh, Camera, EnvironmentMap, Image, LoadOptions, Renderer, Scene, Texture,
+};
+
+const SIZE: u32 = 64;
+
+// blue sky above a gray environment
+fn sky() -> EnvironmentMap {
+    let gray = Texture::from_pixel(4, 4, image::Rgb([128, 128, 128]));
+    let mut faces = [(); 6].map(|_| gray.clone());
+    faces[2] = Texture::from_pixel(4, 4, image::Rgb([32, 64, 255]));
+    EnvironmentMap::new(&faces)
+}
+
+fn render(setup: impl FnOnce(&mut Renderer)) -> Image {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    // lit from behind, the visible side only gets ambient light
+    renderer.set_light_direction(vec3(0.0, 0.0, -1.0));
+    setup(&mut renderer);
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+// average blue minus red of covered pixels in rows (row 0 is at the bottom of the view)
+fn blueness(image: &Image, rows: std::ops::Range<u32>) -> f32 {
+    let (mut sum, mut count) = (0.0, 0);
+    for y in rows {
+        for x in 0..SIZE {
+            let pixel = image.get_pixel(x, y).0;
+            if pixel != [0, 0, 0] {
+                sum += pixel[2] as f32 - pixel[0] as f32;
+                count += 1;
+            }
+        }
+    }
+    assert!(count > 0);
+    sum / count as f32
+}
+
+fn brightness(image: &Image) -> u32 {
+    image
+        .pixels()
+        .map(|p| p.0.iter().map(|&c| c as u32).sum::<u32>())
+        .sum()
+}
+
+#[test]
+fn environment_is_sampled_bilinearly_by_direction() {
+    let environment = sky();
+    let blue = vec3(32.0, 64.0, 255.0) / 255.0;
+    assert!((environment.sample(vec3(0.0, 1.0, 0.0)) - blue).length() < 1e-6);
+    assert!(
+        (environment.sample(vec3(0.3, -1.0, 0.2)) - Vec3::splat(128.0 / 255.0)).length() < 1e-6
+    );
+
+    // between the two texels of a face, towards the right one (+Z, the right of +X facing it)
+    let mut faces = [(); 6].map(|_| Texture::new(2, 1));
+    faces[0].put_pixel(1, 0, image::Rgb([255, 255, 255]));
+    let environment = EnvironmentMap::new(&faces);
+    let sample = environment.sample(vec3(1.0, 0.0, 0.5));
+    assert!((sample - Vec3::splat(0.75)).length() < 1e-6, "{:?}", sample);
+}
+
+#[test]
+fn head_picks_up_the_color_of_the_sky() {
+    let plain = render(|_| {});
+    assert!(plain.pixels().all(|p| p.0[0] == p.0[2]));
+
+    let image = render(|renderer| renderer.set_ambient_environment(sky()));
+    // the top of the head faces the sky, its sides face the gray horizon
+    let top = blueness(&image, SIZE * 7 / 8..SIZE);
+    let sides = blueness(&image, SIZE * 3 / 8..SIZE * 5 / 8);
+    assert!(top > 40.0, "{}", top);
+    assert!(sides < top / 4.0, "{} {}", top, sides);
+
+    // brighter with stronger ambient light, constant or not
+    let strong = render(|renderer| {
+        renderer.set_ambient_environment(sky());
+        renderer.set_ambient_strength(2.0);
+    });
+    assert!(brightness(&strong) > brightness(&image) * 5 / 4);
+    let strong_plain = render(|renderer| renderer.set_ambient_strength(2.0));
+    assert!(brightness(&strong_plain) > brightness(&plain) * 5 / 4);
+
+    // and back to the constant ambient light
+    let cleared = render(|renderer| {
+        renderer.set_ambient_environment(sky());
+        renderer.clear_ambient_environment();
+    });
+    assert!(cleared == plain);
+}
+
+// center of a square facing the camera with its normals along normal, lit from behind
+fn ambient_of_normal(normal: Vec3, sh: AmbientSh) -> [u8; 3] {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("sh_square.obj");
+    let square = format!(
+        "v -1 -1 0\nv 1 -1 0\nv 1 1 0\nv -1 1 0\nvn {} {} {}\nf 1//1 2//1 3//1\nf 1//1 3//1 4//1\n",
+        normal.x(),
+        normal.y(),
+        normal.z()
+    );
+    std::fs::write(&path, square).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (8, 8));
+    renderer.set_light_direction(vec3(0.0, 0.0, -1.0));
+    renderer.set_ambient_sh(sh);
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().get_pixel(4, 4).0
+}
+
+#[test]
+fn up_and_down_facing_normals_get_different_ambient() {
+    let (sky, ground) = (vec3(0.2, 0.4, 1.0), vec3(0.5, 0.3, 0.1));
+    let sh = AmbientSh::hemisphere(sky, ground);
+    // irradiance mostly of the sky above and of the ground below
+    let (up, down) = (
+        sh.irradiance(Vec3::unit_y()),
+        sh.irradiance(-Vec3::unit_y()),
+    );
+    assert!(
+        up.z() > up.x() && down.x() > down.z(),
+        "{:?} {:?}",
+        up,
+        down
+    );
+    let horizon = sh.irradiance(Vec3::unit_x());
+    assert!(
+        (horizon - (up + down) / 2.0).length() < 0.05,
+        "{:?}",
+        horizon
+    );
+
+    let up = ambient_of_normal(Vec3::unit_y(), sh);
+    let down = ambient_of_normal(-Vec3::unit_y(), sh);
+    assert!(up[2] > up[0] && down[0] > down[2], "{:?} {:?}", up, down);
+    // a uniform environment lights both the same
+    let gray = AmbientSh::hemisphere(Vec3::splat(0.5), Vec3::splat(0.5));
+    assert_eq!(
+        ambient_of_normal(Vec3::unit_y(), gray),
+        ambient_of_normal(-Vec3::unit_y(), gray)
+    );
+}
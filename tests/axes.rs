@@ -0,0 +1,70 @@
+use glam::vec3;
+use tinyrenderer::{read_model, AxisConvention, Camera, LoadOptions, Renderer, Scene};
+use wavefront_obj::obj::ObjSet;
+
+const SIZE: usize = 64;
+
+fn render(model: ObjSet) -> Renderer {
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.render_scene(&Scene::single(model, None));
+    renderer
+}
+
+// the head as exported from a Z-up tool: (x, y, z) => (x, -z, y)
+fn z_up_head() -> String {
+    let source = std::fs::read_to_string("obj/african_head.obj").unwrap();
+    let mut obj = String::new();
+    for line in source.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some(kind @ ("v" | "vn")) => {
+                let xyz: Vec<f64> = words.map(|w| w.parse().unwrap()).collect();
+                obj += &format!("{} {} {} {}\n", kind, xyz[0], -xyz[2], xyz[1]);
+            }
+            _ => obj += &format!("{}\n", line),
+        }
+    }
+    obj
+}
+
+#[test]
+fn z_up_model_loads_upright() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("z_up_head.obj");
+    std::fs::write(&path, z_up_head()).unwrap();
+    let path = path.to_str().unwrap();
+    let upright = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let options = LoadOptions {
+        axes: AxisConvention::ZUpToYUp,
+        ..LoadOptions::default()
+    };
+    let converted = read_model(path, &options).unwrap();
+
+    let (a, b) = (&upright.objects[0], &converted.objects[0]);
+    for (a, b) in a.vertices.iter().zip(&b.vertices) {
+        let d = (a.x - b.x).abs() + (a.y - b.y).abs() + (a.z - b.z).abs();
+        assert!(d < 1e-5, "{:?} {:?}", a, b);
+    }
+    // the head's normals aren't quite unit length, converted ones are
+    for (a, b) in a.normals.iter().zip(&b.normals) {
+        let (a, b) = (
+            vec3(a.x as f32, a.y as f32, a.z as f32),
+            vec3(b.x as f32, b.y as f32, b.z as f32),
+        );
+        assert!(a.normalize().dot(b) > 0.9999, "{:?} {:?}", a, b);
+    }
+
+    // renders like the Y-up head, while without conversion it's lying on its back
+    let expected = render(upright);
+    let differing = |renderer: &Renderer| {
+        let pixels = renderer.image().pixels().zip(expected.image().pixels());
+        pixels.filter(|(a, b)| a != b).count()
+    };
+    assert!(differing(&render(converted)) < SIZE);
+    let lying = read_model(path, &LoadOptions::default()).unwrap();
+    assert!(differing(&render(lying)) > SIZE * SIZE / 4);
+}
@@ -0,0 +1,73 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, LoadOptions, Renderer, Scene};
+
+const SIZE: usize = 64;
+
+// square of side 2 * size at depth z facing the camera
+fn quad(name: &str, size: f32, z: f32) -> Scene {
+    let obj = format!(
+        "v {s0} {s0} {z}\nv {s1} {s0} {z}\nv {s1} {s1} {z}\nv {s0} {s1} {z}\nf 1 2 3\nf 1 3 4\n",
+        s0 = -size,
+        s1 = size,
+        z = z
+    );
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.obj", name));
+    std::fs::write(&path, obj).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    Scene::single(model, None)
+}
+
+fn renderer() -> Renderer {
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.set_triangle_id_buffer(true);
+    renderer
+}
+
+#[test]
+fn coverage_counts_covered_pixels() {
+    let mut renderer = renderer();
+    assert_eq!(renderer.coverage(), 0.0);
+    assert_eq!(renderer.uncovered_pixels(), SIZE * SIZE);
+
+    renderer.render_scene(&quad("small_quad", 0.5, 0.0));
+    assert!(renderer.coverage() > 0.0 && renderer.coverage() < 1.0);
+
+    renderer.render_scene(&quad("fullscreen_quad", 10.0, 0.0));
+    assert_eq!(renderer.uncovered_pixels(), 0);
+    assert_eq!(renderer.coverage(), 1.0);
+
+    // nothing is left for the background
+    let image = renderer.image().clone();
+    renderer.set_light_direction(vec3(0.3, 0.0, 1.0));
+    renderer.render_background(&quad("sky", 10.0, 1.0));
+    assert!(renderer.image() == &image);
+}
+
+#[test]
+fn background_fills_uncovered_pixels() {
+    let mut renderer = renderer();
+    renderer.render_scene(&quad("small_quad", 0.5, 0.0));
+    let image = renderer.image().clone();
+    let uncovered = renderer.uncovered_pixels();
+
+    // in front of the square, but drawn behind it
+    renderer.set_light_direction(vec3(0.3, 0.0, 1.0));
+    renderer.render_background(&quad("sky", 10.0, 1.0));
+    for (x, y, &p) in image.enumerate_pixels() {
+        let background = *renderer.image().get_pixel(x, y);
+        if p.0 == [0, 0, 0] {
+            assert_ne!(background, p);
+            assert_eq!(renderer.pick(x, y), None);
+        } else {
+            assert_eq!(background, p);
+            assert!(renderer.pick(x, y).is_some());
+        }
+    }
+    // and still counts as background
+    assert_eq!(renderer.uncovered_pixels(), uncovered);
+}
@@ -0,0 +1,51 @@
+// the checks only run in debug builds
+#![cfg(debug_assertions)]
+
+use glam::{vec2, vec3, Vec2};
+use tinyrenderer::{barycentric, check_barycentric};
+
+fn check(p: Vec2) {
+    let (a, b, c) = (Vec2::zero(), Vec2::unit_x(), Vec2::unit_y());
+    check_barycentric(barycentric(a, b, c, p), p, [a, b, c]);
+}
+
+#[test]
+fn points_of_the_triangle_pass() {
+    for p in [
+        vec2(0.25, 0.25),
+        vec2(0.0, 0.0),
+        vec2(0.5, 0.5),
+        vec2(0.0, 1.0),
+    ] {
+        check(p);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Invalid barycentric coordinates")]
+fn point_outside_of_the_triangle_panics() {
+    // what a broken rasterizer accepting this pixel would interpolate with
+    check(vec2(1.0, 1.0));
+}
+
+#[test]
+#[should_panic(expected = "Invalid barycentric coordinates")]
+fn unnormalized_barycentric_panics() {
+    // the sub-triangle areas without the division by the whole one, which only passes for
+    // triangles of area 1/2
+    let broken = |[a, b, c]: [Vec2; 3], p: Vec2| {
+        vec3(
+            (c - b).perp_dot(p - b),
+            (a - c).perp_dot(p - c),
+            (b - a).perp_dot(p - a),
+        )
+    };
+    let unit = [Vec2::zero(), Vec2::unit_x(), Vec2::unit_y()];
+    let p = vec2(0.25, 0.25);
+    assert_eq!(broken(unit, p), barycentric(unit[0], unit[1], unit[2], p));
+    check_barycentric(broken(unit, p), p, unit);
+
+    // twice as large
+    let large = unit.map(|v| v * 2.0);
+    check_barycentric(broken(large, p), p, large);
+}
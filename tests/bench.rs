@@ -0,0 +1,40 @@
+use std::process::Command;
+
+#[test]
+fn bench_reports_frames() {
+    let output = Command::new(env!("CARGO_BIN_EXE_tinyrenderer"))
+        .args(["bench", "--frames", "3", "--size", "64x64"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // Rendered 3 frames in X ms: FPS FPS, X ms per frame (min X ms, max X ms)
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    let (rendered, loaded, built) = match lines[..] {
+        [rendered, loaded, built] => (rendered, loaded, built),
+        _ => panic!("unexpected output {:?}", stdout),
+    };
+    let numbers: Vec<f64> = rendered
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .filter_map(|n| n.parse().ok())
+        .collect();
+    assert!(stdout.starts_with("Rendered 3 frames"), "{}", stdout);
+    let (total, fps, average, min, max) = match numbers[..] {
+        [_, total, fps, average, min, max] => (total, fps, average, min, max),
+        _ => panic!("unexpected output {:?}", stdout),
+    };
+    assert!(min > 0.0 && min <= average && average <= max && max <= total);
+    assert!((fps - 3e3 / total).abs() / fps < 0.01);
+
+    // Loaded the model in X ms
+    // Built the texture mip chain in X ms
+    let time = |line: &str, prefix| -> f64 {
+        line.strip_prefix(prefix)
+            .and_then(|line| line.strip_suffix(" ms"))
+            .and_then(|ms| ms.parse().ok())
+            .unwrap_or_else(|| panic!("unexpected output {:?}", stdout))
+    };
+    assert!(time(loaded, "Loaded the model in ") > 0.0);
+    assert!(time(built, "Built the texture mip chain in ") > 0.0);
+}
@@ -0,0 +1,102 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{read_model, Camera, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 64;
+
+// a square in the middle of the view, wound both ways so it's seen from either side
+const SQUARE: &str = "
+v -0.25 -0.25 0
+v 0.25 -0.25 0
+v 0.25 0.25 0
+v -0.25 0.25 0
+f 1 2 3
+f 1 3 4
+f 1 3 2
+f 1 4 3
+";
+
+// (min x, min y, max x, max y) of the square on the image, y grows upwards
+fn bounds(camera: Camera) -> (u32, u32, u32, u32) {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("camera_square.obj");
+    std::fs::write(&path, SQUARE).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.render_scene(&Scene::single(model, None));
+
+    let image = renderer.image();
+    let pixels = (0..SIZE).flat_map(|y| (0..SIZE).map(move |x| (x, y)));
+    let covered: Vec<_> = pixels
+        .filter(|&(x, y)| image.get_pixel(x, y).0 != [0, 0, 0])
+        .collect();
+    assert!(!covered.is_empty());
+    covered
+        .iter()
+        .fold((SIZE, SIZE, 0, 0), |(x0, y0, x1, y1), &(x, y)| {
+            (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+        })
+}
+
+fn width((x0, _, x1, _): (u32, u32, u32, u32)) -> u32 {
+    x1 - x0 + 1
+}
+
+fn orthographic() -> Camera {
+    Camera::new(Vec3::unit_z(), Vec3::zero(), Vec3::unit_y())
+}
+
+fn perspective() -> Camera {
+    Camera::perspective(vec3(0.0, 0.0, 2.0), Vec3::zero(), Vec3::unit_y(), 1.0)
+}
+
+#[test]
+fn pan_moves_the_view() {
+    let still = bounds(orthographic());
+    // the view goes right, so the square goes left
+    let mut camera = orthographic();
+    camera.pan(0.25, 0.0);
+    let (x0, y0, x1, y1) = bounds(camera);
+    assert!(x0 < still.0 && x1 < still.2, "{:?} {:?}", (x0, x1), still);
+    assert_eq!((y0, y1), (still.1, still.3));
+
+    // and when it goes up the square goes down
+    let mut camera = orthographic();
+    camera.pan(0.0, 0.25);
+    let (x0, y0, x1, y1) = bounds(camera);
+    assert!(y0 < still.1 && y1 < still.3, "{:?} {:?}", (y0, y1), still);
+    assert_eq!((x0, x1), (still.0, still.2));
+}
+
+#[test]
+fn zoom_magnifies() {
+    for camera in [orthographic, perspective] {
+        let still = width(bounds(camera()));
+        let mut zoomed = camera();
+        zoomed.zoom(2.0);
+        let zoomed = width(bounds(zoomed));
+        assert!(zoomed.abs_diff(2 * still) <= 2, "{} {}", zoomed, still);
+
+        let mut camera = camera();
+        camera.zoom(0.5);
+        assert!(width(bounds(camera)) < still);
+    }
+}
+
+#[test]
+fn dolly_moves_towards_lookat() {
+    let still = width(bounds(perspective()));
+    let mut closer = perspective();
+    closer.dolly(1.0);
+    assert!(width(bounds(closer)) > still);
+    let mut farther = perspective();
+    farther.dolly(-1.0);
+    assert!(width(bounds(farther)) < still);
+    // it doesn't go through lookat however far it moves
+    let mut through = perspective();
+    through.dolly(10.0);
+    assert_eq!(bounds(through), (0, 0, SIZE - 1, SIZE - 1));
+
+    // orthographic projection doesn't depend on the distance
+    let mut camera = orthographic();
+    camera.dolly(0.5);
+    assert_eq!(bounds(camera), bounds(orthographic()));
+}
@@ -0,0 +1,241 @@
+use glam::{vec2, vec3, Vec2, Vec4};
+use tinyrenderer::{read_model, Camera, LoadOptions, Renderer, Scene, ShadingMode, Texture};
+
+const SIZE: u32 = 64;
+
+// triangle reaching out of the left and the top sides of the view (beyond the guard band), its
+// edge between them cutting the top left corner off, facing the camera
+const TRIANGLE: [(f32, f32); 3] = [(-2.6, -1.0), (0.9, -0.9), (1.0, 2.6)];
+
+// floor below the viewer from behind it to far ahead
+const FLOOR: &str = "
+v -5 -0.5 -5
+v 5 -0.5 -5
+v 5 -0.5 5
+v -5 -0.5 5
+f 1 2 3
+f 1 3 4
+";
+
+fn scene(name: &str, obj: &str, texture: Option<Texture>) -> Scene {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, obj).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    Scene::single(model, texture)
+}
+
+// normalized device coordinates of pixel (x, y)
+fn ndc(x: f32, y: f32) -> Vec2 {
+    vec2(x, y) * 2.0 / (SIZE - 1) as f32 - Vec2::one()
+}
+
+// whether pixels around (x, y) are all inside or all outside of the shape, i.e. its edges aren't
+// close enough to decide the coverage of (x, y) by rounding
+fn away_from_edges(x: u32, y: u32, inside: &dyn Fn(Vec2) -> bool) -> bool {
+    let corners = [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)];
+    let center = inside(ndc(x as f32, y as f32));
+    corners
+        .iter()
+        .all(|(dx, dy)| inside(ndc(x as f32 + dx, y as f32 + dy)) == center)
+}
+
+#[test]
+fn triangle_is_clipped_to_the_sides() {
+    let [a, b, c] = TRIANGLE.map(|(x, y)| vec2(x, y));
+    let obj = format!(
+        "v {} {} 0\nv {} {} 0\nv {} {} 0\nvt 0 0\nvt 1 0\nvt 0 1\nf 1/1 2/2 3/3\n",
+        a.x(),
+        a.y(),
+        b.x(),
+        b.y(),
+        c.x(),
+        c.y()
+    );
+    let texture = Texture::from_pixel(4, 4, image::Rgb([255, 255, 255]));
+    let scene = scene("clipped_triangle.obj", &obj, Some(texture));
+
+    // orthographic, normalized device coordinates are the world ones
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_uvw_buffer(true);
+    renderer.render_scene(&scene);
+
+    // texture coordinates are the barycentric coordinates of b and c
+    let area = (b - a).perp_dot(c - a);
+    let uv = |p: Vec2| vec2((p - a).perp_dot(c - a), (b - a).perp_dot(p - a)) / area;
+    let inside = |p: Vec2| {
+        let uv = uv(p);
+        uv.x() >= 0.0 && uv.y() >= 0.0 && uv.x() + uv.y() <= 1.0
+    };
+    let mut covered = 0;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            if !away_from_edges(x, y, &inside) {
+                continue;
+            }
+
+            let p = ndc(x as f32, y as f32);
+            match renderer.uvw(x, y) {
+                Some(uvw) => {
+                    assert!(inside(p), "({}, {}) is outside", x, y);
+                    // interpolated along the new edges of the polygon as across the triangle
+                    let expected = uv(p);
+                    assert!(
+                        (uvw.truncate() - expected).length() < 1e-3,
+                        "uv {:?} instead of {:?} at ({}, {})",
+                        uvw,
+                        expected,
+                        x,
+                        y
+                    );
+                    covered += 1;
+                }
+                None => assert!(!inside(p), "({}, {}) isn't covered", x, y),
+            }
+        }
+    }
+    // the top left corner is cut off
+    assert!(covered > (SIZE * SIZE / 4) as usize, "{}", covered);
+    assert!(renderer.uvw(0, SIZE - 1).is_none());
+    assert!(renderer.uvw(SIZE / 2, SIZE - 1).is_some());
+}
+
+#[test]
+fn triangles_crossing_the_near_plane_are_clipped() {
+    let scene = scene("clipped_floor.obj", FLOOR, None);
+    let camera = Camera::perspective(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        std::f32::consts::FRAC_PI_2,
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_shading_mode(ShadingMode::TriangleColors);
+    renderer.set_double_sided(true);
+    renderer.render_scene(&scene);
+
+    // where the ray through p hits the floor: in front of the viewer (w > 0, camera space z is
+    // 1 - w looking from the viewer) and within the floor
+    let inside = |p: Vec2| {
+        if p.y() >= 0.0 {
+            return false;
+        }
+        let w = -0.5 / p.y();
+        (p.x() * w).abs() <= 5.0 && (1.0 - w).abs() <= 5.0
+    };
+    let image = renderer.image();
+    let mut covered = 0;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            if !away_from_edges(x, y, &inside) {
+                continue;
+            }
+            let drawn = image.get_pixel(x, y).0 != [0, 0, 0];
+            assert_eq!(drawn, inside(ndc(x as f32, y as f32)), "({}, {})", x, y);
+            covered += drawn as usize;
+        }
+    }
+    // the floor fills the bottom of the view up to the horizon
+    assert!(covered > (SIZE * SIZE / 3) as usize, "{}", covered);
+}
+
+#[test]
+fn triangles_crossing_the_far_plane_are_clipped() {
+    let scene = scene("far_floor.obj", FLOOR, None);
+    let mut camera = Camera::perspective(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        std::f32::consts::FRAC_PI_2,
+    );
+    let far = 3.0;
+    camera.set_far(far);
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_shading_mode(ShadingMode::TriangleColors);
+    renderer.set_double_sided(true);
+    renderer.render_scene(&scene);
+
+    // the floor up to depth far, below the horizon
+    let inside =
+        |p: Vec2| p.y() < 0.0 && -0.5 / p.y() <= far && (p.x() * -0.5 / p.y()).abs() <= 5.0;
+    let image = renderer.image();
+    let (mut covered, mut cut) = (0, 0);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            if !away_from_edges(x, y, &inside) {
+                continue;
+            }
+            let drawn = image.get_pixel(x, y).0 != [0, 0, 0];
+            assert_eq!(drawn, inside(ndc(x as f32, y as f32)), "({}, {})", x, y);
+            covered += drawn as usize;
+            cut += (!drawn && ndc(x as f32, y as f32).y() < 0.0) as usize;
+        }
+    }
+    assert!(covered > (SIZE * SIZE / 4) as usize, "{}", covered);
+    // between the far plane and the horizon
+    assert!(cut > SIZE as usize, "{}", cut);
+}
+
+#[test]
+fn clip_plane_cuts_off_half_of_the_model() {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let scene = Scene::single(model, None);
+    let render = |planes: &[Vec4]| {
+        let camera = Camera::new(
+            vec3(0.0, 0.0, -1.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        );
+        let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+        for &plane in planes {
+            renderer.add_clip_plane(plane);
+        }
+        renderer.render_scene(&scene);
+        renderer
+    };
+    let covered =
+        |renderer: &Renderer, x: u32, y: u32| renderer.image().get_pixel(x, y).0 != [0; 3];
+
+    // world x is the screen one, the head is centered on the origin
+    let full = render(&[]);
+    let right = render(&[Vec4::new(1.0, 0.0, 0.0, 0.0)]);
+    let left = render(&[Vec4::new(-1.0, 0.0, 0.0, 0.0)]);
+    let middle = SIZE / 2;
+    let (mut kept, mut cut) = (0, 0);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            if covered(&full, x, y) {
+                let (kept_by, cut_by) = if x >= middle {
+                    (&right, &left)
+                } else {
+                    (&left, &right)
+                };
+                // away from the plane what's left is drawn as before
+                if x + 1 < middle || x > middle {
+                    assert!(kept_by.image().get_pixel(x, y) == full.image().get_pixel(x, y));
+                    assert!(!covered(cut_by, x, y), "({}, {})", x, y);
+                }
+                kept += 1;
+            } else {
+                assert!(!covered(&right, x, y) && !covered(&left, x, y));
+            }
+            cut += covered(&right, x, y) as usize + covered(&left, x, y) as usize;
+        }
+    }
+    assert!(kept > (SIZE * SIZE / 4) as usize, "{}", kept);
+    assert!(cut.abs_diff(kept) < SIZE as usize, "{} {}", cut, kept);
+
+    // both planes cut everything off, until they're cleared
+    let mut renderer = render(&[
+        Vec4::new(1.0, 0.0, 0.0, 0.0),
+        Vec4::new(-1.0, 0.0, 0.0, -0.01),
+    ]);
+    assert!(renderer.image().pixels().all(|p| p.0 == [0; 3]));
+    renderer.clear_clip_planes();
+    renderer.render_scene(&scene);
+    assert!(renderer.image() == full.image());
+}
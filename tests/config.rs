@@ -0,0 +1,71 @@
+use glam::{vec3, vec4};
+use tinyrenderer::{
+    CameraConfig, FrontFace, Handedness, Light, Projection, RenderConfig, ShadingMode, Vignette,
+};
+
+fn path(name: &str) -> String {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    path.to_str().unwrap().to_owned()
+}
+
+#[test]
+fn config_survives_a_round_trip() {
+    let path = path("round_trip.toml");
+    let default = RenderConfig::default();
+    default.save(&path).unwrap();
+    assert_eq!(RenderConfig::load(&path).unwrap(), default);
+
+    // and so does anything changed in it
+    let mut config = RenderConfig {
+        texture: None,
+        output: "head.png".to_owned(),
+        width: 320,
+        height: 240,
+        sizes: vec![(64, 64), (128, 96)],
+        shading: ShadingMode::Normals,
+        front_face: FrontFace::Cw,
+        vignette: Some(Vignette::default()),
+        lights: vec![Light {
+            direction: vec3(1.0, 0.5, 0.0),
+            intensity: 0.25,
+            casts_shadows: true,
+        }],
+        clip_planes: vec![vec4(0.0, 1.0, 0.0, 0.5)],
+        shadow_map_size: Some(512),
+        contact_shadows: Some((0.1, 8)),
+        depth_peeling_layers: Some(3),
+        camera: CameraConfig {
+            lookfrom: vec3(0.0, 1.0, 3.0),
+            projection: Projection::Perspective { fov: 0.8 },
+            handedness: Handedness::LeftHanded,
+            ..CameraConfig::default()
+        },
+        ..default
+    };
+    config
+        .group_colors
+        .insert("eyes".to_owned(), vec3(1.0, 0.0, 0.0));
+    config.load.weld_tolerance = Some(1e-4);
+    config.save(&path).unwrap();
+    assert_ne!(config, RenderConfig::default());
+    assert_eq!(RenderConfig::load(&path).unwrap(), config);
+}
+
+#[test]
+fn missing_fields_take_default_values() {
+    let path = path("partial.toml");
+    std::fs::write(&path, "width = 64\n\n[camera]\nup = [0.0, 0.0, 1.0]\n").unwrap();
+    let config = RenderConfig::load(&path).unwrap();
+    let expected = RenderConfig {
+        width: 64,
+        camera: CameraConfig {
+            up: vec3(0.0, 0.0, 1.0),
+            ..CameraConfig::default()
+        },
+        ..RenderConfig::default()
+    };
+    assert_eq!(config, expected);
+
+    std::fs::write(&path, "width = \"wide\"\n").unwrap();
+    assert!(RenderConfig::load(&path).is_err());
+}
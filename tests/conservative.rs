@@ -0,0 +1,60 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, Image, LoadOptions, Renderer, Scene, ShadingMode};
+
+const SIZE: u32 = 9;
+
+// sliver along x + 2y = 10.45 in screen space, which passes no pixel center but clips
+// the corner of pixel (3, 3) around (3.5, 3.5)
+fn sliver() -> String {
+    let world = |s: f32| s / (SIZE - 1) as f32 * 2.0 - 1.0;
+    format!(
+        "v {} {} 0\nv {} {} 0\nv {} {} 0\nf 1 2 3\n",
+        world(0.5),
+        world(4.975),
+        world(8.5),
+        world(0.975),
+        world(8.5),
+        world(0.985)
+    )
+}
+
+fn render(conservative: bool) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("sliver.obj");
+    std::fs::write(&path, sliver()).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_shading_mode(ShadingMode::TriangleColors);
+    renderer.set_conservative(conservative);
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+#[test]
+fn conservative_covers_touched_pixels() {
+    let covered = |image: &Image| {
+        image
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p.0 != [0, 0, 0])
+            .map(|(x, y, _)| (x, y))
+            .collect::<Vec<_>>()
+    };
+
+    // the sliver misses every pixel center
+    assert!(covered(&render(false)).is_empty());
+
+    let touched = covered(&render(true));
+    assert!(touched.contains(&(3, 3)), "{:?}", touched);
+    // and only pixels whose square the sliver overlaps
+    assert!(touched.len() >= SIZE as usize);
+    for &(x, y) in &touched {
+        let (x, y) = (x as f32, y as f32);
+        let along = |dx: f32, dy: f32| x + dx + 2.0 * (y + dy) - 10.45;
+        assert!(along(-0.5, -0.5) <= 0.0 && along(0.5, 0.5) >= -0.03);
+    }
+}
@@ -0,0 +1,77 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, Image, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 128;
+
+// ground facing the camera and a small bump just above it
+const GROUND: &str = "
+o ground
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+f 1 2 3
+f 1 3 4
+";
+const BUMP: &str = "
+o bump
+v -0.1 -0.1 0.1
+v 0.1 -0.1 0.1
+v 0.1 0.1 0.1
+v -0.1 0.1 0.1
+f 5 6 7
+f 5 7 8
+";
+
+fn render(obj: &str, contact_shadows: bool) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("contact.obj");
+    std::fs::write(&path, obj).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_light_direction(vec3(0.6, 0.0, 1.0));
+    if contact_shadows {
+        renderer.set_contact_shadows(0.3, 16);
+    }
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+#[test]
+fn bump_casts_short_contact_shadow() {
+    // nothing to cast shadows on a plane
+    let ground = render(GROUND, false);
+    assert!(render(GROUND, true) == ground);
+
+    let bumped = format!("{}{}", GROUND, BUMP);
+    let lit = render(&bumped, false);
+    let shadowed = render(&bumped, true);
+    let mut darker = Vec::new();
+    for (x, y, &p) in lit.enumerate_pixels() {
+        let q = *shadowed.get_pixel(x, y);
+        if q != p {
+            assert!(q[0] < p[0], "({}, {}) {:?} {:?}", x, y, p, q);
+            // on the ground, next to the bump
+            assert_eq!(p, *ground.get_pixel(x, y));
+            darker.push((x as i32, y as i32));
+        }
+    }
+    assert!(!darker.is_empty());
+
+    // the bump covers 0.2 of 2 units in the middle, the shadow is offset towards one side by
+    // its height times the slope of the light (0.06 units)
+    let center = SIZE as i32 / 2;
+    let (min_x, max_x) = darker
+        .iter()
+        .fold((i32::MAX, i32::MIN), |(min, max), &(x, _)| {
+            (min.min(x), max.max(x))
+        });
+    assert!(darker.iter().all(|&(_, y)| (y - center).abs() <= 7));
+    assert!(max_x - min_x <= 5, "{} {}", min_x, max_x);
+    assert!((min_x - center).abs() >= 6 && (max_x - center).abs() <= 11);
+}
@@ -0,0 +1,50 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, LoadOptions, Renderer, Scene, Vignette};
+
+const SIZE: usize = 64;
+
+fn render() -> Renderer {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    // post effects are saved in the crop too
+    renderer.set_vignette(Vignette::default());
+    renderer.render_scene(&Scene::single(model, None));
+    renderer
+}
+
+#[test]
+fn crop_matches_the_full_image() {
+    let renderer = render();
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (full, crop) = (dir.join("crop_full.png"), dir.join("crop.png"));
+    renderer.save(full.to_str().unwrap()).unwrap();
+    renderer
+        .save_crop(crop.to_str().unwrap(), 20, 8, 24, 16)
+        .unwrap();
+
+    let full = image::open(&full).unwrap().into_rgb8();
+    let crop = image::open(&crop).unwrap().into_rgb8();
+    assert_eq!(crop.dimensions(), (24, 16));
+    for (x, y, pixel) in crop.enumerate_pixels() {
+        assert_eq!(pixel, full.get_pixel(x + 20, y + 8), "({}, {})", x, y);
+    }
+    assert!(crop.pixels().any(|p| p.0 != [0, 0, 0]));
+}
+
+#[test]
+fn crop_out_of_bounds_is_an_error() {
+    let renderer = render();
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("crop_out.png");
+    let path = path.to_str().unwrap();
+    let size = SIZE as u32;
+    assert!(renderer.save_crop(path, 0, 0, size, size).is_ok());
+    assert!(renderer.save_crop(path, 1, 0, size, 1).is_err());
+    assert!(renderer.save_crop(path, 0, size, 1, 1).is_err());
+    assert!(renderer.save_crop(path, 0, 0, 0, 1).is_err());
+    assert!(renderer.save_crop(path, u32::MAX, 0, 2, 1).is_err());
+}
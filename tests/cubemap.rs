@@ -0,0 +1,112 @@
+use glam::{vec3, Vec3};
+use std::f32::consts::PI;
+use tinyrenderer::{
+    read_model, Camera, Image, LoadOptions, NormalSpace, Renderer, Scene, ShadingMode,
+};
+
+const SIZE: usize = 32;
+
+// sphere around the origin with radial normals, so seen from the inside with world space
+// normals as colors every pixel encodes the direction it's seen in
+fn sphere() -> String {
+    let (stacks, slices) = (32, 64);
+    let mut obj = String::new();
+    for i in 0..=stacks {
+        let theta = PI * i as f32 / stacks as f32;
+        for j in 0..slices {
+            let phi = 2.0 * PI * j as f32 / slices as f32;
+            let n = vec3(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            );
+            let p = n * 5.0;
+            obj += &format!(
+                "v {} {} {}\nvn {} {} {}\n",
+                p.x(),
+                p.y(),
+                p.z(),
+                n.x(),
+                n.y(),
+                n.z()
+            );
+        }
+    }
+    let index = |i: usize, j: usize| i * slices + j % slices + 1;
+    for i in 0..stacks {
+        for j in 0..slices {
+            let (a, b) = (index(i, j), index(i, j + 1));
+            let (c, d) = (index(i + 1, j + 1), index(i + 1, j));
+            obj += &format!("f {0}//{0} {1}//{1} {2}//{2}\n", a, b, c);
+            obj += &format!("f {0}//{0} {1}//{1} {2}//{2}\n", a, c, d);
+        }
+    }
+    obj
+}
+
+fn render() -> [Image; 6] {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("cubemap_sphere.obj");
+    std::fs::write(&path, sphere()).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (16, 16));
+    renderer.set_double_sided(true);
+    renderer.set_shading_mode(ShadingMode::Normals);
+    renderer.set_normal_space(NormalSpace::World);
+    renderer.render_cubemap(&Scene::single(model, None), Vec3::zero(), SIZE)
+}
+
+// direction a pixel is seen in, back faces are shaded with flipped normals (towards the center)
+fn direction(image: &Image, x: usize, y: usize) -> Vec3 {
+    let p = image.get_pixel(x as u32, y as u32);
+    -(vec3(p[0] as f32, p[1] as f32, p[2] as f32) / 127.5 - Vec3::one()).normalize()
+}
+
+#[test]
+fn faces_look_along_their_axes() {
+    let faces = render();
+    // +X, -X, +Y, -Y, +Z, -Z
+    let axes = [Vec3::unit_x(), Vec3::unit_y(), Vec3::unit_z()];
+    for (i, face) in faces.iter().enumerate() {
+        let axis = if i % 2 == 0 {
+            axes[i / 2]
+        } else {
+            -axes[i / 2]
+        };
+        let center = direction(face, SIZE / 2, SIZE / 2);
+        assert!(center.dot(axis) > 0.99, "{} {:?}", i, center);
+        // and see 45 degrees to the side at the edges
+        let edge = direction(face, 0, SIZE / 2);
+        assert!(
+            (edge.dot(axis) - 0.5f32.sqrt()).abs() < 0.05,
+            "{} {:?}",
+            i,
+            edge
+        );
+    }
+}
+
+#[test]
+fn cubemap_seams_match() {
+    let faces = render();
+    let [px, nx, py, ny, pz, nz] = &faces;
+    let last = SIZE - 1;
+    // pixels next to each other across a seam see nearly the same direction
+    let seam = |a: &Image, (ax, ay): (usize, usize), b: &Image, (bx, by): (usize, usize)| {
+        let (a, b) = (direction(a, ax, ay), direction(b, bx, by));
+        assert!(a.dot(b) > 0.995, "{:?} {:?}", a, b);
+    };
+    for i in 0..SIZE {
+        // the horizontal cross -X, -Z, +X, +Z, and around to -X
+        for (left, right) in [(nx, nz), (nz, px), (px, pz), (pz, nx)] {
+            seam(left, (last, i), right, (0, i));
+        }
+        // -Z is down on +Y and up on -Y
+        seam(py, (i, last), nz, (i, 0));
+        seam(ny, (i, 0), nz, (i, last));
+    }
+}
@@ -0,0 +1,110 @@
+use glam::{vec3, Mat4, Vec3};
+use tinyrenderer::{read_model, Camera, Instance, LoadOptions, Renderer, Scene};
+
+// heads in front of the camera, followed by ones out of view (to the sides, above, below and
+// behind it)
+const VISIBLE: usize = 5;
+fn scene() -> Scene {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let mut scene = Scene::single(model, None);
+    scene.instances.clear();
+
+    let mut positions: Vec<Vec3> = (-2..=2).map(|x| vec3(x as f32, 0.0, -2.0)).collect();
+    positions.extend(&[
+        vec3(-30.0, 0.0, 0.0),
+        vec3(30.0, 0.0, -2.0),
+        vec3(0.0, 30.0, 0.0),
+        vec3(0.0, -30.0, -5.0),
+        vec3(0.0, 0.0, -20.0),
+        vec3(3.0, 1.0, -10.0),
+    ]);
+    for position in positions {
+        scene.instances.push(Instance {
+            mesh: 0,
+            transform: Mat4::from_translation(position),
+        });
+    }
+    scene
+}
+
+fn renderer() -> Renderer {
+    let camera = Camera::perspective(
+        vec3(0.0, 0.0, 4.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        1.0,
+    );
+    Renderer::new(camera, (128, 128))
+}
+
+fn render(scene: &Scene, leaf_size: Option<usize>) -> Renderer {
+    let mut renderer = renderer();
+    if let Some(leaf_size) = leaf_size {
+        renderer.set_frustum_culling(leaf_size);
+    }
+    renderer.render_scene(scene);
+    renderer
+}
+
+#[test]
+fn culls_instances_out_of_view() {
+    let scene = scene();
+    let reference = render(&scene, None);
+    assert!(reference.image().pixels().any(|p| p.0 != [0, 0, 0]));
+
+    for leaf_size in 1..=4 {
+        let culled = render(&scene, Some(leaf_size));
+        let stats = culled.culling_stats();
+        assert_eq!(stats.visible, VISIBLE, "leaf size {}", leaf_size);
+        assert_eq!(stats.culled, scene.instances.len() - VISIBLE);
+        assert!(stats.nodes_tested > 0);
+        assert!(
+            culled.image() == reference.image(),
+            "culling changed the image with leaf size {}",
+            leaf_size
+        );
+    }
+}
+
+#[test]
+fn culled_instances_are_not_rasterized() {
+    // every culled instance rendered on its own is indeed invisible
+    let all = scene();
+    for instance in &all.instances[VISIBLE..] {
+        let scene = Scene {
+            meshes: all.meshes.clone(),
+            instances: vec![*instance],
+        };
+        let renderer = render(&scene, None);
+        assert!(renderer.image().pixels().all(|p| p.0 == [0, 0, 0]));
+    }
+}
+
+#[test]
+fn sphere_culling_matches_boxes() {
+    let scene = scene();
+    let reference = render(&scene, None);
+    let mut spheres = renderer();
+    spheres.set_sphere_culling();
+    spheres.render_scene(&scene);
+
+    // the same instances, every one tested once
+    let stats = spheres.culling_stats();
+    assert_eq!(stats.visible, VISIBLE);
+    assert_eq!(stats.culled, scene.instances.len() - VISIBLE);
+    assert_eq!(stats.nodes_tested, scene.instances.len());
+    assert!(spheres.image() == reference.image());
+
+    // bounding spheres follow scaling
+    let mut scaled = scene.clone();
+    for instance in &mut scaled.instances[..VISIBLE] {
+        instance.transform =
+            Mat4::from_translation(vec3(0.0, 0.0, -2.0)) * Mat4::from_scale(Vec3::splat(0.2));
+    }
+    scaled.instances[VISIBLE].transform =
+        Mat4::from_translation(vec3(-8.0, 0.0, 0.0)) * Mat4::from_scale(Vec3::splat(5.0));
+    spheres.clear_all();
+    spheres.render_scene(&scaled);
+    assert_eq!(spheres.culling_stats().visible, VISIBLE + 1);
+    assert!(spheres.image() == render(&scaled, None).image());
+}
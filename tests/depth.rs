@@ -0,0 +1,132 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, Colormap, LoadOptions, Renderer, Scene};
+
+const SIZE: usize = 64;
+
+// small square 3 units in front of the camera, a larger one behind it
+const QUADS: &str = "
+o front
+v -0.2 -0.2 0
+v 0.2 -0.2 0
+v 0.2 0.2 0
+v -0.2 0.2 0
+f 1 2 3
+f 1 3 4
+o back
+v -3 -3 -1.5
+v 3 -3 -1.5
+v 3 3 -1.5
+v -3 3 -1.5
+f 5 6 7
+f 5 7 8
+";
+
+fn render(camera: Camera) -> Renderer {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("depth_quads.obj");
+    std::fs::write(&path, QUADS).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.render_scene(&Scene::single(model, None));
+    renderer
+}
+
+#[test]
+fn linear_depth_is_distance_from_camera() {
+    let (lookfrom, up) = (vec3(0.0, 0.0, -3.0), vec3(0.0, 1.0, 0.0));
+    let perspective = Camera::perspective(lookfrom, vec3(0.0, 0.0, 0.0), up, 1.2);
+    let orthographic = Camera::new(lookfrom, vec3(0.0, 0.0, 0.0), up);
+    for camera in [perspective, orthographic] {
+        let renderer = render(camera);
+        let depth = renderer.linear_depth();
+        let at = |x: usize, y: usize| depth[x + y * SIZE].unwrap();
+        assert!(
+            (at(SIZE / 2, SIZE / 2) - 3.0).abs() < 0.02,
+            "{}",
+            at(32, 32)
+        );
+        assert!((at(2, 2) - 4.5).abs() < 0.02, "{}", at(2, 2));
+    }
+
+    // nothing behind the edges of the back square with a wide enough view
+    let wide = Camera::perspective(lookfrom, vec3(0.0, 0.0, 0.0), up, 2.5);
+    let depth = render(wide).linear_depth();
+    assert_eq!(depth[0], None);
+}
+
+#[test]
+fn linear_depth_is_saved_as_16_bit_image() {
+    let camera = Camera::perspective(
+        vec3(0.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        1.2,
+    );
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("depth.png");
+    render(camera)
+        .save_linear_depth(path.to_str().unwrap())
+        .unwrap();
+
+    let depth = image::open(&path).unwrap().into_luma16();
+    let (front, back) = (depth.get_pixel(32, 32)[0], depth.get_pixel(2, 2)[0]);
+    assert_eq!(back, 0xffff);
+    assert!((front as f32 / back as f32 - 3.0 / 4.5).abs() < 0.01);
+}
+
+#[test]
+fn depth_is_saved_in_false_colors() {
+    let camera = Camera::perspective(
+        vec3(0.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        1.2,
+    );
+    let renderer = render(camera);
+    let ends = [
+        (Colormap::Viridis, [68, 1, 84], [253, 231, 37]),
+        (Colormap::Jet, [0, 0, 128], [128, 0, 0]),
+    ];
+    for (colormap, start, end) in ends {
+        assert_eq!(colormap.color(0.0).0, start);
+        assert_eq!(colormap.color(1.0).0, end);
+
+        let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("depth_colored.png");
+        let path = path.to_str().unwrap();
+        renderer.save_depth_colored(path, colormap).unwrap();
+        let image = image::open(path).unwrap().into_rgb8();
+        // the front square is the nearest, the back one the farthest
+        assert_eq!(image.get_pixel(32, 32).0, start);
+        assert_eq!(image.get_pixel(2, 2).0, end);
+    }
+
+    // in between the colors blend
+    let middle = Colormap::Jet.color(0.5).0;
+    assert_eq!(middle, [128, 255, 128]);
+}
+
+#[test]
+fn depth_histogram_buckets_covered_pixels() {
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera.clone(), (SIZE, SIZE));
+    assert_eq!(renderer.depth_histogram(8), vec![0; 8]);
+
+    // the front square alone is at a single depth
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("depth_quad.obj");
+    std::fs::write(&path, QUADS.split("o back").next().unwrap()).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    renderer.render_scene(&Scene::single(model, None));
+    let covered = SIZE * SIZE - renderer.uncovered_pixels();
+    let histogram = renderer.depth_histogram(8);
+    assert!(covered > 0);
+    assert_eq!(histogram[0], covered);
+    assert_eq!(histogram.iter().sum::<usize>(), covered);
+
+    // the back square is in the first bin, the front one in the last
+    let histogram = render(camera).depth_histogram(8);
+    assert!(histogram[0] > histogram[7] && histogram[7] > 0);
+    assert_eq!(histogram.iter().sum::<usize>(), SIZE * SIZE);
+    assert!(histogram[1..7].iter().all(|&n| n == 0));
+}
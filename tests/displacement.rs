@@ -0,0 +1,156 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_model, read_texture, Camera, Color, LoadOptions, Renderer, Scene, Texture,
+};
+
+const SIZE: usize = 64;
+
+// square facing the camera, its normals pointing at it
+const SQUARE: &str = "
+v -0.5 -0.5 0
+v 0.5 -0.5 0
+v 0.5 0.5 0
+v -0.5 0.5 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+f 1/1/1 2/2/1 3/3/1
+f 1/1/1 3/3/1 4/4/1
+";
+
+// 3 units from the square, see tests/depth.rs
+fn render(displacement: Option<(Texture, f32)>) -> Vec<Option<f32>> {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("displaced_square.obj");
+    std::fs::write(&path, SQUARE).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    if let Some((map, scale)) = displacement {
+        renderer.set_displacement_map(map, scale);
+        renderer.set_subdivision_level(3);
+    }
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.linear_depth()
+}
+
+#[test]
+fn displacement_moves_the_surface_along_its_normal() {
+    // the left half of the square is low, the right one high
+    let map = Texture::from_fn(16, 16, |x, _| {
+        let height = if x < 8 { 0 } else { 0xff };
+        Color::from([height; 3])
+    });
+    let flat = render(None);
+    let displaced = render(Some((map, 0.5)));
+
+    let row = SIZE / 2;
+    let at = |depth: &[Option<f32>], x: usize| depth[x + row * SIZE].unwrap();
+    for x in [SIZE / 2 - 12, SIZE / 2 + 12] {
+        assert!((at(&flat, x) - 3.0).abs() < 0.01, "{}", at(&flat, x));
+    }
+    // towards the camera, by the scale
+    let (low, high) = (at(&displaced, SIZE / 2 - 12), at(&displaced, SIZE / 2 + 12));
+    assert!((low - 3.0).abs() < 0.01, "{}", low);
+    assert!((high - 2.5).abs() < 0.01, "{}", high);
+
+    // a flat map moves all of it
+    let map = Texture::from_fn(16, 16, |_, _| Color::from([0xff; 3]));
+    let displaced = render(Some((map, 0.25)));
+    for x in [SIZE / 2 - 12, SIZE / 2, SIZE / 2 + 12] {
+        assert!(
+            (at(&displaced, x) - 2.75).abs() < 0.01,
+            "{}",
+            at(&displaced, x)
+        );
+    }
+}
+
+// coverage of the head, displaced by map
+fn head(displacement: Option<(Texture, f32)>) -> Vec<bool> {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let mut camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    // zoomed out, so the displaced head fits in the image
+    camera.zoom(0.8);
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    if let Some((map, scale)) = displacement {
+        renderer.set_displacement_map(map, scale);
+        renderer.set_subdivision_level(1);
+    }
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    renderer
+        .linear_depth()
+        .iter()
+        .map(Option::is_some)
+        .collect()
+}
+
+#[test]
+fn displaced_head_has_relief_at_its_silhouette() {
+    let count = |covered: &[bool]| covered.iter().filter(|&&c| c).count();
+    let plain = head(None);
+    // raised everywhere, or in stripes of the texture
+    let raised = head(Some((
+        Texture::from_pixel(16, 16, Color::from([0xff; 3])),
+        0.05,
+    )));
+    let stripes = Texture::from_fn(64, 64, |x, _| {
+        Color::from([if x % 16 < 8 { 0 } else { 0xff }; 3])
+    });
+    let striped = head(Some((stripes, 0.05)));
+    // the raised head is larger, the striped one in between
+    assert!(count(&raised) > count(&plain) * 21 / 20);
+    assert!(count(&striped) > count(&plain) && count(&striped) < count(&raised));
+
+    // where the stripes are high the outline grows like the raised one, elsewhere it doesn't
+    let only = |a: &[bool], b: &[bool]| a.iter().zip(b).filter(|(&a, &b)| a && !b).count();
+    assert!(only(&striped, &plain) > 40, "{}", only(&striped, &plain));
+    assert!(only(&raised, &striped) > 20, "{}", only(&raised, &striped));
+}
+
+#[test]
+fn displaced_objects_are_kept_until_the_displacement_changes() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("redisplaced_square.obj");
+    std::fs::write(&path, SQUARE).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let scene = Scene::single(model, None);
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    let at_center = |renderer: &mut Renderer, scene: &Scene| {
+        renderer.clear_all();
+        renderer.render_scene(scene);
+        renderer.linear_depth()[SIZE / 2 + SIZE / 2 * SIZE].unwrap()
+    };
+
+    let map = |height: u8| Texture::from_pixel(4, 4, Color::from([height; 3]));
+    renderer.set_displacement_map(map(0xff), 0.5);
+    assert!((at_center(&mut renderer, &scene) - 2.5).abs() < 0.01);
+    // the same again
+    assert!((at_center(&mut renderer, &scene) - 2.5).abs() < 0.01);
+    // a new map, a new scale or another model are displaced again
+    renderer.set_displacement_map(map(0), 0.5);
+    assert!((at_center(&mut renderer, &scene) - 3.0).abs() < 0.01);
+    renderer.set_displacement_map(map(0xff), 0.25);
+    assert!((at_center(&mut renderer, &scene) - 2.75).abs() < 0.01);
+    let mut moved = scene.clone();
+    for vertex in &mut moved.meshes[0].model.objects[0].vertices {
+        vertex.z += 0.5;
+    }
+    assert!((at_center(&mut renderer, &moved) - 2.25).abs() < 0.01);
+    renderer.clear_displacement_map();
+    assert!((at_center(&mut renderer, &scene) - 3.0).abs() < 0.01);
+}
@@ -0,0 +1,145 @@
+use std::process::Command;
+
+use glam::vec3;
+use tinyrenderer::{
+    read_model, Camera, Color, DepthOfField, Image, LoadOptions, RenderConfig, Renderer, Scene,
+    Texture, TextureExt,
+};
+
+const SIZE: u32 = 128;
+
+// checkered squares next to each other, 3 (left) and 6 (right) units from the viewer
+const SQUARES: &str = "
+o near
+v -1.2 -1 0
+v -0.1 -1 0
+v -0.1 1 0
+v -1.2 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+f 1/1 2/2 3/3
+f 1/1 3/3 4/4
+o far
+v 0.1 -2 -3
+v 2.4 -2 -3
+v 2.4 2 -3
+v 0.1 2 -3
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+f 5/5 6/6 7/7
+f 5/5 7/7 8/8
+";
+
+fn render(dof: Option<(f32, f32)>) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("dof_squares.obj");
+    std::fs::write(&path, SQUARES).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let texture = Texture::checkerboard(
+        64,
+        64,
+        16,
+        Color::from([255, 255, 255]),
+        Color::from([0, 0, 0]),
+    );
+
+    let camera = Camera::perspective(
+        vec3(0.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        1.0,
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_light_direction(vec3(0.0, 0.0, 1.0));
+    if let Some((focus, aperture)) = dof {
+        renderer.set_dof(focus, aperture);
+    }
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("dof.png");
+    renderer.save(path.to_str().unwrap()).unwrap();
+    image::open(path).unwrap().into_rgb8()
+}
+
+// sum of differences between horizontally adjacent pixels in columns xs
+fn contrast(image: &Image, xs: std::ops::Range<u32>) -> u32 {
+    let mut sum = 0;
+    for y in 0..image.height() {
+        for x in xs.clone().skip(1) {
+            let (a, b) = (image.get_pixel(x - 1, y)[1], image.get_pixel(x, y)[1]);
+            sum += (a as i32 - b as i32).unsigned_abs();
+        }
+    }
+    sum
+}
+
+#[test]
+fn blur_grows_away_from_focus() {
+    let sharp = render(None);
+    let focused = render(Some((3.0, 8.0)));
+    let (left, right) = (4..SIZE / 2 - 8, SIZE / 2 + 8..SIZE - 4);
+    assert!(contrast(&sharp, left.clone()) > 0 && contrast(&sharp, right.clone()) > 0);
+
+    // the near square is in focus
+    for y in 0..SIZE {
+        for x in left.clone() {
+            assert_eq!(
+                focused.get_pixel(x, y),
+                sharp.get_pixel(x, y),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+    // the far one isn't
+    assert!(contrast(&focused, right.clone()) * 2 < contrast(&sharp, right.clone()));
+
+    // and the other way around
+    let far_focus = render(Some((6.0, 8.0)));
+    assert!(contrast(&far_focus, left.clone()) * 2 < contrast(&sharp, left));
+    assert!(contrast(&far_focus, right.clone()) * 10 > contrast(&sharp, right) * 9);
+}
+
+#[test]
+fn near_and_far_blur_around_focus() {
+    let sharp = render(None);
+    let (left, right) = (4..SIZE / 2 - 8, SIZE / 2 + 8..SIZE - 4);
+    // between the squares, so the near one blurs as well as the far one
+    let between = render(Some((4.5, 8.0)));
+    assert!(contrast(&between, left.clone()) * 2 < contrast(&sharp, left.clone()));
+    assert!(contrast(&between, right.clone()) * 2 < contrast(&sharp, right.clone()));
+    // less with a smaller aperture
+    let narrow = render(Some((4.5, 2.0)));
+    assert!(contrast(&narrow, left.clone()) > contrast(&between, left));
+    assert!(contrast(&narrow, right.clone()) > contrast(&between, right));
+}
+
+#[test]
+fn dof_is_set_from_the_command_line() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (config, output) = (dir.join("dof.toml"), dir.join("dof_cli.png"));
+    let run = |dof: &str| {
+        Command::new(env!("CARGO_BIN_EXE_tinyrenderer"))
+            .args(["--dof", dof, "--size", "32x32", "--save-config"])
+            .arg(&config)
+            .arg("--output")
+            .arg(&output)
+            .output()
+            .unwrap()
+    };
+    assert!(run("3,8").status.success());
+    let config = RenderConfig::load(config.to_str().unwrap()).unwrap();
+    let dof = DepthOfField {
+        focus_distance: 3.0,
+        aperture: 8.0,
+    };
+    assert_eq!(config.dof, Some(dof));
+
+    let invalid = run("3");
+    assert!(!invalid.status.success());
+    let stderr = String::from_utf8(invalid.stderr).unwrap();
+    assert!(stderr.contains("Invalid depth of field"), "{}", stderr);
+}
@@ -0,0 +1,32 @@
+use glam::vec3;
+use tinyrenderer::{read_model, read_texture, Camera, LoadOptions, PixelFormat, Renderer, Scene};
+
+#[test]
+fn export_swizzles_channels() {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let camera = Camera::new(
+        vec3(0.5, 0.0, -0.5),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (64, 48));
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+
+    let rgb = renderer.export_as(PixelFormat::Rgb);
+    let rgba = renderer.export_as(PixelFormat::Rgba);
+    let bgra = renderer.export_as(PixelFormat::Bgra);
+    assert_eq!(rgb, renderer.image().as_raw().clone());
+    assert_eq!(rgba.len(), 64 * 48 * 4);
+    assert_eq!(bgra.len(), 64 * 48 * 4);
+
+    let mut colored = 0;
+    for ((rgb, rgba), bgra) in rgb.chunks(3).zip(rgba.chunks(4)).zip(bgra.chunks(4)) {
+        assert_eq!(rgba, [rgb[0], rgb[1], rgb[2], 0xff]);
+        assert_eq!(bgra, [rgba[2], rgba[1], rgba[0], rgba[3]]);
+        if rgb[0] != rgb[2] {
+            colored += 1;
+        }
+    }
+    assert!(colored > 0);
+}
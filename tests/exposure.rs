@@ -0,0 +1,119 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_model, Camera, Light, LoadOptions, PixelFormat, Renderer, Scene, LUMINANCE_STOPS,
+};
+
+const SIZE: u32 = 32;
+
+// square in the middle of the image facing the camera
+const QUAD: &str = "
+v -0.5 -0.5 0
+v 0.5 -0.5 0
+v 0.5 0.5 0
+v -0.5 0.5 0
+f 1 2 3
+f 1 3 4
+";
+
+fn renderer(intensity: f32) -> Renderer {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("exposed_quad.obj");
+    std::fs::write(&path, QUAD).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    // the key light barely reaches the square (lighting is at least 0.2), the others make up
+    // intensity
+    renderer.set_light_direction(vec3(1.0, 0.0, 0.05));
+    for _ in 0..4 {
+        renderer.add_light(Light {
+            direction: vec3(0.0, 0.0, 1.0),
+            intensity: intensity / 4.0,
+            casts_shadows: false,
+        });
+    }
+    renderer.render_scene(&Scene::single(model, None));
+    renderer
+}
+
+// green channel (in [0; 1]) of the saved image at the center of the square and at a corner
+fn brightness(renderer: &Renderer) -> (f32, f32) {
+    let pixels = renderer.export_as(PixelFormat::Rgb);
+    let green = |x: u32, y: u32| pixels[((x + y * SIZE) * 3 + 1) as usize] as f32 / 255.0;
+    (green(SIZE / 2, SIZE / 2), green(0, 0))
+}
+
+#[test]
+fn auto_exposure_reaches_target() {
+    let target = 0.5;
+    for &intensity in &[0.05, 0.9] {
+        let mut renderer = renderer(intensity);
+        let (before, _) = brightness(&renderer);
+        assert_eq!(renderer.exposure(), None);
+
+        renderer.set_auto_exposure(target);
+        let exposure = renderer.exposure().unwrap();
+        let (after, background) = brightness(&renderer);
+        if before < target.sqrt() {
+            // brightened
+            assert!(exposure > 1.0 && after > before, "{} {}", before, after);
+        } else {
+            assert!(exposure < 1.0 && after < before, "{} {}", before, after);
+        }
+        // the square is all there is, so it gets the target luminance (gamma corrected)
+        assert!(
+            (after - target.sqrt()).abs() < 0.02,
+            "{} {}",
+            intensity,
+            after
+        );
+
+        // and the background stays black
+        assert_eq!(background, 0.0);
+    }
+}
+
+#[test]
+fn luminance_histogram_sets_exposure() {
+    const BINS: usize = 16;
+    let stop = |bin: usize| {
+        let (min, max) = LUMINANCE_STOPS;
+        min + (max - min) * bin as f32 / BINS as f32
+    };
+
+    let (dark, bright) = (renderer(0.0), renderer(8.0));
+    let mut bins = Vec::new();
+    for renderer in &[&dark, &bright] {
+        // only the square is counted, all of it in the same bin
+        let histogram = renderer.luminance_histogram(BINS);
+        assert_eq!(histogram.iter().sum::<usize>(), (SIZE * SIZE / 4) as usize);
+        let bin = histogram.iter().position(|&n| n > 0).unwrap();
+        assert_eq!(
+            histogram[bin],
+            (SIZE * SIZE / 4) as usize,
+            "{:?}",
+            histogram
+        );
+        bins.push(bin);
+    }
+    // lit at least by 0.2, and by 8 more (beyond white)
+    assert!(stop(bins[0]) <= 0.2f32.log2() && stop(bins[0] + 1) > 0.2f32.log2());
+    assert!(stop(bins[1]) <= 8.0f32.log2() && stop(bins[1] + 1) > 8.0f32.log2());
+
+    // the dark scene is brightened and the bright one is darkened to the same target
+    let mut exposures = Vec::new();
+    for mut renderer in [dark, bright] {
+        assert_eq!(renderer.auto_exposure(), None);
+        renderer.set_auto_exposure(0.5);
+        assert_eq!(renderer.auto_exposure(), Some(0.5));
+        exposures.push(renderer.exposure().unwrap());
+        renderer.clear_auto_exposure();
+        assert_eq!(renderer.exposure(), None);
+    }
+    assert!(exposures[0] > 2.0 && exposures[1] < 0.1, "{:?}", exposures);
+    assert!(renderer(8.0).luminance_histogram(0).is_empty());
+}
@@ -0,0 +1,64 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{Camera, Color, Renderer};
+
+const SIZE: u32 = 24;
+const BLACK: [u8; 3] = [0, 0, 0];
+const RED: [u8; 3] = [255, 0, 0];
+const GREEN: [u8; 3] = [0, 255, 0];
+const BLUE: [u8; 3] = [0, 0, 255];
+
+fn looking_from(lookfrom: Vec3) -> Renderer {
+    let camera = Camera::new(lookfrom, vec3(0.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0));
+    Renderer::new(camera, (SIZE as usize, SIZE as usize))
+}
+
+fn pixels(renderer: &Renderer, color: [u8; 3]) -> Vec<(u32, u32)> {
+    let image = renderer.image();
+    let pixels = (0..SIZE).flat_map(|y| (0..SIZE).map(move |x| (x, y)));
+    pixels
+        .filter(|&(x, y)| image.get_pixel(x, y).0 == color)
+        .collect()
+}
+
+#[test]
+fn axes_have_their_colors() {
+    // looking along z, x goes to the right and y up, z towards the camera
+    let mut renderer = looking_from(vec3(0.0, 0.0, -1.0));
+    renderer.draw_axes(0.8);
+    let center = SIZE / 2;
+    let red = pixels(&renderer, RED);
+    let green = pixels(&renderer, GREEN);
+    assert!(red.len() > 5 && red.iter().all(|&(x, y)| x > center && y == center));
+    assert!(green.len() > 5 && green.iter().all(|&(x, y)| x == center && y > center));
+    // in front of the other two at the origin
+    assert_eq!(pixels(&renderer, BLUE), [(center, center)]);
+
+    // from the side every axis is seen, and nothing else is drawn
+    let mut renderer = looking_from(vec3(-1.0, -1.0, -1.0));
+    renderer.draw_axes(0.8);
+    let colored = [RED, GREEN, BLUE].map(|color| pixels(&renderer, color).len());
+    assert!(colored.iter().all(|&n| n > 5), "{:?}", colored);
+    let black = pixels(&renderer, BLACK).len();
+    assert_eq!(
+        black + colored.iter().sum::<usize>(),
+        (SIZE * SIZE) as usize
+    );
+}
+
+#[test]
+fn grid_has_its_color() {
+    let gray = [0x80, 0x80, 0x80];
+    let mut renderer = looking_from(vec3(-1.0, -1.0, -1.0));
+    renderer.draw_grid(0.5, 0.25, Color::from(gray));
+    let grid = pixels(&renderer, gray).len();
+    assert!(grid > SIZE as usize);
+    assert_eq!(
+        grid + pixels(&renderer, BLACK).len(),
+        (SIZE * SIZE) as usize
+    );
+
+    // a grid without lines draws nothing
+    let mut renderer = looking_from(vec3(-1.0, -1.0, -1.0));
+    renderer.draw_grid(0.5, 0.0, Color::from(gray));
+    assert_eq!(pixels(&renderer, BLACK).len(), (SIZE * SIZE) as usize);
+}
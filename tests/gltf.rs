@@ -0,0 +1,110 @@
+#![cfg(feature = "gltf")]
+
+use glam::vec3;
+use tinyrenderer::{read_gltf, read_model, Camera, LoadOptions, Renderer, Scene};
+
+// corners of the cube from -1 to 1 (bit 0 is x, 1 is y, 2 is z), and its faces wound
+// counter-clockwise seen from outside
+const CORNERS: usize = 8;
+const FACES: [[u16; 4]; 6] = [
+    [0, 4, 6, 2],
+    [1, 3, 7, 5],
+    [0, 1, 5, 4],
+    [2, 6, 7, 3],
+    [0, 2, 3, 1],
+    [4, 5, 7, 6],
+];
+
+fn corner(i: usize) -> [f32; 3] {
+    let s = |bit: usize| if i & bit != 0 { 1.0 } else { -1.0 };
+    [s(1), s(2), s(4)]
+}
+
+fn triangles() -> impl Iterator<Item = [u16; 3]> {
+    FACES
+        .iter()
+        .flat_map(|f| [[f[0], f[1], f[2]], [f[0], f[2], f[3]]])
+}
+
+fn cube_obj() -> String {
+    let mut obj = String::new();
+    for [x, y, z] in (0..CORNERS).map(corner) {
+        obj += &format!("v {} {} {}\n", x, y, z);
+    }
+    for [a, b, c] in triangles() {
+        obj += &format!("f {} {} {}\n", a + 1, b + 1, c + 1);
+    }
+    obj
+}
+
+// glTF of the same cube, with positions and indices in cube.bin
+fn cube_gltf() -> (String, Vec<u8>) {
+    let mut bin = Vec::new();
+    for c in (0..CORNERS).flat_map(corner) {
+        bin.extend_from_slice(&c.to_le_bytes());
+    }
+    let positions = bin.len();
+    for i in triangles().flatten() {
+        bin.extend_from_slice(&i.to_le_bytes());
+    }
+    let indices = bin.len() - positions;
+
+    let gltf = format!(
+        r#"{{
+  "asset": {{ "version": "2.0" }},
+  "scene": 0,
+  "scenes": [{{ "nodes": [0] }}],
+  "nodes": [{{ "mesh": 0 }}],
+  "meshes": [{{ "name": "cube", "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1 }}] }}],
+  "buffers": [{{ "uri": "cube.bin", "byteLength": {total} }}],
+  "bufferViews": [
+    {{ "buffer": 0, "byteOffset": 0, "byteLength": {positions} }},
+    {{ "buffer": 0, "byteOffset": {positions}, "byteLength": {indices} }}
+  ],
+  "accessors": [
+    {{ "bufferView": 0, "componentType": 5126, "count": {CORNERS}, "type": "VEC3",
+       "min": [-1, -1, -1], "max": [1, 1, 1] }},
+    {{ "bufferView": 1, "componentType": 5123, "count": {count}, "type": "SCALAR" }}
+  ]
+}}"#,
+        total = bin.len(),
+        count = indices / 2,
+    );
+    (gltf, bin)
+}
+
+fn render(scene: &Scene) -> Renderer {
+    // seen from a corner
+    let camera = Camera::new(
+        vec3(-1.0, -1.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (64, 64));
+    renderer.render_scene(scene);
+    renderer
+}
+
+#[test]
+fn gltf_cube_renders_like_obj_one() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (gltf, bin) = cube_gltf();
+    std::fs::write(dir.join("cube.gltf"), gltf).unwrap();
+    std::fs::write(dir.join("cube.bin"), bin).unwrap();
+    std::fs::write(dir.join("cube.obj"), cube_obj()).unwrap();
+
+    let scene = read_gltf(dir.join("cube.gltf").to_str().unwrap()).unwrap();
+    assert_eq!(scene.meshes.len(), 1);
+    assert_eq!(scene.instances.len(), 1);
+    let object = &scene.meshes[0].model.objects[0];
+    assert_eq!(object.name, "cube");
+    assert_eq!(object.vertices.len(), CORNERS);
+    assert_eq!(object.geometry[0].shapes.len(), 12);
+
+    let path = dir.join("cube.obj");
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let expected = render(&Scene::single(model, None));
+    let rendered = render(&scene);
+    assert!(expected.image().pixels().any(|p| p.0 != [0, 0, 0]));
+    assert!(rendered.image() == expected.image());
+}
@@ -0,0 +1,101 @@
+// Renders compared against committed reference images (tests/golden/*.png).
+// After an intended change of the output regenerate them with
+//     UPDATE_GOLDEN=1 cargo test --test golden
+// and check the new images before committing them.
+use std::path::PathBuf;
+
+use glam::vec3;
+use tinyrenderer::{
+    read_model, read_texture, CameraConfig, Image, Projection, RenderConfig, Scene,
+};
+
+// largest allowed difference of a color channel
+const TOLERANCE: u8 = 2;
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{}.png", name))
+}
+
+// compare image to the reference called name (or replace it if UPDATE_GOLDEN is set)
+fn check_golden(name: &str, image: &Image) {
+    let path = golden_path(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        image.save(&path).unwrap();
+        return;
+    }
+
+    let golden = match image::open(&path) {
+        Ok(golden) => golden.to_rgb8(),
+        Err(e) => panic!(
+            "Could not read {:?} ({}), run with UPDATE_GOLDEN=1 to create it",
+            path, e
+        ),
+    };
+    assert_eq!(
+        image.dimensions(),
+        golden.dimensions(),
+        "{}: size differs from the golden image",
+        name
+    );
+
+    let mut max = 0;
+    let mut sum = 0u64;
+    let mut differing = 0;
+    for (a, b) in image.pixels().zip(golden.pixels()) {
+        let difference = (0..3)
+            .map(|i| (a[i] as i32 - b[i] as i32).unsigned_abs() as u8)
+            .max()
+            .unwrap_or(0);
+        max = max.max(difference);
+        sum += difference as u64;
+        if difference > TOLERANCE {
+            differing += 1;
+        }
+    }
+
+    let mean = sum as f64 / (image.width() * image.height()) as f64;
+    assert!(
+        max <= TOLERANCE,
+        "{}: {} pixels differ from the golden image by more than {}, max difference {}, mean {:.4}",
+        name,
+        differing,
+        TOLERANCE,
+        max,
+        mean
+    );
+}
+
+fn head_config() -> RenderConfig {
+    RenderConfig {
+        width: 256,
+        height: 256,
+        camera: CameraConfig {
+            lookfrom: vec3(0.5, 0.0, -0.5),
+            lookat: vec3(0.0, 0.0, 0.0),
+            up: vec3(0.0, 1.0, 0.0),
+            projection: Projection::Orthographic { scale: 1.0 },
+            ..CameraConfig::default()
+        },
+        ..RenderConfig::default()
+    }
+}
+
+fn render(config: &RenderConfig) -> Image {
+    let model = read_model(&config.model, &config.load).unwrap();
+    let texture = config
+        .texture
+        .as_ref()
+        .map(|path| read_texture(path).unwrap());
+
+    let mut renderer = config.renderer();
+    renderer.render_scene(&Scene::single(model, texture));
+    renderer.flipv();
+    renderer.image().clone()
+}
+
+#[test]
+fn african_head() {
+    check_golden("african_head", &render(&head_config()));
+}
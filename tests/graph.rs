@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+
+use glam::vec3;
+use tinyrenderer::{
+    read_model, Camera, Image, LoadOptions, PassBlend, PassBuffer, RenderGraph, Renderer, Scene,
+};
+
+const SIZE: usize = 128;
+
+// ground facing the camera, a square high above it casting a shadow map shadow and a bump
+// just above it casting a contact shadow too
+const QUADS: &str = "
+o ground
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+f 1 2 3
+f 1 3 4
+o occluder
+v 0.2 0.2 0.6
+v 0.6 0.2 0.6
+v 0.6 0.6 0.6
+v 0.2 0.6 0.6
+f 5 6 7
+f 5 7 8
+o bump
+v -0.1 -0.5 0.1
+v 0.1 -0.5 0.1
+v 0.1 -0.3 0.1
+v -0.1 -0.3 0.1
+f 9 10 11
+f 9 11 12
+";
+
+fn scene() -> Scene {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("graph_quads.obj");
+    std::fs::write(&path, QUADS).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    Scene::single(model, None)
+}
+
+fn renderer() -> Renderer {
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.set_light_direction(vec3(0.6, 0.0, 1.0));
+    renderer.set_shadows(32);
+    renderer.set_contact_shadows(0.3, 16);
+    renderer
+}
+
+#[test]
+fn passes_run_in_dependency_order() {
+    let scene = scene();
+    let mut manual = renderer();
+    manual.render_scene(&scene);
+    let expected: Image = manual.image().clone();
+
+    // added in the reverse order
+    let mut graph = RenderGraph::new();
+    graph
+        .contact_shadow_pass()
+        .main_pass(&scene)
+        .shadow_pass(&scene);
+    assert_eq!(
+        graph.order().unwrap(),
+        vec!["shadow", "main", "contact shadows"]
+    );
+    let mut rendered = renderer();
+    graph.execute(&mut rendered).unwrap();
+    assert!(rendered.image() == &expected);
+
+    // without either kind of shadows it's a different image
+    let mut unshadowed = renderer();
+    RenderGraph::new()
+        .shadow_pass(&scene)
+        .main_pass(&scene)
+        .execute(&mut unshadowed)
+        .unwrap();
+    assert!(unshadowed.image() != &expected);
+    let mut unshadowed = renderer();
+    RenderGraph::new()
+        .main_pass(&scene)
+        .contact_shadow_pass()
+        .execute(&mut unshadowed)
+        .unwrap();
+    assert!(unshadowed.image() != &expected);
+
+    // blended like any other render call
+    let mut twice = renderer();
+    twice.render_scene(&scene);
+    twice.set_pass_blend(PassBlend::Add);
+    graph.execute(&mut twice).unwrap();
+    assert!(twice.image() != &expected);
+}
+
+#[test]
+fn custom_passes_are_sorted() {
+    let ran = RefCell::new(Vec::new());
+    let pass = |name: &'static str| {
+        let ran = &ran;
+        move |_: &mut Renderer| ran.borrow_mut().push(name)
+    };
+
+    let (color, depth) = (PassBuffer::Color, PassBuffer::Depth);
+    let mut graph = RenderGraph::new();
+    graph
+        .add_pass("post", &[color], &[], pass("post"))
+        .add_pass("outline", &[depth, color], &[color], pass("outline"))
+        .add_pass("fog", &[depth, color], &[color], pass("fog"))
+        .add_pass("draw", &[], &[depth, color], pass("draw"));
+    graph.execute(&mut renderer()).unwrap();
+    // passes modifying the image keep their order
+    assert_eq!(*ran.borrow(), vec!["draw", "outline", "fog", "post"]);
+    assert_eq!(graph.order().unwrap(), *ran.borrow());
+
+    let mut cyclic = RenderGraph::new();
+    cyclic
+        .add_pass("a", &[depth], &[color], |_| {})
+        .add_pass("b", &[color], &[depth], |_| {});
+    assert!(cyclic.order().is_err());
+    assert!(cyclic.execute(&mut renderer()).is_err());
+}
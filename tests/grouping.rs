@@ -0,0 +1,80 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_materials, read_model, Camera, GroupingMode, LoadOptions, Renderer, Scene,
+};
+
+const SIZE: u32 = 64;
+
+// two squares side by side as `g` groups with their own materials, there is no `o`
+const GROUPS: &str = "
+v -1 -1 0
+v 0 -1 0
+v 0 1 0
+v -1 1 0
+v 1 -1 0
+v 1 1 0
+g left
+usemtl red
+f 1 2 3
+f 1 3 4
+g right
+usemtl blue
+f 2 5 6
+f 2 6 3
+";
+
+const MTL: &str = "
+newmtl red
+Kd 1 0 0
+newmtl blue
+Kd 0 0 1
+";
+
+fn render(grouping: GroupingMode) -> Renderer {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (obj, mtl) = (dir.join("g_only.obj"), dir.join("g_only.mtl"));
+    std::fs::write(&obj, GROUPS).unwrap();
+    std::fs::write(&mtl, MTL).unwrap();
+    let model = read_model(obj.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+    renderer.set_grouping_mode(grouping);
+    renderer.set_triangle_id_buffer(true);
+    renderer.render_scene(&Scene::single(model, None));
+    renderer
+}
+
+#[test]
+fn groups_get_their_own_materials() {
+    for grouping in [GroupingMode::Groups, GroupingMode::Objects] {
+        let renderer = render(grouping);
+        let image = renderer.image();
+        for y in 0..SIZE {
+            for x in [2, SIZE / 2 - 3] {
+                let p = image.get_pixel(x, y);
+                assert!(p[0] > 0 && (p[1], p[2]) == (0, 0), "{:?}", p);
+            }
+            for x in [SIZE / 2 + 2, SIZE - 3] {
+                let p = image.get_pixel(x, y);
+                assert!(p[2] > 0 && (p[0], p[1]) == (0, 0), "{:?}", p);
+            }
+        }
+    }
+}
+
+#[test]
+fn groups_are_objects_of_their_own() {
+    let object = |renderer: &Renderer, x| renderer.pick(x, SIZE / 2).unwrap().object;
+    let groups = render(GroupingMode::Groups);
+    assert_eq!(object(&groups, 2), "left");
+    assert_eq!(object(&groups, SIZE - 3), "right");
+
+    // there's a single unnamed object otherwise
+    let objects = render(GroupingMode::Objects);
+    assert_eq!(object(&objects, 2), object(&objects, SIZE - 3));
+}
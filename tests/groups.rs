@@ -0,0 +1,85 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, GroupFilter, Image, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 64;
+
+// two squares side by side facing the camera, in different groups
+const QUADS: &str = "
+v -1 -1 0
+v 0 -1 0
+v 0 1 0
+v -1 1 0
+v 0 -1 0
+v 1 -1 0
+v 1 1 0
+v 0 1 0
+g left
+f 1 2 3
+f 1 3 4
+g right
+f 5 6 7
+f 5 7 8
+";
+
+fn render(filter: GroupFilter, colors: &[(&str, [f32; 3])]) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("groups_quads.obj");
+    std::fs::write(&path, QUADS).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_group_filter(filter);
+    for &(group, [r, g, b]) in colors {
+        renderer.set_group_color(group, vec3(r, g, b));
+    }
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+fn only(group: &str) -> GroupFilter {
+    GroupFilter::Only(vec![group.to_owned()])
+}
+
+#[test]
+fn renders_only_selected_groups() {
+    let all = render(GroupFilter::All, &[]);
+    let left = render(only("left"), &[]);
+    let right = render(GroupFilter::Except(vec!["left".to_owned()]), &[]);
+    assert!(render(only("default"), &[]).pixels().all(|p| p.0 == [0, 0, 0]));
+
+    let (mut in_left, mut in_right) = (0, 0);
+    for ((a, l), r) in all.pixels().zip(left.pixels()).zip(right.pixels()) {
+        // every covered pixel is drawn by exactly one of the groups, as it was with both
+        assert!(l.0 == [0, 0, 0] || r.0 == [0, 0, 0]);
+        if l.0 != [0, 0, 0] {
+            assert_eq!(a, l);
+            in_left += 1;
+        } else {
+            assert_eq!(a, r);
+            in_right += (r.0 != [0, 0, 0]) as usize;
+        }
+    }
+    assert!(in_left > 0 && in_right > 0);
+    // the groups are on different sides
+    let column = |image: &Image, x| (0..SIZE).any(|y| image.get_pixel(x, y).0 != [0, 0, 0]);
+    assert!(column(&left, 2) != column(&left, SIZE - 3));
+    assert!(column(&right, 2) != column(&right, SIZE - 3));
+}
+
+#[test]
+fn recolors_groups() {
+    let left = render(only("left"), &[]);
+    let red = render(GroupFilter::All, &[("left", [1.0, 0.0, 0.0])]);
+    let right = render(GroupFilter::Except(vec!["left".to_owned()]), &[]);
+    for ((l, r), c) in left.pixels().zip(right.pixels()).zip(red.pixels()) {
+        if l.0 != [0, 0, 0] {
+            assert_eq!(c.0, [l[0], 0, 0]);
+        } else {
+            assert_eq!(c, r);
+        }
+    }
+}
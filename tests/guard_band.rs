@@ -0,0 +1,53 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{read_model, Camera, Color, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 64;
+
+fn renderer() -> Renderer {
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    Renderer::new(camera, (SIZE as usize, SIZE as usize))
+}
+
+// whether pixel (x, y) is drawn
+fn covered(renderer: &Renderer, x: u32, y: u32) -> bool {
+    renderer.image().get_pixel(x, y).0 != [0, 0, 0]
+}
+
+#[test]
+fn vertex_far_outside_of_the_view_renders_its_visible_part() {
+    // almost a horizontal band from x = -0.5 to the right, its tip ~3e10 pixels away
+    let obj = "v -0.5 -0.5 0\nv 1e9 0 0\nv -0.5 0.5 0\nf 1 2 3\n";
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("far_vertex.obj");
+    std::fs::write(&path, obj).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let mut renderer = renderer();
+    renderer.render_scene(&Scene::single(model, None));
+
+    // pixels at ndc (-0.5; 1) x (-0.5; 0.5) are covered, the rest isn't
+    let quarter = SIZE / 4;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let inside = x > quarter && y > quarter && y < SIZE - quarter - 1;
+            let outside = x < quarter - 1 || y < quarter - 1 || y > SIZE - quarter;
+            if inside || outside {
+                assert_eq!(covered(&renderer, x, y), inside, "({}, {})", x, y);
+            }
+        }
+    }
+
+    // and so is a line to there
+    let mut renderer = self::renderer();
+    let white = Color::from([0xff; 3]);
+    renderer.line(Vec3::zero(), vec3(1e9, 0.0, 0.0), white);
+    let row = (0..SIZE)
+        .filter(|&x| covered(&renderer, x, SIZE / 2))
+        .count() as u32;
+    assert!(row >= SIZE / 2 - 1, "{}", row);
+    let image = renderer.image();
+    let drawn = image.pixels().filter(|p| p.0 != [0, 0, 0]).count() as u32;
+    assert_eq!(drawn, row);
+}
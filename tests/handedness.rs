@@ -0,0 +1,43 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_model, read_texture, Camera, Handedness, Image, LoadOptions, Renderer, Scene,
+};
+
+const SIZE: u32 = 128;
+
+fn render(handedness: Handedness) -> Image {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let mut camera = Camera::new(
+        vec3(0.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    camera.set_handedness(handedness);
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    renderer.image().clone()
+}
+
+#[test]
+fn left_handed_coordinates_mirror_the_image() {
+    let right = render(Handedness::RightHanded);
+    let left = render(Handedness::LeftHanded);
+    assert!(left != right);
+
+    // the same faces are culled and lit the same way (the light is symmetric), up to pixels
+    // rasterized differently at edges
+    let (mut covered, mut differing) = (0, 0);
+    for (x, y, &p) in right.enumerate_pixels() {
+        let mirrored = *left.get_pixel(SIZE - 1 - x, y);
+        if p.0 != [0, 0, 0] {
+            covered += 1;
+        }
+        let difference = (0..3).map(|c| (p[c] as i32 - mirrored[c] as i32).abs());
+        if difference.max().unwrap() > 16 {
+            differing += 1;
+        }
+    }
+    assert!(covered > (SIZE * SIZE / 4) as usize);
+    assert!(differing * 50 < covered, "{} of {}", differing, covered);
+}
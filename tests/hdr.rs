@@ -0,0 +1,58 @@
+#![cfg(feature = "exr")]
+
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, Light, LoadOptions, Renderer, Scene};
+
+const SIZE: usize = 16;
+
+// white square facing the camera
+const QUAD: &str = "
+v -0.5 -0.5 0
+v 0.5 -0.5 0
+v 0.5 0.5 0
+v -0.5 0.5 0
+f 1 2 3
+f 1 3 4
+";
+
+#[test]
+fn linear_values_above_one_survive_exr() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("hdr_quad.obj");
+    std::fs::write(&path, QUAD).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    // the key light and 3 times as bright a light from the camera too
+    renderer.add_light(Light {
+        direction: vec3(0.0, 0.0, 1.0),
+        intensity: 3.0,
+        casts_shadows: false,
+    });
+    renderer.render_scene(&Scene::single(model, None));
+
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("hdr.exr");
+    renderer.save_hdr(path.to_str().unwrap()).unwrap();
+    let image = exr::prelude::read_first_rgba_layer_from_file(
+        &path,
+        |resolution, _| vec![(0.0, 0.0, 0.0); resolution.width() * resolution.height()],
+        move |pixels: &mut Vec<(f32, f32, f32)>, position, (r, g, b, _): (f32, f32, f32, f32)| {
+            pixels[position.x() + position.y() * SIZE] = (r, g, b)
+        },
+    )
+    .unwrap();
+    let pixels = image.layer_data.channel_data.pixels;
+
+    // 8-bit colors saturate, but in the middle of the square the light adds up to 4
+    assert_eq!(renderer.image().get_pixel(8, 8).0, [255, 255, 255]);
+    let (r, g, b) = pixels[8 + 8 * SIZE];
+    for c in [r, g, b] {
+        assert!((c - 4.0).abs() < 1e-3, "{:?}", (r, g, b));
+    }
+    // and the background is black
+    assert_eq!(pixels[0], (0.0, 0.0, 0.0));
+}
@@ -0,0 +1,65 @@
+use glam::vec3;
+use tinyrenderer::{read_model, read_texture, Camera, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 96;
+
+// small square in front of a larger one, with a gap around it
+const QUADS: &str = "
+o ground
+v -0.8 -0.8 0
+v 0.8 -0.8 0
+v 0.8 0.8 0
+v -0.8 0.8 0
+f 1 2 3
+f 1 3 4
+o occluder
+v -0.3 -0.3 0.3
+v 0.3 -0.3 0.3
+v 0.3 0.3 0.3
+v -0.3 0.3 0.3
+f 5 6 7
+f 5 7 8
+";
+
+#[test]
+fn ids_are_colored_per_object() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let path = dir.join("ids_quads.obj");
+    std::fs::write(&path, QUADS).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_triangle_id_buffer(true);
+    renderer.render_scene(&Scene::single(model, None));
+    let ids = dir.join("ids.png");
+    renderer.save_ids(ids.to_str().unwrap()).unwrap();
+    let ids = read_texture(ids.to_str().unwrap()).unwrap();
+
+    // every object is a region of its own color with the silhouette pick finds
+    let (mut ground, mut occluder) = (None, None);
+    for (x, y, &color) in ids.enumerate_pixels() {
+        match renderer.pick(x, y).map(|pick| pick.object).as_deref() {
+            None => assert_eq!(color.0, [0, 0, 0], "({}, {})", x, y),
+            Some(object) => {
+                let region = if object == "ground" {
+                    &mut ground
+                } else {
+                    &mut occluder
+                };
+                assert_eq!(*region.get_or_insert(color), color, "({}, {})", x, y);
+            }
+        }
+    }
+    let (ground, occluder) = (ground.unwrap(), occluder.unwrap());
+    assert_ne!(ground, occluder);
+    assert_ne!(ground.0, [0, 0, 0]);
+    assert_ne!(occluder.0, [0, 0, 0]);
+    assert_eq!(*ids.get_pixel(SIZE / 2, SIZE / 2), occluder);
+    assert_eq!(*ids.get_pixel(SIZE / 8, SIZE / 2), ground);
+    assert_eq!(ids.get_pixel(0, 0).0, [0, 0, 0]);
+}
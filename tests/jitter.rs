@@ -0,0 +1,109 @@
+use std::collections::BTreeSet;
+
+use glam::{vec3, Vec2};
+use tinyrenderer::{
+    halton_jitter, read_model, Camera, Image, LoadOptions, Renderer, Scene, ShadingMode,
+};
+
+const SIZE: u32 = 32;
+
+// triangle with a shallow edge across the whole image, facing the camera
+const TRIANGLE: &str = "
+v -1 -1 0
+v 1 -1 0
+v -1 0.3 0
+f 1 2 3
+";
+
+fn render(jitter: Vec2) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("jitter.obj");
+    std::fs::write(&path, TRIANGLE).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    // flat colored, so pixels are either covered or not
+    renderer.set_shading_mode(ShadingMode::TriangleColors);
+    renderer.set_jitter(jitter);
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+#[test]
+fn halton_jitter_covers_the_pixel() {
+    let offsets: Vec<Vec2> = (0..16).map(halton_jitter).collect();
+    assert!((offsets[0] - Vec2::new(0.0, -1.0 / 6.0)).length() < 1e-6);
+    for offset in &offsets {
+        assert!(offset.x() >= -0.5 && offset.x() < 0.5, "{:?}", offset);
+        assert!(offset.y() >= -0.5 && offset.y() < 0.5, "{:?}", offset);
+    }
+    // every quarter of the pixel gets the same number of samples
+    for quarter in 0..4 {
+        let count = offsets
+            .iter()
+            .filter(|o| (o.x() >= 0.0) as usize + 2 * (o.y() >= 0.0) as usize == quarter)
+            .count();
+        assert!((3..=5).contains(&count), "{} {}", quarter, count);
+    }
+}
+
+#[test]
+fn jittered_frames_average_to_smooth_edge() {
+    let frames: Vec<Image> = (0..16).map(|frame| render(halton_jitter(frame))).collect();
+    assert!(render(Vec2::zero()) == render(Vec2::zero()));
+
+    // coverage is shifted a bit: only along the edges
+    let color = frames[0].pixels().map(|p| p[0]).max().unwrap();
+    for frame in &frames[1..] {
+        let differing = frame
+            .pixels()
+            .zip(frames[0].pixels())
+            .filter(|(a, b)| a != b)
+            .count();
+        assert!(
+            differing > 0 && differing < 4 * SIZE as usize,
+            "{}",
+            differing
+        );
+    }
+
+    // frames are either covered or not, their average has steps in between
+    let levels = |values: &mut dyn Iterator<Item = u32>| values.collect::<BTreeSet<_>>().len();
+    assert_eq!(levels(&mut frames[0].pixels().map(|p| p[0] as u32)), 2);
+    let average: Vec<u32> = (0..(SIZE * SIZE) as usize)
+        .map(|i| {
+            let (x, y) = (i as u32 % SIZE, i as u32 / SIZE);
+            frames
+                .iter()
+                .map(|f| f.get_pixel(x, y)[0] as u32)
+                .sum::<u32>()
+                / 16
+        })
+        .collect();
+    assert!(levels(&mut average.iter().copied()) >= 4);
+
+    // coverage falls off along every column crossing the edge, in steps in most of them (the
+    // edge can be too close to pixel borders for 16 frames to cross pixel centers)
+    let (mut crossing, mut smooth) = (0, 0);
+    for x in 0..SIZE {
+        let column: Vec<u32> = (0..SIZE)
+            .map(|y| average[(x + y * SIZE) as usize])
+            .collect();
+        if column[0] == 0 || column[SIZE as usize - 1] != 0 {
+            continue;
+        }
+        // (the bottom row is on an edge of the triangle too)
+        let column = &column[1..];
+        assert!(column.windows(2).all(|w| w[0] >= w[1]), "{:?}", column);
+        crossing += 1;
+        if column.iter().any(|&v| v > 0 && v < color as u32) {
+            smooth += 1;
+        }
+    }
+    assert!(crossing > SIZE / 2);
+    assert!(smooth * 4 > crossing * 3, "{} of {}", smooth, crossing);
+}
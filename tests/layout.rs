@@ -0,0 +1,84 @@
+use tinyrenderer::{read_model, render_uv_layout, uv_layout, Image, LoadOptions};
+
+const SIZE: u32 = 65;
+
+// square with UVs in the middle half of the texture, and with the same UVs in texture pixels
+fn quad(scale: f32) -> String {
+    let uv = |u: f32, v: f32| format!("vt {} {}\n", u * scale, v * scale);
+    format!(
+        "v -1 -1 0\nv 1 -1 0\nv 1 1 0\nv -1 1 0\n{}{}{}{}f 1/1 2/2 3/3\nf 1/1 3/3 4/4\n",
+        uv(0.25, 0.25),
+        uv(0.75, 0.25),
+        uv(0.75, 0.75),
+        uv(0.25, 0.75)
+    )
+}
+
+fn quad_layout(name: &str, obj: &str) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, obj).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    uv_layout(&model, (SIZE as usize, SIZE as usize))
+}
+
+fn lit(image: &Image, x: u32, y: u32) -> bool {
+    image.get_pixel(x, y).0 != [0, 0, 0]
+}
+
+#[test]
+fn quad_edges_are_drawn_in_texture_space() {
+    let layout = quad_layout("layout_quad.obj", &quad(1.0));
+    // the outline and the diagonal between (0.25, 0.25) and (0.75, 0.75), flipped like textures
+    let (low, high) = (16, 48);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let inside = (low..=high).contains(&x) && (low..=high).contains(&y);
+            let edge = inside && (x == low || x == high || y == low || y == high);
+            let diagonal = inside && x == SIZE - 1 - y;
+            assert_eq!(lit(&layout, x, y), edge || diagonal, "({}, {})", x, y);
+        }
+    }
+
+    // UVs in texture pixels are scaled down to it
+    assert!(quad_layout("layout_pixel_quad.obj", &quad(SIZE as f32)) == layout);
+}
+
+#[test]
+fn head_islands_are_outlined() {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let layout = uv_layout(&model, (SIZE as usize * 4, SIZE as usize * 4));
+    let lit_in = |x0: u32, y0: u32, x1: u32, y1: u32| {
+        let mut count = 0;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                count += lit(&layout, x, y) as usize;
+            }
+        }
+        count
+    };
+    let (width, height) = layout.dimensions();
+    // the head covers most of the texture, its mesh is dense around the eyes
+    assert!(lit_in(0, 0, width, height) > (width * height / 8) as usize);
+    let eye = lit_in(
+        width * 3 / 8,
+        height * 11 / 27,
+        width * 7 / 16,
+        height * 12 / 27,
+    );
+    let cheek = lit_in(
+        width * 5 / 16,
+        height * 5 / 8,
+        width * 3 / 8,
+        height * 2 / 3,
+    );
+    assert!(eye > cheek, "{} {}", eye, cheek);
+    // the top corners are outside of it, between it and the small islands of the eyes and teeth
+    // at the top there's nothing either
+    assert_eq!(lit_in(0, 0, width / 16, height / 16), 0);
+    assert_eq!(lit_in(width - width / 16, 0, width, height / 16), 0);
+    assert!(lit_in(width * 3 / 8, 0, width * 5 / 8, height / 16) > 0);
+
+    let out = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("head_layout.png");
+    render_uv_layout(&model, (32, 16), out.to_str().unwrap()).unwrap();
+    assert_eq!(image::image_dimensions(&out).unwrap(), (32, 16));
+}
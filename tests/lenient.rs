@@ -0,0 +1,75 @@
+use tinyrenderer::{read_model, read_model_lenient, LoadOptions, SkippedLine};
+
+// two triangles with a garbage line between them
+const MODEL: &str = "v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+f 1 2 3
+this is not a statement
+f 1 3 4
+";
+
+fn write(name: &str, obj: &str) -> String {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, obj).unwrap();
+    path.to_str().unwrap().to_owned()
+}
+
+fn triangles(model: &wavefront_obj::obj::ObjSet) -> usize {
+    let object = &model.objects[0];
+    object.geometry.iter().map(|g| g.shapes.len()).sum()
+}
+
+#[test]
+fn garbage_line_is_skipped() {
+    let path = write("lenient.obj", MODEL);
+    let options = LoadOptions::default();
+    assert!(read_model(&path, &options).is_err());
+
+    let (model, skipped) = read_model_lenient(&path, &options, 1).unwrap();
+    assert_eq!(model.objects.len(), 1);
+    assert_eq!(model.objects[0].vertices.len(), 4);
+    assert_eq!(triangles(&model), 2);
+    assert!(matches!(
+        skipped.as_slice(),
+        [SkippedLine { line_number: 6, content, .. }] if content == "this is not a statement"
+    ));
+
+    // with nothing to skip it's the model read_model reads
+    let clean = write(
+        "lenient_clean.obj",
+        &MODEL.replace("this is not a statement\n", ""),
+    );
+    let (lenient, skipped) = read_model_lenient(&clean, &options, 1).unwrap();
+    assert!(skipped.is_empty());
+    assert_eq!(lenient, read_model(&clean, &options).unwrap());
+}
+
+#[test]
+fn too_many_garbage_lines_are_errors() {
+    let options = LoadOptions::default();
+    let path = write("lenient_one.obj", MODEL);
+    assert!(read_model_lenient(&path, &options, 0).is_err());
+
+    // and every one of them counts, also without a trailing line break
+    let twice = MODEL.replace("f 1 2 3\n", "f 1 2 3\n???\n");
+    let path = write("lenient_two.obj", twice.trim_end());
+    assert!(read_model_lenient(&path, &options, 1).is_err());
+    let (model, skipped) = read_model_lenient(&path, &options, 2).unwrap();
+    assert_eq!(triangles(&model), 2);
+    let lines: Vec<usize> = skipped.iter().map(|line| line.line_number).collect();
+    assert_eq!(lines, [6, 7]);
+}
+
+#[test]
+fn skipped_lines_after_points_keep_their_numbers() {
+    let obj = MODEL.replace("f 1 2 3\n", "p 1 2 3 4\nf 1 2 3\n");
+    let path = write("lenient_points.obj", &obj);
+    let (model, skipped) = read_model_lenient(&path, &LoadOptions::default(), 1).unwrap();
+    assert_eq!(triangles(&model), 2 + 4);
+    assert!(matches!(
+        skipped.as_slice(),
+        [SkippedLine { line_number: 7, content, .. }] if content == "this is not a statement"
+    ));
+}
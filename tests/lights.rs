@@ -0,0 +1,89 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{read_model, Camera, Image, Light, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 96;
+
+// small square hovering in front of a large one, both facing the camera
+const QUADS: &str = "
+o ground
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+f 1 2 3
+f 1 3 4
+o occluder
+v -0.3 -0.3 0.3
+v 0.3 -0.3 0.3
+v 0.3 0.3 0.3
+v -0.3 0.3 0.3
+f 5 6 7
+f 5 7 8
+";
+
+fn render(key: Vec3, others: &[Light]) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("lights_quads.obj");
+    std::fs::write(&path, QUADS).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_shadows(256);
+    renderer.set_light_direction(key);
+    for &light in others {
+        renderer.add_light(light);
+    }
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+#[test]
+fn lights_shadow_independently() {
+    let (left, right) = (vec3(-0.6, 0.0, 1.0), vec3(0.6, 0.0, 1.0));
+    let right_light = |casts_shadows| Light {
+        direction: right,
+        intensity: 1.0,
+        casts_shadows,
+    };
+    let from_left = render(left, &[]);
+    let from_right = render(right, &[]);
+    let both = render(left, &[right_light(true)]);
+
+    // both squares face both lights at the same angle, so they only differ by shadows
+    let (mut shadowed_left, mut shadowed_right, mut lit) = (0, 0, 0);
+    for ((l, r), b) in from_left
+        .pixels()
+        .zip(from_right.pixels())
+        .zip(both.pixels())
+    {
+        if l[0] < r[0] {
+            // in the shadow of the left light, lit by the right one alone
+            assert_eq!(b, r);
+            shadowed_left += 1;
+        } else if r[0] < l[0] {
+            assert_eq!(b, l);
+            shadowed_right += 1;
+        } else {
+            assert!(b[0] > l[0], "{:?} {:?}", b, l);
+            lit += 1;
+        }
+    }
+    assert!(shadowed_left > 0 && shadowed_right > 0 && lit > 0);
+
+    // a light without shadows lights everything
+    let unshadowed = render(left, &[right_light(false)]);
+    for ((l, r), b) in from_left
+        .pixels()
+        .zip(from_right.pixels())
+        .zip(unshadowed.pixels())
+    {
+        assert!(b[0] > l[0].min(r[0]), "{:?} {:?} {:?}", b, l, r);
+        if r[0] < l[0] {
+            assert!(b[0] > l[0], "{:?} {:?}", b, l);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_materials, read_model, read_model_lenient, read_texture_limited, Camera, Color, Image,
+    LoadOptions, MaterialWarning, Renderer, TextureTooLarge,
+};
+
+// quad and a triangle
+const MODEL: &str = "
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+f 1 2 3 4
+f 1 2 3
+";
+
+#[test]
+fn models_with_too_many_triangles_are_rejected() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("limited.obj");
+    std::fs::write(&path, MODEL).unwrap();
+    let path = path.to_str().unwrap();
+
+    let options = |max_triangles, loop_subdivisions| LoadOptions {
+        max_triangles: Some(max_triangles),
+        loop_subdivisions,
+        ..LoadOptions::default()
+    };
+    assert!(read_model(path, &options(3, 0)).is_ok());
+    let error = read_model(path, &options(2, 0)).unwrap_err().to_string();
+    assert!(
+        error.contains("3 triangles") && error.contains("limit of 2"),
+        "{}",
+        error
+    );
+    // every level of subdivision makes 4 times more triangles
+    assert!(read_model(path, &options(12, 1)).is_ok());
+    let error = read_model(path, &options(11, 1)).unwrap_err().to_string();
+    assert!(error.contains("12 after 1 levels"), "{}", error);
+}
+
+#[test]
+fn points_arent_counted_as_triangles() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("limited_points.obj");
+    std::fs::write(&path, "v 0 0 0\nv 1 0 0\nv 0 1 0\np 1 2 3\nf 1 2 3\n").unwrap();
+    let path = path.to_str().unwrap();
+
+    let options = |max_triangles| LoadOptions {
+        max_triangles: Some(max_triangles),
+        ..LoadOptions::default()
+    };
+    assert!(read_model(path, &options(1)).is_ok());
+    assert!(read_model_lenient(path, &options(1), 0).is_ok());
+    let error = read_model(path, &options(0)).unwrap_err().to_string();
+    assert!(error.contains("1 triangles"), "{}", error);
+}
+
+#[test]
+fn textures_with_too_many_pixels_are_rejected() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("limited.png");
+    Image::from_pixel(8, 4, Color::from([0, 0, 200]))
+        .save(&path)
+        .unwrap();
+    let path = path.to_str().unwrap();
+
+    assert!(read_texture_limited(path, None).is_ok());
+    assert_eq!(
+        read_texture_limited(path, Some(32)).unwrap().dimensions(),
+        (8, 4)
+    );
+    let error = read_texture_limited(path, Some(31))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("8x4 (32 pixels)"), "{}", error);
+}
+
+#[test]
+fn material_textures_with_too_many_pixels_are_told_from_missing_ones() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (texture, mtl) = (dir.join("limited_skin.png"), dir.join("limited.mtl"));
+    Image::from_pixel(8, 4, Color::from([0, 0, 200]))
+        .save(&texture)
+        .unwrap();
+    std::fs::write(
+        &mtl,
+        format!(
+            "newmtl skin\nmap_Kd {}\nnewmtl lost\nmap_Kd missing.png\n",
+            texture.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+    let materials = read_materials(mtl.to_str().unwrap()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (4, 4));
+    renderer.set_max_texture_pixels(Some(32));
+    let warnings = renderer.set_materials(materials.clone());
+    assert!(
+        matches!(warnings.as_slice(), [MaterialWarning::Unreadable { material, .. }] if material == "lost"),
+        "{:?}",
+        warnings
+    );
+
+    renderer.set_max_texture_pixels(Some(31));
+    let mut warnings = renderer.set_materials(materials);
+    warnings.sort_by_key(|warning| warning.to_string());
+    let size = TextureTooLarge {
+        width: 8,
+        height: 4,
+        max_pixels: 31,
+    };
+    assert!(
+        matches!(
+            warnings.as_slice(),
+            [MaterialWarning::Unreadable { .. }, MaterialWarning::TooLarge { material, size: s, .. }]
+                if material == "skin" && *s == size
+        ),
+        "{:?}",
+        warnings
+    );
+    assert!(
+        warnings[1].to_string().contains("too large"),
+        "{}",
+        warnings[1]
+    );
+}
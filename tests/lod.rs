@@ -0,0 +1,102 @@
+use glam::{vec3, Mat4};
+use tinyrenderer::{
+    read_model, Camera, Image, Instance, LoadOptions, Lod, LodStats, Renderer, Scene,
+};
+
+const SIZE: usize = 128;
+
+// a head in front of the camera and, unless it's alone, four far away ones in the corners
+fn field(alone: bool) -> Scene {
+    let mut scene = Scene::single(
+        read_model("obj/african_head.obj", &LoadOptions::default()).unwrap(),
+        None,
+    );
+    if !alone {
+        for &(x, y) in &[(-8.0, -8.0), (8.0, -8.0), (-8.0, 8.0), (8.0, 8.0)] {
+            scene.instances.push(Instance {
+                mesh: 0,
+                transform: Mat4::from_translation(vec3(x, y, -20.0)),
+            });
+        }
+    }
+    scene
+}
+
+fn render(scene: &Scene, lod: Option<Lod>) -> (Image, LodStats) {
+    let camera = Camera::perspective(
+        vec3(0.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        1.0,
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    if let Some(lod) = lod {
+        renderer.set_lod(lod);
+    }
+    renderer.render_scene(scene);
+    (renderer.image().clone(), renderer.lod_stats())
+}
+
+#[test]
+fn distant_instances_are_skipped() {
+    let (near, _) = render(&field(true), None);
+    let (all, stats) = render(&field(false), None);
+    assert_eq!(stats, LodStats::default());
+    // the far heads are drawn
+    assert!(all != near);
+
+    let lod = Lod {
+        max_distance: Some(10.0),
+        ..Lod::default()
+    };
+    let (culled, stats) = render(&field(false), Some(lod));
+    assert_eq!(stats.instances, 4);
+    assert_eq!(stats.triangles, 0);
+    // and the near one is as it was
+    assert!(culled == near);
+}
+
+#[test]
+fn tiny_triangles_of_distant_instances_are_skipped() {
+    let lod = Lod {
+        min_triangle_area: 0.5,
+        ..Lod::default()
+    };
+    let (near, near_stats) = render(&field(true), Some(lod));
+    let (all, stats) = render(&field(false), Some(lod));
+    // most triangles of the far heads are tiny
+    let triangles = read_model("obj/african_head.obj", &LoadOptions::default())
+        .unwrap()
+        .objects[0]
+        .geometry[0]
+        .shapes
+        .len();
+    let far_skipped = stats.triangles - near_stats.triangles;
+    assert!(
+        far_skipped > 4 * triangles / 2,
+        "{} of {}",
+        far_skipped,
+        4 * triangles
+    );
+    assert!(
+        near_stats.triangles < triangles / 4,
+        "{}",
+        near_stats.triangles
+    );
+    assert_eq!(stats.instances, 0);
+
+    // the near head barely changes (just a few holes), with or without the far ones
+    let (plain, _) = render(&field(true), None);
+    let differing = near
+        .pixels()
+        .zip(plain.pixels())
+        .filter(|(a, b)| a != b)
+        .count();
+    assert!(differing < SIZE * SIZE / 100, "{}", differing);
+    let center =
+        (SIZE / 4..SIZE * 3 / 4).flat_map(|y| (SIZE / 4..SIZE * 3 / 4).map(move |x| (x, y)));
+    for (x, y) in center {
+        let (x, y) = (x as u32, y as u32);
+        assert_eq!(all.get_pixel(x, y), near.get_pixel(x, y), "({}, {})", x, y);
+    }
+}
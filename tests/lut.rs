@@ -0,0 +1,74 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{read_model, read_texture, Camera, LoadOptions, PixelFormat, Renderer, Scene};
+
+// .cube LUT of size entries per channel mapping colors with f
+fn cube(size: usize, f: impl Fn(Vec3) -> Vec3) -> String {
+    let mut lut = format!("TITLE \"test\"\nLUT_3D_SIZE {}\n", size);
+    let step = (size - 1) as f32;
+    for b in 0..size {
+        for g in 0..size {
+            for r in 0..size {
+                let c = f(vec3(r as f32, g as f32, b as f32) / step);
+                lut += &format!("{} {} {}\n", c.x(), c.y(), c.z());
+            }
+        }
+    }
+    lut
+}
+
+// final pixels of the textured head graded with LUT
+fn render(lut: Option<String>) -> Vec<u8> {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (64, 64));
+    if let Some(lut) = lut {
+        let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("test.cube");
+        std::fs::write(&path, lut).unwrap();
+        renderer.set_color_lut(path.to_str().unwrap()).unwrap();
+    }
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    renderer.export_as(PixelFormat::Rgb)
+}
+
+#[test]
+fn identity_lut_changes_nothing() {
+    let plain = render(None);
+    for size in [2, 17] {
+        assert!(render(Some(cube(size, |c| c))) == plain, "{}", size);
+    }
+}
+
+#[test]
+fn lut_grades_colors() {
+    let plain = render(None);
+    assert!(plain.iter().any(|&c| c != 0));
+
+    // swapping red and blue
+    let swapped = render(Some(cube(2, |c| vec3(c.z(), c.y(), c.x()))));
+    for (plain, swapped) in plain.chunks(3).zip(swapped.chunks(3)) {
+        assert_eq!(swapped, [plain[2], plain[1], plain[0]]);
+    }
+
+    // and inverting them, which the LUT's 8 entries interpolate exactly as well
+    let inverted = render(Some(cube(2, |c| Vec3::one() - c)));
+    for (&plain, &inverted) in plain.iter().zip(&inverted) {
+        assert_eq!(inverted, 255 - plain);
+    }
+}
+
+#[test]
+fn malformed_lut_is_an_error() {
+    let camera = Camera::new(Vec3::zero(), vec3(0.0, 0.0, 1.0), vec3(0.0, 1.0, 0.0));
+    let mut renderer = Renderer::new(camera, (8, 8));
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("short.cube");
+    // 8 entries are expected, but only 7 are given
+    let lut = cube(2, |c| c);
+    let lut: Vec<&str> = lut.lines().take(9).collect();
+    std::fs::write(&path, lut.join("\n")).unwrap();
+    assert!(renderer.set_color_lut(path.to_str().unwrap()).is_err());
+}
@@ -0,0 +1,301 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_materials, read_model, read_model_materials, Camera, Color, Image, LoadOptions, Material,
+    MaterialOverride, MaterialWarning, Renderer, Scene,
+};
+
+const SIZE: u32 = 64;
+
+// square facing the camera, normals bent on one side
+const QUAD: &str = "
+o quad
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+vn 0 0 1
+vn 1 0 1
+usemtl red
+f 1//1 2//2 3//2
+f 1//1 3//2 4//1
+";
+
+const MTL: &str = "
+newmtl red
+Kd 1 0 0
+";
+
+fn render(with_normals: bool, material_override: MaterialOverride) -> Image {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (obj, mtl) = (dir.join("red_quad.obj"), dir.join("red.mtl"));
+    let quad = if with_normals {
+        QUAD.to_string()
+    } else {
+        QUAD.replace("//1", "").replace("//2", "")
+    };
+    std::fs::write(&obj, quad).unwrap();
+    std::fs::write(&mtl, MTL).unwrap();
+    let model = read_model(obj.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+    renderer.set_material_override(material_override);
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+#[test]
+fn untextured_material_is_lit_with_its_color() {
+    let image = render(true, MaterialOverride::default());
+    let covered: Vec<_> = image.pixels().filter(|p| p.0 != [0, 0, 0]).collect();
+    assert!(covered.len() > (SIZE * SIZE / 2) as usize);
+    for p in &covered {
+        assert_eq!((p[1], p[2]), (0, 0), "{:?} isn't red", p);
+    }
+
+    // shaded per pixel from the interpolated normals
+    let red = |x| image.get_pixel(x, SIZE / 2)[0];
+    assert_ne!(red(2), red(SIZE - 3));
+
+    // and evenly without them
+    let image = render(false, MaterialOverride::default());
+    let red = |x| image.get_pixel(x, SIZE / 2)[0];
+    assert_eq!(red(2), red(SIZE - 3));
+    assert!(red(2) > 0);
+}
+
+#[test]
+fn material_override_wins_over_mtl() {
+    let red = render(true, MaterialOverride::default());
+    let green = render(
+        true,
+        MaterialOverride {
+            color: Some(vec3(0.0, 1.0, 0.0)),
+            ..MaterialOverride::default()
+        },
+    );
+    // the same shading, only green instead of red
+    for (red, green) in red.pixels().zip(green.pixels()) {
+        assert_eq!(green.0, [0, red[0], 0]);
+    }
+
+    // properties that aren't overridden stay those of the MTL
+    let shiny = render(
+        true,
+        MaterialOverride {
+            specular: Some(vec3(1.0, 1.0, 1.0)),
+            shininess: Some(4.0),
+            ..MaterialOverride::default()
+        },
+    );
+    assert!(shiny.pixels().all(|p| p[0] >= p[1] && p[1] == p[2]));
+    assert!(shiny.pixels().any(|p| p[1] > 0));
+    assert!(shiny.pixels().zip(red.pixels()).all(|(s, r)| s[0] >= r[0]));
+}
+
+// the same square with UVs and a textured material
+const TEXTURED_QUAD: &str = "
+o quad
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+usemtl textured
+f 1/1 2/2 3/3
+f 1/1 3/3 4/4
+";
+
+fn render_textured(map: &str, fallback: Option<Color>) -> Image {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (obj, mtl) = (dir.join("textured_quad.obj"), dir.join("textured.mtl"));
+    std::fs::write(&obj, TEXTURED_QUAD).unwrap();
+    std::fs::write(&mtl, format!("newmtl textured\nmap_Kd {}\n", map)).unwrap();
+    let model = read_model(obj.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_light_direction(vec3(0.0, 0.0, 1.0));
+    if let Some(color) = fallback {
+        renderer.set_missing_texture_fallback(Image::from_pixel(1, 1, color));
+    }
+    renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+#[test]
+fn material_texture_is_read_relative_to_mtl() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    Image::from_pixel(4, 4, Color::from([0, 0, 200]))
+        .save(dir.join("blue.png"))
+        .unwrap();
+
+    let image = render_textured("blue.png", None);
+    let center = image.get_pixel(SIZE / 2, SIZE / 2);
+    assert!(
+        center[2] > 0 && (center[0], center[1]) == (0, 0),
+        "{:?}",
+        center
+    );
+}
+
+#[test]
+fn missing_material_texture_is_replaced() {
+    let green = Color::from([0, 255, 0]);
+    let image = render_textured("missing.png", Some(green));
+    let covered: Vec<_> = image.pixels().filter(|p| p.0 != [0, 0, 0]).collect();
+    assert!(covered.len() > (SIZE * SIZE / 2) as usize);
+    for p in covered {
+        assert_eq!((p[0], p[2]), (0, 0), "{:?} isn't green", p);
+    }
+
+    // by a magenta checkerboard by default (the square covers the whole image)
+    let image = render_textured("missing.png", None);
+    let magenta = image
+        .pixels()
+        .filter(|p| p[0] > 0 && p[0] == p[2] && p[1] == 0)
+        .count();
+    let black = image.pixels().filter(|p| p.0 == [0, 0, 0]).count();
+    assert!(magenta > 0 && black > 0);
+}
+
+#[test]
+fn unreadable_material_textures_are_reported() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let mtl = dir.join("unreadable.mtl");
+    std::fs::write(
+        &mtl,
+        "newmtl skin\nmap_Kd missing.png\nnewmtl plain\nKd 1 0 0\n",
+    )
+    .unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    let warnings = renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+    assert!(
+        matches!(
+            warnings.as_slice(),
+            [MaterialWarning::Unreadable { material, path, .. }]
+                if material == "skin" && path.ends_with("missing.png")
+        ),
+        "{:?}",
+        warnings
+    );
+    let warning = warnings[0].to_string();
+    assert!(
+        warning.contains("missing.png") && warning.contains("\"skin\""),
+        "{}",
+        warning
+    );
+}
+
+#[test]
+fn back_faces_use_back_material() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (obj, mtl) = (dir.join("leaf_quad.obj"), dir.join("leaf.mtl"));
+    std::fs::write(&obj, QUAD.replace("usemtl red", "usemtl leaf")).unwrap();
+    std::fs::write(
+        &mtl,
+        "newmtl leaf\nKd 0 1 0\nback_mtl leaf back\nnewmtl leaf back\nKd 1 0 0\n",
+    )
+    .unwrap();
+    let model = read_model(obj.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let render = |z: f32, double_sided: bool| {
+        let camera = Camera::new(vec3(0.0, 0.0, z), vec3(0.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0));
+        let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+        renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+        renderer.set_double_sided(double_sided);
+        renderer.render_scene(&Scene::single(model.clone(), None));
+        *renderer.image().get_pixel(SIZE / 2, SIZE / 2)
+    };
+
+    let front = render(-1.0, true);
+    assert!(
+        front[1] > 0 && (front[0], front[2]) == (0, 0),
+        "{:?}",
+        front
+    );
+    let back = render(1.0, true);
+    assert!(back[0] > 0 && (back[1], back[2]) == (0, 0), "{:?}", back);
+    // culled otherwise
+    assert_eq!(render(1.0, false).0, [0, 0, 0]);
+    assert_eq!(render(-1.0, false), front);
+}
+
+#[test]
+fn model_materials_are_found_from_any_directory() {
+    // asset pack unpacked somewhere else than the working directory
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("asset_pack");
+    std::fs::create_dir_all(dir.join("textures")).unwrap();
+    Image::from_pixel(4, 4, Color::from([0, 0, 200]))
+        .save(dir.join("textures/skin.png"))
+        .unwrap();
+    // exported on Windows
+    std::fs::write(
+        dir.join("quad.mtl"),
+        "newmtl textured\nmap_Kd textures\\skin.png\n",
+    )
+    .unwrap();
+    let obj = dir.join("quad.obj");
+    std::fs::write(&obj, format!("mtllib quad.mtl\n{}", TEXTURED_QUAD)).unwrap();
+
+    let path = obj.to_str().unwrap();
+    let model = read_model(path, &LoadOptions::default()).unwrap();
+    let materials = read_model_materials(path, &model).unwrap();
+    let texture = materials["textured"].texture.as_ref().unwrap();
+    assert!(std::path::Path::new(texture).is_file(), "{}", texture);
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    assert!(renderer.set_materials(materials).is_empty());
+    renderer.render_scene(&Scene::single(model, None));
+    let center = renderer.image().get_pixel(SIZE / 2, SIZE / 2);
+    assert!(
+        center[2] > 0 && (center[0], center[1]) == (0, 0),
+        "{:?}",
+        center
+    );
+
+    // models without mtllib have no materials
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    assert!(read_model_materials("obj/african_head.obj", &model)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn texture_scale_and_offset_are_read_from_mtl() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("tiled.mtl");
+    std::fs::write(
+        &path,
+        "newmtl tiled\nmap_Kd -s 4 2 1 -o 0.5 skin.png\nnewmtl plain\nmap_Kd -bm 2 skin.png\n",
+    )
+    .unwrap();
+    let materials = read_materials(path.to_str().unwrap()).unwrap();
+    let tiled = &materials["tiled"];
+    assert_eq!((tiled.uv_scale.x(), tiled.uv_scale.y()), (4.0, 2.0));
+    assert_eq!((tiled.uv_offset.x(), tiled.uv_offset.y()), (0.5, 0.0));
+    assert!(tiled.texture.as_ref().unwrap().ends_with("skin.png"));
+    assert_eq!(materials["plain"].uv_scale, Material::default().uv_scale);
+}
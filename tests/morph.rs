@@ -0,0 +1,90 @@
+use glam::{vec3, Mat4};
+use tinyrenderer::{morph, read_model, read_texture, Camera, Image, LoadOptions, Renderer, Scene};
+use wavefront_obj::obj::ObjSet;
+
+fn head() -> ObjSet {
+    read_model("obj/african_head.obj", &LoadOptions::default()).unwrap()
+}
+
+// the head squashed and moved, so vertices and normals differ
+fn target() -> ObjSet {
+    let transform =
+        Mat4::from_translation(vec3(0.1, -0.2, 0.0)) * Mat4::from_scale(vec3(0.6, 0.9, 0.8));
+    let mut model = head();
+    for object in &mut model.objects {
+        for v in &mut object.vertices {
+            let p = transform.transform_point3(vec3(v.x as f32, v.y as f32, v.z as f32));
+            *v = wavefront_obj::obj::Vertex {
+                x: p.x() as f64,
+                y: p.y() as f64,
+                z: p.z() as f64,
+            };
+        }
+        for n in &mut object.normals {
+            n.x *= 0.5;
+            n.z = -n.z;
+        }
+    }
+    model
+}
+
+fn renderer() -> Renderer {
+    let camera = Camera::new(
+        vec3(0.5, 0.0, -0.5),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    Renderer::new(camera, (128, 128))
+}
+
+fn render(model: &ObjSet) -> Image {
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let mut renderer = renderer();
+    renderer.render_scene(&Scene::single(model.clone(), Some(texture)));
+    renderer.image().clone()
+}
+
+fn render_morph(a: &ObjSet, b: &ObjSet, t: f32) -> Image {
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let mut renderer = renderer();
+    renderer.render_morph(a, b, t, Some(&texture)).unwrap();
+    renderer.image().clone()
+}
+
+#[test]
+fn morph_interpolates_targets() {
+    let (a, b) = (head(), target());
+    assert_eq!(morph(&a, &b, 0.0).unwrap(), a);
+    assert_eq!(morph(&a, &b, 1.0).unwrap(), b);
+
+    let half = morph(&a, &b, 0.5).unwrap();
+    let (a, b, half) = (&a.objects[0], &b.objects[0], &half.objects[0]);
+    for ((va, vb), v) in a.vertices.iter().zip(&b.vertices).zip(&half.vertices) {
+        assert!((v.x - (va.x + vb.x) / 2.0).abs() < 1e-9);
+        assert!((v.y - (va.y + vb.y) / 2.0).abs() < 1e-9);
+        assert!((v.z - (va.z + vb.z) / 2.0).abs() < 1e-9);
+    }
+    for ((na, nb), n) in a.normals.iter().zip(&b.normals).zip(&half.normals) {
+        assert!((n.x - (na.x + nb.x) / 2.0).abs() < 1e-9);
+        assert!((n.z - (na.z + nb.z) / 2.0).abs() < 1e-9);
+    }
+    assert_eq!(half.tex_vertices, a.tex_vertices);
+    assert_eq!(half.geometry, a.geometry);
+}
+
+#[test]
+fn render_morph_matches_targets() {
+    let (a, b) = (head(), target());
+    assert!(render_morph(&a, &b, 0.0) == render(&a));
+    assert!(render_morph(&a, &b, 1.0) == render(&b));
+    assert!(render_morph(&a, &b, 0.5) == render(&morph(&a, &b, 0.5).unwrap()));
+    assert!(render_morph(&a, &b, 0.5) != render(&a));
+}
+
+#[test]
+fn morph_requires_same_topology() {
+    let a = head();
+    let mut b = head();
+    b.objects[0].vertices.pop();
+    assert!(morph(&a, &b, 0.5).is_err());
+}
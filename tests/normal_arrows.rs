@@ -0,0 +1,108 @@
+use glam::vec3;
+use std::f32::consts::PI;
+use tinyrenderer::{read_model, Camera, Color, LoadOptions, Renderer, Scene};
+
+const SIZE: usize = 64;
+const GREEN: [u8; 3] = [0, 255, 0];
+const RADIUS: f32 = 0.5;
+const LENGTH: f32 = 0.3;
+
+// UV sphere with normals pointing out of it (or into it when inverted)
+fn sphere(inverted: bool) -> String {
+    let (stacks, slices) = (8, 16);
+    let mut obj = String::new();
+    for i in 0..=stacks {
+        let theta = PI * i as f32 / stacks as f32;
+        for j in 0..slices {
+            let phi = 2.0 * PI * j as f32 / slices as f32;
+            let n = vec3(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            );
+            let p = n * RADIUS;
+            let n = if inverted { -n } else { n };
+            obj += &format!(
+                "v {} {} {}\nvn {} {} {}\n",
+                p.x(),
+                p.y(),
+                p.z(),
+                n.x(),
+                n.y(),
+                n.z()
+            );
+        }
+    }
+    let index = |i: usize, j: usize| i * slices + j % slices + 1;
+    for i in 0..stacks {
+        for j in 0..slices {
+            let (a, b) = (index(i, j), index(i, j + 1));
+            let (c, d) = (index(i + 1, j + 1), index(i + 1, j));
+            obj += &format!("f {0}//{0} {1}//{1} {2}//{2}\n", a, b, c);
+            obj += &format!("f {0}//{0} {1}//{1} {2}//{2}\n", a, c, d);
+        }
+    }
+    obj
+}
+
+// green pixels of the arrows over the rendered sphere, by distance from the center in pixels
+fn arrows(inverted: bool) -> Vec<(f32, (usize, usize))> {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("arrow_sphere.obj");
+    std::fs::write(&path, sphere(inverted)).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.set_double_sided(true);
+    renderer.render_scene(&Scene::single(model.clone(), None));
+    renderer.draw_normals(&model, LENGTH, Color::from(GREEN));
+
+    let center = (SIZE - 1) as f32 / 2.0;
+    let image = renderer.image();
+    image
+        .enumerate_pixels()
+        .filter(|(_, _, p)| p.0 == GREEN)
+        .map(|(x, y, _)| {
+            let (dx, dy) = (x as f32 - center, y as f32 - center);
+            ((dx * dx + dy * dy).sqrt(), (x as usize, y as usize))
+        })
+        .collect()
+}
+
+#[test]
+fn arrows_point_away_from_the_sphere() {
+    // in pixels
+    let scale = (SIZE - 1) as f32 / 2.0;
+    let (sphere, tips) = (RADIUS * scale, (RADIUS + LENGTH) * scale);
+
+    let outward = arrows(false);
+    // sticking out of the silhouette up to their length, but not further
+    assert!(outward.iter().any(|&(r, _)| r > sphere + 2.0));
+    assert!(
+        outward.iter().all(|&(r, _)| r < tips + 1.0),
+        "{:?}",
+        outward
+    );
+    // radially: the ones of the silhouette's vertices at the right and top stay on the axes
+    let center = SIZE / 2;
+    for r in (sphere.ceil() as usize + 1)..(tips.floor() as usize) {
+        let on = |x: usize, y: usize| {
+            outward
+                .iter()
+                .any(|&(_, p)| p == (x, y) || p == (x, y - 1) || p == (x - 1, y))
+        };
+        assert!(on(center + r, center), "{}", r);
+        assert!(on(center, center + r), "{}", r);
+    }
+
+    // inverted normals point into the sphere, where they are hidden
+    let inward = arrows(true);
+    assert!(
+        inward.iter().all(|&(r, _)| r < sphere + 1.0),
+        "{:?}",
+        inward
+    );
+}
@@ -0,0 +1,99 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_model, Camera, Color, LoadOptions, NormalMapSpace, Renderer, Scene, Texture,
+};
+
+const SIZE: usize = 32;
+
+// square facing the camera with u along x (or along y when rotated)
+fn square(rotated: bool) -> String {
+    let uv = if rotated {
+        ["0 1", "0 0", "1 0", "1 1"]
+    } else {
+        ["0 0", "1 0", "1 1", "0 1"]
+    };
+    format!(
+        "
+v -0.5 -0.5 0
+v 0.5 -0.5 0
+v 0.5 0.5 0
+v -0.5 0.5 0
+vt {}
+vt {}
+vt {}
+vt {}
+vn 0 0 1
+f 1/1/1 2/2/1 3/3/1
+f 1/1/1 3/3/1 4/4/1
+",
+        uv[0], uv[1], uv[2], uv[3]
+    )
+}
+
+// brightness of the center of the white square lit from the right, optionally normal mapped
+// with a single color
+fn render(rotated: bool, map: Option<(Color, NormalMapSpace)>) -> u8 {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("normal_mapped_square.obj");
+    std::fs::write(&path, square(rotated)).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.set_light_direction(vec3(1.0, 0.0, 1.0));
+    if let Some((color, space)) = map {
+        renderer.set_normal_map(Texture::from_pixel(4, 4, color), space);
+    }
+    let texture = Texture::from_pixel(4, 4, Color::from([0xff; 3]));
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    let center = (SIZE / 2) as u32;
+    renderer.image().get_pixel(center, center)[0]
+}
+
+// normals of the map tilted by 45° from the surface normal
+const FLAT: [u8; 3] = [128, 128, 255];
+const RIGHT: [u8; 3] = [218, 128, 218];
+const LEFT: [u8; 3] = [38, 128, 218];
+const UP: [u8; 3] = [128, 218, 218];
+
+fn close(a: u8, b: u8) -> bool {
+    (a as i32 - b as i32).abs() <= 1
+}
+
+#[test]
+fn flat_tangent_space_map_keeps_vertex_normals() {
+    for rotated in [false, true] {
+        let plain = render(rotated, None);
+        let mapped = render(rotated, Some((Color::from(FLAT), NormalMapSpace::Tangent)));
+        assert!(close(mapped, plain), "{} {}", mapped, plain);
+    }
+}
+
+#[test]
+fn object_space_map_ignores_uv_mapping() {
+    let plain = render(false, None);
+    for rotated in [false, true] {
+        let at = |color| render(rotated, Some((Color::from(color), NormalMapSpace::Object)));
+        // facing the light is brighter, facing away darker and sideways in between
+        assert!(at(RIGHT) > plain && at(LEFT) < plain);
+        assert!(at(UP) > at(LEFT) && at(UP) < plain);
+        assert!(close(at(FLAT), plain));
+    }
+}
+
+#[test]
+fn tangent_space_map_follows_uv_mapping() {
+    let object = |color| render(false, Some((Color::from(color), NormalMapSpace::Object)));
+    let tangent =
+        |rotated, color| render(rotated, Some((Color::from(color), NormalMapSpace::Tangent)));
+    // u along x and v along y are the object's axes
+    for color in [RIGHT, LEFT, UP] {
+        assert!(close(tangent(false, color), object(color)));
+    }
+    // with u along y and v against x the same colors tilt normals elsewhere
+    assert!(close(tangent(true, RIGHT), object(UP)));
+    assert!(close(tangent(true, UP), object(LEFT)));
+    assert!(!close(tangent(true, RIGHT), object(RIGHT)));
+}
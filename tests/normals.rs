@@ -0,0 +1,110 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{
+    read_model, Camera, Color, LoadOptions, NormalSpace, NormalWeighting, Renderer, Scene,
+    ShadingMode,
+};
+use wavefront_obj::obj::Primitive;
+
+// a huge face (facing +z) and a fan of segments tiny ones (facing +x) meeting at a right angle
+// in vertex 1, the fan covers a right angle of vertex 1 as well
+fn fold(segments: usize) -> String {
+    let mut obj = "o fold\nv 0 0 0\nv 10 0 0\nv 0 10 0\n".to_owned();
+    for k in 0..=segments {
+        let angle = std::f32::consts::FRAC_PI_2 * k as f32 / segments as f32;
+        obj += &format!("v 0 {} {}\n", 0.1 * angle.cos(), -0.1 * angle.sin());
+    }
+    obj += "s 1\nf 1 2 3\n";
+    for k in 0..segments {
+        obj += &format!("f 1 {} {}\n", k + 5, k + 4);
+    }
+    obj
+}
+
+// computed normal of vertex 1
+fn corner_normal(segments: usize, weighting: NormalWeighting) -> Vec3 {
+    let path =
+        std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("fold_{}.obj", segments));
+    std::fs::write(&path, fold(segments)).unwrap();
+    let options = LoadOptions {
+        normal_weighting: weighting,
+        ..LoadOptions::default()
+    };
+    let model = read_model(path.to_str().unwrap(), &options).unwrap();
+
+    let object = &model.objects[0];
+    let normals: Vec<Vec3> = object.geometry[0]
+        .shapes
+        .iter()
+        .map(|shape| match shape.primitive {
+            Primitive::Triangle(a, b, c) => {
+                let corner = [a, b, c].iter().find(|corner| corner.0 == 0).unwrap().2;
+                let n = object.normals[corner.unwrap()];
+                vec3(n.x as f32, n.y as f32, n.z as f32)
+            }
+            _ => unreachable!(),
+        })
+        .collect();
+    // smooth, the same for every face
+    assert!(normals.iter().all(|&n| (n - normals[0]).length() < 1e-6));
+    normals[0]
+}
+
+#[test]
+fn angle_weighting_ignores_face_sizes() {
+    let diagonal = vec3(1.0, 0.0, 1.0).normalize();
+    let area = corner_normal(4, NormalWeighting::Area);
+    let angle = corner_normal(4, NormalWeighting::Angle);
+    let uniform = corner_normal(4, NormalWeighting::Uniform);
+
+    // the huge face dominates by area, the many tiny ones by count
+    assert!(area.z() > 0.99, "{:?}", area);
+    assert!(uniform.x() > 0.9, "{:?}", uniform);
+    // both faces cover a right angle of the vertex
+    assert!((angle - diagonal).length() < 1e-3, "{:?}", angle);
+    assert!(angle.angle_between(area) > 0.7);
+
+    // no matter how the fan is split
+    for &segments in &[1, 2, 8] {
+        let normal = corner_normal(segments, NormalWeighting::Angle);
+        assert!((normal - angle).length() < 1e-3, "{:?}", normal);
+    }
+    assert!(corner_normal(1, NormalWeighting::Uniform).x() < uniform.x());
+}
+
+// color of the middle pixel of a square facing +z seen from lookfrom
+fn normal_color(lookfrom: Vec3, space: NormalSpace) -> Color {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("normal_square.obj");
+    let square =
+        "v -1 -1 0\nv 1 -1 0\nv 1 1 0\nv -1 1 0\nvn 0 0 1\nf 1//1 2//1 3//1\nf 1//1 3//1 4//1\n";
+    std::fs::write(&path, square).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(lookfrom, Vec3::zero(), vec3(0.0, 1.0, 0.0));
+    let mut renderer = Renderer::new(camera, (32, 32));
+    renderer.set_shading_mode(ShadingMode::Normals);
+    renderer.set_normal_space(space);
+    renderer.render_scene(&Scene::single(model, None));
+    *renderer.image().get_pixel(16, 16)
+}
+
+#[test]
+fn normals_are_drawn_in_view_or_world_space() {
+    let (front, side) = (vec3(0.0, 0.0, -3.0), vec3(2.0, 0.0, -2.0));
+    let near = |color: Color, expected: [u8; 3]| {
+        (0..3).all(|c| (color[c] as i32 - expected[c] as i32).abs() <= 1)
+    };
+
+    // facing the viewer seen from the front, so the same in both spaces
+    for space in [NormalSpace::View, NormalSpace::World] {
+        let color = normal_color(front, space);
+        assert!(near(color, [127, 127, 255]), "{:?}", color);
+    }
+
+    // the same in world space from any side
+    let world = normal_color(side, NormalSpace::World);
+    assert!(near(world, [127, 127, 255]), "{:?}", world);
+    // and turned 45 degrees away from a viewer at its side in view space
+    let view = normal_color(side, NormalSpace::View);
+    let (x, z) = (127.5 - 127.5 * 0.5f32.sqrt(), 127.5 + 127.5 * 0.5f32.sqrt());
+    assert!(near(view, [x as u8, 127, z as u8]), "{:?}", view);
+}
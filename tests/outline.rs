@@ -0,0 +1,66 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, Color, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 64;
+const RED: [u8; 3] = [255, 0, 0];
+
+fn render() -> Renderer {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let mut camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    // zoomed out, so the outline fits in the image
+    camera.zoom(0.8);
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_triangle_id_buffer(true);
+    renderer.render_scene(&Scene::single(model, None));
+    renderer
+}
+
+#[test]
+fn outline_has_the_requested_thickness() {
+    for thickness in [1, 3] {
+        let mut renderer = render();
+        let head: Vec<(i64, i64)> = (0..SIZE)
+            .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+            .filter(|&(x, y)| renderer.pick(x, y).is_some())
+            .map(|(x, y)| (x as i64, y as i64))
+            .collect();
+        let before = renderer.image().clone();
+        renderer
+            .draw_outline("head", Color::from(RED), thickness)
+            .unwrap();
+
+        // squared distance to the nearest pixel of the head
+        let distance = |x: i64, y: i64| {
+            head.iter()
+                .map(|&(hx, hy)| (hx - x).pow(2) + (hy - y).pow(2))
+                .min()
+                .unwrap()
+        };
+        let t = thickness as i64;
+        let mut outline = 0;
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let d = distance(x as i64, y as i64);
+                let pixel = renderer.image().get_pixel(x, y);
+                if d == 0 {
+                    // the head itself is left as is
+                    assert_eq!(pixel, before.get_pixel(x, y));
+                } else if d <= t * t {
+                    assert_eq!(pixel.0, RED, "({}, {}) at {}", x, y, d);
+                    outline += 1;
+                } else {
+                    assert_eq!(pixel, before.get_pixel(x, y), "({}, {}) at {}", x, y, d);
+                }
+            }
+        }
+        assert!(outline > 0);
+    }
+
+    // objects that weren't rendered can't be outlined
+    let mut renderer = render();
+    assert!(renderer.draw_outline("tail", Color::from(RED), 1).is_err());
+}
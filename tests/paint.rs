@@ -0,0 +1,94 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_model, read_texture, Camera, Image, LoadOptions, PassBlend, Renderer, Scene, ShadingMode,
+};
+
+const SIZE: usize = 128;
+
+fn scene() -> Scene {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    Scene::single(model, Some(texture))
+}
+
+fn renderer() -> Renderer {
+    let camera = Camera::new(
+        vec3(0.5, 0.0, -0.5),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    Renderer::new(camera, (SIZE, SIZE))
+}
+
+fn single_pass(scene: &Scene, shading: ShadingMode) -> Image {
+    let mut renderer = renderer();
+    renderer.set_shading_mode(shading);
+    renderer.render_scene(scene);
+    renderer.image().clone()
+}
+
+#[test]
+fn passes_accumulate() {
+    let scene = scene();
+    let lit = single_pass(&scene, ShadingMode::Lit);
+    let colors = single_pass(&scene, ShadingMode::TriangleColors);
+
+    // the same pass twice adds up
+    let mut renderer = renderer();
+    renderer.render_scene(&scene);
+    renderer.set_pass_blend(PassBlend::Add);
+    renderer.render_scene(&scene);
+    for (a, b) in renderer.image().pixels().zip(lit.pixels()) {
+        assert_eq!(a.0, b.0.map(|c| c.saturating_add(c)));
+    }
+
+    // a pass with other settings is mixed over the previous one
+    renderer.clear_all();
+    renderer.set_pass_blend(PassBlend::Depth);
+    renderer.render_scene(&scene);
+    renderer.set_pass_blend(PassBlend::Over { opacity: 0.5 });
+    renderer.set_shading_mode(ShadingMode::TriangleColors);
+    renderer.render_scene(&scene);
+    for ((a, b), c) in renderer
+        .image()
+        .pixels()
+        .zip(lit.pixels())
+        .zip(colors.pixels())
+    {
+        for i in 0..3 {
+            let expected = (b[i] as f32 + c[i] as f32) / 2.0;
+            assert!(
+                (a[i] as f32 - expected).abs() <= 1.0,
+                "{:?} {:?} {:?}",
+                a,
+                b,
+                c
+            );
+        }
+    }
+}
+
+#[test]
+fn paints_over_canvas() {
+    let scene = scene();
+    let lit = single_pass(&scene, ShadingMode::Lit);
+
+    let gray = image::Rgb([100, 100, 100]);
+    let mut renderer = renderer();
+    renderer
+        .set_canvas(&Image::from_pixel(SIZE as u32, SIZE as u32, gray))
+        .unwrap();
+    renderer.render_scene(&scene);
+
+    let mut covered = 0;
+    for (a, b) in renderer.image().pixels().zip(lit.pixels()) {
+        if b.0 == [0, 0, 0] {
+            assert_eq!(*a, gray);
+        } else {
+            assert_eq!(a, b);
+            covered += 1;
+        }
+    }
+    assert!(covered > 0);
+    assert!(renderer.set_canvas(&Image::new(1, 1)).is_err());
+}
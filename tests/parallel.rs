@@ -0,0 +1,53 @@
+use tinyrenderer::{read_model, read_texture, LoadOptions, TextureExt};
+
+// Checksums of what's computed at load time are the same with and without the rayon feature,
+// i.e. the parallel results are exactly the serial ones.
+
+// FNV-1a of bytes
+fn checksum(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[test]
+fn computed_normals_match_serial_ones() {
+    // the head without its normals
+    let source = std::fs::read_to_string("obj/african_head.obj").unwrap();
+    let mut obj = String::new();
+    for line in source.lines().filter(|line| !line.starts_with("vn ")) {
+        match line.strip_prefix("f ") {
+            Some(corners) => {
+                let corners: Vec<&str> = corners
+                    .split_whitespace()
+                    .map(|corner| corner.rsplit_once('/').unwrap().0)
+                    .collect();
+                obj += &format!("f {}\n", corners.join(" "));
+            }
+            None => obj += &format!("{}\n", line),
+        }
+    }
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("head_without_normals.obj");
+    std::fs::write(&path, obj).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let normals = &model.objects[0].normals;
+    let bytes = normals
+        .iter()
+        .flat_map(|n| [n.x, n.y, n.z])
+        .flat_map(f64::to_le_bytes);
+    assert_eq!(normals.len(), 1258);
+    assert_eq!(checksum(bytes), 0x2880_ce1c_0dce_39a7);
+}
+
+#[test]
+fn mip_chain_matches_serial_one() {
+    let chain = read_texture("obj/african_head_diffuse.png")
+        .unwrap()
+        .mip_chain();
+    let bytes = chain
+        .iter()
+        .flat_map(|level| level.as_raw().iter().copied());
+    assert_eq!(chain.len(), 11);
+    assert_eq!(checksum(bytes), 0xb75d_792d_2c3d_e506);
+}
@@ -0,0 +1,134 @@
+use glam::vec3;
+use tinyrenderer::{read_materials, read_model, Camera, Image, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 32;
+
+// a red quad going from behind a blue one on the left to in front of it on the right, both
+// half transparent
+const VERTICES: &str = "
+v -1 -1 -0.5
+v 1 -1 0.5
+v 1 1 0.5
+v -1 1 -0.5
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+";
+const RED: &str = "usemtl red\nf 1 2 3\nf 1 3 4\n";
+const BLUE: &str = "usemtl blue\nf 5 6 7\nf 5 7 8\n";
+const MTL: &str = "
+newmtl red
+Kd 1 0 0
+d 0.5
+newmtl blue
+Kd 0 0 1
+d 0.5
+";
+
+fn render(faces: [&str; 2], layers: usize) -> Image {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (obj, mtl) = (dir.join("peeled.obj"), dir.join("peeled.mtl"));
+    std::fs::write(&obj, format!("{}{}{}", VERTICES, faces[0], faces[1])).unwrap();
+    std::fs::write(&mtl, MTL).unwrap();
+    let model = read_model(obj.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+    renderer.render_depth_peeled(&Scene::single(model, None), layers);
+    renderer.image().clone()
+}
+
+#[test]
+fn intersecting_quads_are_peeled_per_pixel() {
+    let image = render([RED, BLUE], 2);
+    assert!(image == render([BLUE, RED], 2));
+
+    // blue is in front on the left, red on the right
+    let [red, _, blue] = image.get_pixel(4, SIZE / 2).0;
+    assert!(blue > red && red > 0, "{:?}", (red, blue));
+    let [red, _, blue] = image.get_pixel(SIZE - 5, SIZE / 2).0;
+    assert!(red > blue && blue > 0, "{:?}", (red, blue));
+
+    // a single layer is just the front one
+    let image = render([RED, BLUE], 1);
+    let [red, _, blue] = image.get_pixel(4, SIZE / 2).0;
+    assert!(blue > 0 && red == 0, "{:?}", (red, blue));
+    let [red, _, blue] = image.get_pixel(SIZE - 5, SIZE / 2).0;
+    assert!(red > 0 && blue == 0, "{:?}", (red, blue));
+}
+
+// three parallel quads, red in front of green in front of blue
+const STACKED_VERTICES: &str = "
+v -1 -1 0.5
+v 1 -1 0.5
+v 1 1 0.5
+v -1 1 0.5
+v -1 -1 0.25
+v 1 -1 0.25
+v 1 1 0.25
+v -1 1 0.25
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+";
+const STACKED: [&str; 3] = [
+    "usemtl red\nf 1 2 3\nf 1 3 4\n",
+    "usemtl green\nf 5 6 7\nf 5 7 8\n",
+    "usemtl blue\nf 9 10 11\nf 9 11 12\n",
+];
+
+fn render_stacked(order: [usize; 3], alpha: f32, layers: usize) -> [u8; 3] {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (obj, mtl) = (dir.join("stacked.obj"), dir.join("stacked.mtl"));
+    let faces: String = order.iter().map(|&i| STACKED[i]).collect();
+    std::fs::write(&obj, format!("{}{}", STACKED_VERTICES, faces)).unwrap();
+    let materials = format!(
+        "newmtl red\nKd 1 0 0\nd {0}\nnewmtl green\nKd 0 1 0\nd {0}\nnewmtl blue\nKd 0 0 1\nd {0}\n",
+        alpha
+    );
+    std::fs::write(&mtl, materials).unwrap();
+    let model = read_model(obj.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_light_direction(vec3(0.0, 0.0, 1.0));
+    renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+    renderer.render_depth_peeled(&Scene::single(model, None), layers);
+    renderer.image().get_pixel(SIZE / 2, SIZE / 2).0
+}
+
+#[test]
+fn stacked_quads_composite_front_to_back() {
+    let opaque = render_stacked([0, 1, 2], 1.0, 3);
+    assert_eq!(opaque, [255, 0, 0]);
+
+    // every layer lets half of the light of the ones behind it through
+    let pixel = render_stacked([0, 1, 2], 0.5, 3);
+    let expected = [255.0 * 0.5, 255.0 * 0.25, 255.0 * 0.125];
+    for (&c, e) in pixel.iter().zip(expected) {
+        assert!((c as f32 - e).abs() <= 1.0, "{:?}", pixel);
+    }
+    // no matter the order of the faces
+    for order in [[0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]] {
+        assert_eq!(render_stacked(order, 0.5, 3), pixel, "{:?}", order);
+    }
+
+    // the back one is lost without enough layers
+    let [red, green, blue] = render_stacked([2, 1, 0], 0.5, 2);
+    assert!(
+        red > green && green > 0 && blue == 0,
+        "{:?}",
+        (red, green, blue)
+    );
+}
@@ -0,0 +1,51 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, LoadOptions, Renderer, Scene, ShadingMode};
+
+const SIZE: usize = 128;
+
+fn renderer() -> Renderer {
+    let camera = Camera::new(
+        vec3(0.5, 0.0, -0.5),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.set_shading_mode(ShadingMode::TriangleColors);
+    renderer
+}
+
+#[test]
+fn picks_visible_triangle() {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let triangles = model.objects[0].geometry[0].shapes.len();
+    let scene = Scene::single(model, None);
+
+    let mut picking = renderer();
+    picking.render_scene(&scene);
+    assert_eq!(picking.pick(SIZE as u32 / 2, SIZE as u32 / 2), None);
+
+    picking.set_triangle_id_buffer(true);
+    picking.render_scene(&scene);
+    // the nose is the nearest point of the head
+    let (mut nose, mut nearest) = ((0, 0), f32::NEG_INFINITY);
+    picking.for_each_pixel(|x, y, _, depth| {
+        if depth > nearest {
+            nose = (x, y);
+            nearest = depth;
+        }
+    });
+
+    let picked = picking.pick(nose.0, nose.1).unwrap();
+    assert_eq!(picked.object, "head");
+    assert!(picked.triangle < triangles);
+    assert_eq!(picked.depth, nearest);
+    assert_eq!(picking.pick(0, 0), None);
+
+    // and the triangle covers the pixel
+    let mut model = scene.meshes[0].model.clone();
+    let shapes = &mut model.objects[0].geometry[0].shapes;
+    *shapes = vec![shapes[picked.triangle].clone()];
+    let mut single = renderer();
+    single.render_scene(&Scene::single(model, None));
+    assert_ne!(single.image().get_pixel(nose.0, nose.1).0, [0, 0, 0]);
+}
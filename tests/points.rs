@@ -0,0 +1,155 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, Image, LoadOptions, Renderer, Scene, ShadingMode, Texture};
+
+const SIZE: u32 = 33;
+
+fn render(obj: &str, camera: Camera, setup: impl FnOnce(&mut Renderer)) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("points.obj");
+    std::fs::write(&path, obj).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    setup(&mut renderer);
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+fn orthographic() -> Camera {
+    Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    )
+}
+
+// covered pixels
+fn covered(image: &Image) -> Vec<(u32, u32)> {
+    let mut covered = Vec::new();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            if image.get_pixel(x, y).0 != [0, 0, 0] {
+                covered.push((x, y));
+            }
+        }
+    }
+    covered
+}
+
+// pixels of the size x size square around (x, y)
+fn square(x: u32, y: u32, size: u32) -> Vec<(u32, u32)> {
+    let range = |c: u32| (c - size / 2)..(c - size / 2 + size);
+    range(y)
+        .flat_map(|y| range(x).map(move |x| (x, y)))
+        .collect()
+}
+
+#[test]
+fn point_is_a_square_of_its_size() {
+    // in the middle of the image (at pixel 16, 16)
+    let obj = "v 0 0 0\np 1\n";
+    assert_eq!(
+        covered(&render(obj, orthographic(), |_| {})),
+        vec![(16, 16)]
+    );
+    let image = render(obj, orthographic(), |renderer| renderer.set_point_size(5.0));
+    assert_eq!(covered(&image), square(16, 16, 5));
+    // the material color, the default white
+    assert_eq!(image.get_pixel(16, 16).0, [255, 255, 255]);
+    // and off the middle, depth tested against a triangle (the point is in front of it first)
+    for &(z, visible) in &[(0.5, true), (-0.5, false)] {
+        let obj = format!(
+            "v 0.5 -0.5 {}\nv -1 -1 0\nv 1 -1 0\nv 0 1 0\np 1\nf 2 3 4\n",
+            z
+        );
+        let image = render(&obj, orthographic(), |renderer| {
+            renderer.set_shading_mode(ShadingMode::TriangleColors);
+            renderer.set_point_size(3.0);
+        });
+        let point = *image.get_pixel(24, 8);
+        let triangle = *image.get_pixel(24, 10);
+        for (x, y) in square(24, 8, 3) {
+            assert!(image.get_pixel(x, y) == &point);
+        }
+        assert_eq!(point != triangle, visible);
+    }
+}
+
+#[test]
+fn point_elements_can_have_several_points() {
+    // in the middle and at the corners of a square around it
+    let obj = "v 0 0 0\nv -0.5 -0.5 0\nv 0.5 0.5 0\np 1 2 3\n";
+    assert_eq!(
+        covered(&render(obj, orthographic(), |_| {})),
+        vec![(8, 8), (16, 16), (24, 24)]
+    );
+
+    // lines after them keep their numbers in parse errors
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("points_error.obj");
+    std::fs::write(&path, format!("{}f 1 x 3\n", obj)).unwrap();
+    let error = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap_err();
+    assert!(error.to_string().contains("line #5"), "{}", error);
+}
+
+#[test]
+fn points_get_smaller_farther_away() {
+    // at lookat and twice as far from the viewer
+    let obj = "v 0 0 0\nv 0 0 -2\np 1\np 2\n";
+    let camera = Camera::perspective(
+        vec3(0.0, 0.0, -2.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        std::f32::consts::FRAC_PI_2,
+    );
+    let size = |index: usize, attenuation: bool| {
+        let obj = obj.replace(if index == 0 { "p 2\n" } else { "p 1\n" }, "");
+        let image = render(&obj, camera.clone(), |renderer| {
+            renderer.set_point_size(8.0);
+            renderer.set_point_attenuation(attenuation);
+        });
+        covered(&image).len()
+    };
+    assert_eq!(size(0, false), 64);
+    assert_eq!(size(1, false), 64);
+    assert_eq!(size(0, true), 64);
+    assert_eq!(size(1, true), 16);
+}
+
+#[test]
+fn sprite_shapes_points() {
+    // a plus sign: the corners of the sprite are black
+    let mut sprite = Texture::from_pixel(3, 3, image::Rgb([255, 128, 255]));
+    for &(x, y) in &[(0, 0), (2, 0), (0, 2), (2, 2)] {
+        sprite.put_pixel(x, y, image::Rgb([0, 0, 0]));
+    }
+    let image = render("v 0 0 0\np 1\n", orthographic(), |renderer| {
+        renderer.set_point_size(6.0);
+        renderer.set_point_sprite(sprite);
+    });
+    let expected: Vec<_> = square(16, 16, 6)
+        .into_iter()
+        .filter(|&(x, y)| {
+            let corner = |c: u32| !(15..=16).contains(&c);
+            !(corner(x) && corner(y))
+        })
+        .collect();
+    assert_eq!(covered(&image), expected);
+    // colored by the sprite
+    assert_eq!(image.get_pixel(16, 16).0, [255, 128, 255]);
+}
+
+#[test]
+fn line_elements_are_drawn() {
+    // across the middle row, from pixel 8 to 24
+    let obj = "v -0.5 0 0\nv 0.5 0 0\nl 1 2\n";
+    let image = render(obj, orthographic(), |_| {});
+    let row: Vec<(u32, u32)> = (8..=24).map(|x| (x, 16)).collect();
+    assert_eq!(covered(&image), row);
+
+    // unlit, in the color of the triangle
+    let colors = render(obj, orthographic(), |renderer| {
+        renderer.set_shading_mode(ShadingMode::TriangleColors)
+    });
+    let color = colors.get_pixel(8, 16);
+    assert!(row.iter().all(|&(x, y)| colors.get_pixel(x, y) == color));
+    assert_ne!(color, image.get_pixel(8, 16));
+}
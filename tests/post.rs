@@ -0,0 +1,191 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_materials, read_model, Bloom, Camera, LoadOptions, PixelFormat, Renderer, Scene, Vignette,
+};
+
+const SIZE: u32 = 64;
+
+// gray square over the whole view, lit evenly
+const QUAD: &str = "
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+f 1 2 3
+f 1 3 4
+";
+
+fn render() -> Renderer {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("post_quad.obj");
+    std::fs::write(&path, QUAD).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_light_direction(vec3(0.0, 0.0, 1.0));
+    renderer.render_scene(&Scene::single(model, None));
+    renderer
+}
+
+// red of the exported (post-processed and graded) pixel
+fn red(pixels: &[u8], x: u32, y: u32) -> u8 {
+    pixels[((x + y * SIZE) * 3) as usize]
+}
+
+#[test]
+fn custom_vignette_darkens_corners() {
+    let mut renderer = render();
+    let plain = renderer.export_as(PixelFormat::Rgb);
+    let center = (SIZE - 1) as f32 / 2.0;
+    renderer.for_each_pixel(|x, y, color, depth| {
+        assert!(depth > f32::NEG_INFINITY);
+        let (dx, dy) = (x as f32 - center, y as f32 - center);
+        let distance = (dx * dx + dy * dy).sqrt() / (2.0 * center * center).sqrt();
+        let factor = 1.0 - 0.8 * distance * distance;
+        for c in color.0.iter_mut() {
+            *c = (*c as f32 * factor) as u8;
+        }
+    });
+    let vignetted = renderer.export_as(PixelFormat::Rgb);
+
+    let mid = SIZE / 2;
+    assert!(red(&plain, mid, mid) - red(&vignetted, mid, mid) <= 1);
+    // darker and darker towards every corner
+    for &(cx, cy) in &[(0, 0), (SIZE - 1, 0), (0, SIZE - 1), (SIZE - 1, SIZE - 1)] {
+        let along = |t: u32| {
+            let at = |from: u32, to: u32| {
+                (from as i32 + (to as i32 - from as i32) * t as i32 / 8) as u32
+            };
+            red(&vignetted, at(mid, cx), at(mid, cy))
+        };
+        for t in 1..=8 {
+            assert!(along(t) < along(t - 1), "({}, {}) at {}", cx, cy, t);
+        }
+        assert!(red(&vignetted, cx, cy) < red(&plain, cx, cy) / 3);
+    }
+    // in the image itself as well
+    assert!(renderer.image().get_pixel(0, 0)[0] < red(&plain, 0, 0) / 3);
+}
+
+#[test]
+fn vignette_darkens_corners_progressively() {
+    let plain = render().export_as(PixelFormat::Rgb);
+    let vignetted = |strength| {
+        let mut renderer = render();
+        renderer.set_vignette(Vignette {
+            strength,
+            radius: 0.3,
+            softness: 0.5,
+        });
+        renderer.export_as(PixelFormat::Rgb)
+    };
+    let vignetted_half = vignetted(0.5);
+
+    // the center is unchanged
+    let center = (SIZE - 1) as f32 / 2.0;
+    let half_diagonal = center * 2f32.sqrt();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let distance = (x as f32 - center).hypot(y as f32 - center) / half_diagonal;
+            if distance < 0.3 {
+                assert_eq!(
+                    red(&vignetted_half, x, y),
+                    red(&plain, x, y),
+                    "({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+    // and it's darker and darker along the diagonal beyond the radius
+    let diagonal: Vec<u8> = (SIZE / 2..SIZE)
+        .map(|i| red(&vignetted_half, i, i))
+        .collect();
+    assert!(diagonal.windows(2).all(|w| w[1] <= w[0]), "{:?}", diagonal);
+    let plain_corner = red(&plain, SIZE - 1, SIZE - 1) as i32;
+    let corner = red(&vignetted_half, SIZE - 1, SIZE - 1) as i32;
+    // by strength in the corners
+    assert!(
+        (corner - plain_corner / 2).abs() <= 1,
+        "{} {}",
+        corner,
+        plain_corner
+    );
+    assert!(red(&vignetted(0.9), SIZE - 1, SIZE - 1) < corner as u8);
+}
+
+// small white square in the middle and a dim one in the corner, over the background
+const SPOTS: &str = "
+v -0.1 -0.1 0
+v 0.1 -0.1 0
+v 0.1 0.1 0
+v -0.1 0.1 0
+v -1 -1 0
+v -0.6 -1 0
+v -0.6 -0.6 0
+v -1 -0.6 0
+usemtl white
+f 1 2 3
+f 1 3 4
+usemtl dim
+f 5 6 7
+f 5 7 8
+";
+
+fn render_spots(bloom: Option<Bloom>) -> Vec<u8> {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (obj, mtl) = (dir.join("bloom_spots.obj"), dir.join("bloom_spots.mtl"));
+    std::fs::write(&obj, SPOTS).unwrap();
+    std::fs::write(&mtl, "newmtl white\nKd 1 1 1\nnewmtl dim\nKd 0.2 0.2 0.2\n").unwrap();
+    let model = read_model(obj.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_light_direction(vec3(0.0, 0.0, 1.0));
+    renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+    if let Some(bloom) = bloom {
+        renderer.set_bloom(bloom);
+    }
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.export_as(PixelFormat::Rgb)
+}
+
+#[test]
+fn bright_spot_blooms() {
+    let plain = render_spots(None);
+    let bloom = Bloom {
+        threshold: 0.5,
+        radius: 8.0,
+        intensity: 1.0,
+    };
+    let bloomed = render_spots(Some(bloom));
+
+    // a soft halo fading away from the edge of the spot (at about 3 pixels from the center)
+    let (mid, edge) = (SIZE / 2, SIZE / 2 + 4);
+    assert_eq!(red(&plain, edge, mid), 0);
+    let halo: Vec<u8> = (edge..edge + 8).map(|x| red(&bloomed, x, mid)).collect();
+    assert!(halo[0] > 16, "{:?}", halo);
+    assert!(halo.windows(2).all(|w| w[1] < w[0]), "{:?}", halo);
+
+    // dark areas farther than the radius are left alone
+    for y in 0..SIZE / 4 {
+        for x in 0..SIZE / 4 {
+            assert_eq!(red(&bloomed, x, y), red(&plain, x, y), "({}, {})", x, y);
+        }
+    }
+    assert!(red(&plain, 4, 4) > 0);
+
+    // nothing is above a higher threshold
+    let high = render_spots(Some(Bloom {
+        threshold: 2.0,
+        ..bloom
+    }));
+    assert!(high == plain);
+}
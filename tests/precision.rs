@@ -0,0 +1,79 @@
+use glam::{vec2, vec3, Vec2};
+use tinyrenderer::{read_model, Camera, Image, LoadOptions, Renderer, Scene, ShadingMode};
+
+const SIZE: u32 = 64;
+const ZOOM: f32 = 1.5;
+
+// triangle abc above edge ab and two below it meeting at m, a vertex on ab (a T-junction):
+// a, m and b rounded to single precision far from the origin aren't on a line anymore
+const VERTICES: [(f64, f64); 5] = [
+    (0.0, 0.0),  // a
+    (1.0, 0.1),  // b
+    (0.5, 0.6),  // c
+    (0.5, -0.5), // d
+    (0.3, 0.03), // m
+];
+
+fn render(offset: f64, high_precision: bool) -> Image {
+    let mut obj = String::new();
+    for (x, y) in VERTICES {
+        obj += &format!("v {} {} 0\n", x + offset, y + offset);
+    }
+    obj += "f 1 2 3\nf 1 4 5\nf 5 4 2\n";
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join(format!("t_junction_{}.obj", offset));
+    std::fs::write(&path, obj).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    // looking at the triangles from a point exactly representable at the offset
+    let lookat = vec3(offset as f32 + 0.5, offset as f32, 0.0);
+    let mut camera = Camera::new(lookat - vec3(0.0, 0.0, 1.0), lookat, vec3(0.0, 1.0, 0.0));
+    camera.zoom(ZOOM);
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_shading_mode(ShadingMode::TriangleColors);
+    renderer.set_high_precision(high_precision);
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+// uncovered pixels well inside of the outline of the triangles
+fn gaps(image: &Image) -> usize {
+    let outline = [0, 3, 1, 2].map(|i| vec2(VERTICES[i].0 as f32, VERTICES[i].1 as f32));
+    let pixel = 2.0 / ((SIZE - 1) as f32 * ZOOM);
+    let inside = |p: Vec2| {
+        (0..4).all(|i| {
+            let (from, to) = (outline[i], outline[(i + 1) % 4]);
+            (to - from).perp_dot(p - from) / (to - from).length() > 2.0 * pixel
+        })
+    };
+    let mut gaps = 0;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let p = vec2(x as f32, y as f32) * pixel - Vec2::splat(1.0 / ZOOM) + vec2(0.5, 0.0);
+            if inside(p) && image.get_pixel(x, y).0 == [0, 0, 0] {
+                gaps += 1;
+            }
+        }
+    }
+    gaps
+}
+
+#[test]
+fn triangles_far_from_the_origin_meet() {
+    let near = render(0.0, false);
+    assert_eq!(gaps(&near), 0);
+    // the same at the origin either way
+    assert!(render(0.0, true) == near);
+
+    let rounded = render(1e6, false);
+    assert!(gaps(&rounded) > 20, "{}", gaps(&rounded));
+    let precise = render(1e6, true);
+    assert_eq!(gaps(&precise), 0);
+    // and it's (nearly) the image of the triangles at the origin
+    let differing = precise
+        .pixels()
+        .zip(near.pixels())
+        .filter(|(a, b)| a != b)
+        .count();
+    assert!(differing < 4, "{}", differing);
+}
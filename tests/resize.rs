@@ -0,0 +1,49 @@
+use glam::vec3;
+use tinyrenderer::{read_model, read_texture, Camera, Image, LoadOptions, Renderer, Scene};
+
+fn renderer(size: (usize, usize)) -> Renderer {
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -3.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, size);
+    renderer.set_triangle_id_buffer(true);
+    renderer
+}
+
+// corners of the covered area
+fn bounds(image: &Image) -> (u32, u32, u32, u32) {
+    let covered = image
+        .enumerate_pixels()
+        .filter(|(_, _, p)| p.0 != [0, 0, 0]);
+    covered.fold((u32::MAX, u32::MAX, 0, 0), |(x0, y0, x1, y1), (x, y, _)| {
+        (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+    })
+}
+
+#[test]
+fn resized_renderer_matches_new_one() {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let scene = Scene::single(model, Some(texture));
+
+    let mut resized = renderer((128, 128));
+    resized.render_scene(&scene);
+    let small = resized.image().clone();
+    resized.resize((256, 256));
+    resized.render_scene(&scene);
+    assert_eq!(resized.image().dimensions(), (256, 256));
+
+    let mut fresh = renderer((256, 256));
+    fresh.render_scene(&scene);
+    assert!(resized.image() == fresh.image());
+    assert_eq!(resized.pick(128, 128), fresh.pick(128, 128));
+
+    // and is framed the same way as at the smaller size
+    let (x0, y0, x1, y1) = bounds(&small);
+    let (u0, v0, u1, v1) = bounds(resized.image());
+    for (a, b) in [(x0, u0), (y0, v0), (x1, u1), (y1, v1)] {
+        assert!((a as i32 * 2 - b as i32).abs() <= 2, "{} {}", a, b);
+    }
+}
@@ -0,0 +1,61 @@
+use glam::vec3;
+use tinyrenderer::{Camera, Color, Image, Renderer, ResolveFilter};
+
+const FACTOR: u32 = 4;
+const SIZE: u32 = 64;
+
+// supersampled framebuffer with a hard diagonal edge between black and white
+fn renderer() -> Renderer {
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    let edge = Image::from_fn(SIZE, SIZE, |x, y| {
+        let white = x as f32 > y as f32 * 0.7 + 10.0;
+        Color::from([if white { 255 } else { 0 }; 3])
+    });
+    renderer.set_canvas(&edge).unwrap();
+    renderer
+}
+
+fn mean(image: &Image) -> f32 {
+    let sum: u32 = image.pixels().map(|p| p[0] as u32).sum();
+    sum as f32 / image.pixels().len() as f32
+}
+
+// largest difference between horizontally adjacent pixels
+fn steepest(image: &Image) -> u8 {
+    let mut steepest = 0;
+    for y in 0..image.height() {
+        for x in 1..image.width() {
+            let (a, b) = (image.get_pixel(x - 1, y)[0], image.get_pixel(x, y)[0]);
+            steepest = steepest.max(a.max(b) - a.min(b));
+        }
+    }
+    steepest
+}
+
+#[test]
+fn tent_filter_is_softer() {
+    let renderer = renderer();
+    let supersampled = mean(renderer.image());
+    let boxed = renderer.resolve(FACTOR, ResolveFilter::Box).unwrap();
+    let tent = renderer.resolve(FACTOR, ResolveFilter::Tent).unwrap();
+    assert_eq!(boxed.dimensions(), (SIZE / FACTOR, SIZE / FACTOR));
+    assert_eq!(tent.dimensions(), boxed.dimensions());
+
+    // both keep the overall brightness
+    assert!((mean(&boxed) - supersampled).abs() < 1.0);
+    assert!((mean(&tent) - supersampled).abs() < 2.0);
+
+    // the tent spreads the edge over more pixels
+    assert!(steepest(&tent) < steepest(&boxed));
+    let between = |image: &Image| image.pixels().filter(|p| p[0] != 0 && p[0] != 255).count();
+    assert!(between(&tent) > between(&boxed));
+
+    // a single sample per pixel is kept as it is
+    assert!(renderer.resolve(1, ResolveFilter::Tent).unwrap() == *renderer.image());
+    assert!(renderer.resolve(3, ResolveFilter::Box).is_err());
+}
@@ -0,0 +1,45 @@
+use glam::vec3;
+use tinyrenderer::{read_model, read_texture, Camera, Image, LoadOptions, Rect, Renderer, Scene};
+
+const SIZE: usize = 256;
+
+fn render(scissor: Option<Rect>) -> Image {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let camera = Camera::new(
+        vec3(0.5, 0.0, -0.5),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.set_scissor(scissor);
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    renderer.image().clone()
+}
+
+#[test]
+fn scissor_keeps_the_rest_untouched() {
+    let full = render(None);
+    let left = render(Some(Rect {
+        x: 0,
+        y: 0,
+        width: SIZE / 2,
+        height: SIZE,
+    }));
+
+    let black = [0, 0, 0];
+    let mut covered = 0;
+    for (x, y, pixel) in left.enumerate_pixels() {
+        if (x as usize) < SIZE / 2 {
+            assert_eq!(pixel, full.get_pixel(x, y), "pixel ({}, {}) differs", x, y);
+        } else {
+            assert_eq!(pixel.0, black, "pixel ({}, {}) is outside of scissor", x, y);
+            if full.get_pixel(x, y).0 != black {
+                covered += 1;
+            }
+        }
+    }
+    // the head does cover the right half without the scissor
+    assert!(covered > 1000, "only {} pixels on the right", covered);
+}
@@ -0,0 +1,132 @@
+use glam::{vec3, Mat4, Vec3};
+use tinyrenderer::{
+    read_model, Camera, Image, LoadOptions, Projection, Renderer, Scene, ShadowMode,
+};
+
+const SIZE: u32 = 128;
+
+// small square hovering in front of a large one, both facing the camera
+const QUADS: &str = "
+o ground
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+f 1 2 3
+f 1 3 4
+o occluder
+v -0.3 -0.3 0.3
+v 0.3 -0.3 0.3
+v 0.3 0.3 0.3
+v -0.3 0.3 0.3
+f 5 6 7
+f 5 7 8
+";
+
+fn render(mode: ShadowMode) -> Image {
+    render_scaled(mode, 1.0)
+}
+
+// the same scene and view with everything scaled by scale
+fn render_scaled(mode: ShadowMode, scale: f32) -> Image {
+    render_filtered(mode, scale, 1)
+}
+
+// with PCF kernel of the given size
+fn render_filtered(mode: ShadowMode, scale: f32, pcf_kernel_size: usize) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("shadow_quads.obj");
+    std::fs::write(&path, QUADS).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::with_projection(
+        vec3(0.0, 0.0, -scale),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        Projection::Orthographic { scale: 1.0 / scale },
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_shadows(128);
+    renderer.set_shadow_mode(mode);
+    renderer.set_pcf_kernel_size(pcf_kernel_size);
+    renderer.set_light_direction(vec3(0.6, 0.0, 1.0));
+    let mut scene = Scene::single(model, None);
+    scene.instances[0].transform = Mat4::from_scale(Vec3::splat(scale));
+    renderer.render_scene(&scene);
+    renderer.image().clone()
+}
+
+// pixels between fully lit and fully shadowed, both squares face the light the same way
+fn penumbra(image: &Image) -> usize {
+    let values: Vec<u8> = image.pixels().map(|p| p[0]).collect();
+    let (shadowed, lit) = (*values.iter().min().unwrap(), *values.iter().max().unwrap());
+    assert!(lit > shadowed + 32, "{} {}", shadowed, lit);
+    values
+        .iter()
+        .filter(|&&v| v > shadowed + 2 && v + 2 < lit)
+        .count()
+}
+
+#[test]
+fn variance_shadows_are_soft() {
+    let hard = render(ShadowMode::Pcf);
+    let soft = render(ShadowMode::Variance { blur: 0 });
+    let softer = render(ShadowMode::Variance { blur: 4 });
+
+    // the shadow is in the same place
+    let dark = |image: &Image, x: u32| image.get_pixel(x, SIZE / 2)[0] < 150;
+    for x in (0..SIZE).step_by(8) {
+        assert_eq!(dark(&soft, x), dark(&hard, x), "{}", x);
+    }
+
+    let (hard, soft, softer) = (penumbra(&hard), penumbra(&soft), penumbra(&softer));
+    assert!(soft > hard, "{} {}", hard, soft);
+    assert!(softer > soft * 2, "{} {}", soft, softer);
+}
+
+#[test]
+fn shadows_dont_depend_on_model_scale() {
+    for mode in [ShadowMode::Pcf, ShadowMode::Variance { blur: 2 }] {
+        let unit = render_scaled(mode, 1.0);
+        assert!(penumbra(&unit) < (SIZE * SIZE) as usize / 2);
+        // no retuning of bias or extents needed
+        for scale in [0.001, 1000.0] {
+            let scaled = render_scaled(mode, scale);
+            let differing = unit
+                .pixels()
+                .zip(scaled.pixels())
+                .filter(|(a, b)| (0..3).any(|c| (a[c] as i32 - b[c] as i32).abs() > 2))
+                .count();
+            assert!(differing < 16, "{:?} at {}: {}", mode, scale, differing);
+        }
+    }
+}
+
+#[test]
+fn pcf_shadow_edges_are_gradients() {
+    // across the left edge of the shadow, from lit to shadowed
+    let edge = |pcf_kernel_size| {
+        let image = render_filtered(ShadowMode::Pcf, 1.0, pcf_kernel_size);
+        let row: Vec<u8> = (0..SIZE / 2)
+            .map(|x| image.get_pixel(x, SIZE / 2)[0])
+            .collect();
+        let (shadowed, lit) = (*row.iter().min().unwrap(), *row.iter().max().unwrap());
+        assert!(lit > shadowed + 32, "{} {}", shadowed, lit);
+        let start = row.iter().position(|&v| v < lit).unwrap();
+        let end = row.iter().position(|&v| v == shadowed).unwrap();
+        // darkening all the way
+        assert!(
+            row[start..=end].windows(2).all(|w| w[1] <= w[0]),
+            "{:?}",
+            row
+        );
+        end - start
+    };
+
+    let hard = edge(1);
+    assert!(hard <= 1, "{}", hard);
+    // a texel of the shadow map is a pixel, so the gradient is about as wide as the kernel
+    let soft = edge(5);
+    let softer = edge(9);
+    assert!(soft >= 3, "{}", soft);
+    assert!(softer >= 7 && softer > soft, "{} {}", soft, softer);
+}
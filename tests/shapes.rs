@@ -0,0 +1,94 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{Camera, Mesh, Renderer, Scene};
+use wavefront_obj::obj::{Primitive, Vertex};
+
+fn to_vec3(v: Vertex) -> Vec3 {
+    vec3(v.x as f32, v.y as f32, v.z as f32)
+}
+
+// position, normal and UV
+type Corner = (Vec3, Vec3, (f64, f64));
+
+fn triangles(mesh: &Mesh) -> Vec<[Corner; 3]> {
+    let object = &mesh.model.objects[0];
+    let corner = |(v, t, n): (usize, Option<usize>, Option<usize>)| {
+        let t = object.tex_vertices[t.unwrap()];
+        (
+            to_vec3(object.vertices[v]),
+            to_vec3(object.normals[n.unwrap()]),
+            (t.u, t.v),
+        )
+    };
+    object.geometry[0]
+        .shapes
+        .iter()
+        .map(|shape| match shape.primitive {
+            Primitive::Triangle(a, b, c) => [corner(a), corner(b), corner(c)],
+            _ => panic!("{:?} isn't a triangle", shape.primitive),
+        })
+        .collect()
+}
+
+// winding agrees with the normals
+fn assert_faces_out(triangles: &[[Corner; 3]]) {
+    for [(a, na, _), (b, nb, _), (c, nc, _)] in triangles {
+        let face = (*b - *a).cross(*c - *a);
+        assert!(face.dot(*na + *nb + *nc) > 0.0, "{:?}", (a, b, c));
+    }
+}
+
+#[test]
+fn sphere_normals_are_radial() {
+    for subdivisions in 0..3 {
+        let sphere = triangles(&Mesh::sphere(subdivisions));
+        assert_eq!(sphere.len(), 20 * 4usize.pow(subdivisions));
+        assert_faces_out(&sphere);
+        for corner in sphere.iter().flatten() {
+            let (p, n, (u, v)) = *corner;
+            assert!((p.length() - 1.0).abs() < 1e-6, "{:?}", p);
+            assert!((n - p).length() < 1e-6, "{:?} {:?}", n, p);
+            assert!((0.0..=1.0).contains(&v) && (0.0..=1.25).contains(&u));
+        }
+    }
+}
+
+#[test]
+fn cube_and_plane() {
+    let cube = triangles(&Mesh::cube());
+    assert_eq!(cube.len(), 12);
+    assert_faces_out(&cube);
+    for corner in cube.iter().flatten() {
+        let (p, n, _) = *corner;
+        assert_eq!(p.abs().max_element(), 1.0);
+        assert_eq!(p.dot(n), 1.0);
+    }
+
+    let plane = triangles(&Mesh::plane(4.0));
+    assert_eq!(plane.len(), 2);
+    assert_faces_out(&plane);
+    for corner in plane.iter().flatten() {
+        let (p, n, _) = *corner;
+        assert_eq!(n, Vec3::unit_y());
+        assert_eq!((p.x().abs(), p.y(), p.z().abs()), (2.0, 0.0, 2.0));
+    }
+}
+
+#[test]
+fn sphere_renders_as_disc() {
+    let sphere = Mesh::sphere(3);
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (64, 64));
+    renderer.set_light_direction(vec3(0.0, 0.0, 1.0));
+    renderer.render_scene(&Scene::single(sphere.model, sphere.texture));
+
+    let image = renderer.image();
+    assert_eq!(image.get_pixel(1, 1).0, [0, 0, 0]);
+    let center = image.get_pixel(32, 32)[0];
+    // brightest where it faces the light
+    let rim = image.get_pixel(32, 2)[0];
+    assert!(center > rim && rim > 0, "{} {}", center, rim);
+}
@@ -0,0 +1,90 @@
+use tinyrenderer::{contact_sheet, render_contact_sheet, Image};
+
+const CELL: u32 = 64;
+
+// cube from -1 to 1 scaled and moved by the given amounts, wound counter-clockwise outside
+fn cube(scale: f32, offset: f32) -> String {
+    let mut obj = String::new();
+    for i in 0..8 {
+        let s = |bit: usize| if i & bit != 0 { 1.0 } else { -1.0 };
+        let c = |bit: usize| s(bit) * scale + offset;
+        obj += &format!("v {} {} {}\n", c(1), c(2), c(4));
+    }
+    let faces = [
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+    ];
+    for face in &faces {
+        obj += &format!("f {} {} {}\n", face[0] + 1, face[1] + 1, face[2] + 1);
+        obj += &format!("f {} {} {}\n", face[0] + 1, face[2] + 1, face[3] + 1);
+    }
+    obj
+}
+
+// covered pixels of cell (col, row) above its label, and white ones in its label
+fn cell_pixels(sheet: &Image, col: u32, row: u32) -> (Vec<(u32, u32)>, usize) {
+    let (mut covered, mut label) = (Vec::new(), 0);
+    for y in 0..CELL {
+        for x in 0..CELL {
+            let p = sheet.get_pixel(col * CELL + x, row * CELL + y);
+            if y >= CELL - 7 {
+                label += (p.0 == [255, 255, 255]) as usize;
+            } else if p.0 != [0, 0, 0] {
+                covered.push((x, y));
+            }
+        }
+    }
+    (covered, label)
+}
+
+#[test]
+fn contact_sheet_has_a_cell_per_model() {
+    let directory = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let huge = directory.join("huge_cube.obj");
+    std::fs::write(&huge, cube(500.0, 1000.0)).unwrap();
+    let tiny = directory.join("tiny_cube.obj");
+    std::fs::write(&tiny, cube(0.001, -0.5)).unwrap();
+    let models = [
+        "obj/african_head.obj",
+        huge.to_str().unwrap(),
+        tiny.to_str().unwrap(),
+    ];
+
+    let sheet = contact_sheet(&models, CELL as usize, 2).unwrap();
+    assert_eq!(sheet.dimensions(), (2 * CELL, 2 * CELL));
+    for (i, (col, row)) in [(0, 0), (1, 0), (0, 1)].iter().enumerate() {
+        let (covered, label) = cell_pixels(&sheet, *col, *row);
+        assert!(label > 0, "{}", models[i]);
+        // fit into the middle of the cell whatever the model's size and place
+        assert!(covered.len() > (CELL * CELL / 8) as usize, "{}", models[i]);
+        let mean = |axis: fn(&(u32, u32)) -> u32| {
+            covered.iter().map(axis).sum::<u32>() as f32 / covered.len() as f32
+        };
+        let (x, y) = (mean(|p| p.0), mean(|p| p.1));
+        let middle = CELL as f32 / 2.0;
+        assert!(
+            (x - middle).abs() < 8.0 && (y - middle).abs() < 8.0,
+            "{} {}",
+            x,
+            y
+        );
+        assert!(covered.iter().all(|&(x, y)| x > 0 && y > 0 && x < CELL - 1));
+    }
+    // the same cubes look the same
+    let cell = |col: u32, row: u32| {
+        image::imageops::crop_imm(&sheet, col * CELL, row * CELL, CELL, CELL - 8).to_image()
+    };
+    assert!(cell(1, 0) == cell(0, 1));
+    assert!(cell(0, 0) != cell(1, 0));
+    // and the cell after the last model is empty
+    assert!(cell_pixels(&sheet, 1, 1) == (Vec::new(), 0));
+
+    let out = directory.join("sheet.png");
+    render_contact_sheet(&models, CELL as usize, 3, out.to_str().unwrap()).unwrap();
+    assert_eq!(image::image_dimensions(&out).unwrap(), (3 * CELL, CELL));
+    assert!(contact_sheet(&["missing.obj"], CELL as usize, 1).is_err());
+}
@@ -0,0 +1,104 @@
+use glam::{vec3, Mat4, Vec3};
+use tinyrenderer::{read_model, skin, BoneWeights, LoadOptions};
+use wavefront_obj::obj::Object;
+
+// thin vertical strip from y = 0 to 2 with a joint at y = 1, normals of every vertex point
+// along x
+const STRIP: &str = "
+v -0.1 0 0
+v 0.1 0 0
+v -0.1 1 0
+v 0.1 1 0
+v -0.1 2 0
+v 0.1 2 0
+vn 1 0 0
+vn 1 0 0
+vn 1 0 0
+vn 1 0 0
+vn 1 0 0
+vn 1 0 0
+f 1//1 2//2 4//4
+f 1//1 4//4 3//3
+f 3//3 4//4 6//6
+f 3//3 6//6 5//5
+";
+
+fn strip() -> Object {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("skin_strip.obj");
+    std::fs::write(&path, STRIP).unwrap();
+    let mut model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    model.objects.remove(0)
+}
+
+// the lower half follows the first bone, the upper one the second, the joint both
+fn weights() -> Vec<BoneWeights> {
+    let joint = BoneWeights {
+        bones: [0, 1, 0, 0],
+        weights: [0.5, 0.5, 0.0, 0.0],
+    };
+    let (lower, upper) = (BoneWeights::single(0), BoneWeights::single(1));
+    vec![lower, lower, joint, joint, upper, upper]
+}
+
+fn position(object: &Object, i: usize) -> Vec3 {
+    let v = object.vertices[i];
+    vec3(v.x as f32, v.y as f32, v.z as f32)
+}
+
+#[test]
+fn strip_bends_at_the_joint() {
+    // the second bone rotates by 90 degrees counterclockwise around the joint
+    let joint = Mat4::from_translation(vec3(0.0, 1.0, 0.0));
+    let bend = joint * Mat4::from_rotation_z(std::f32::consts::FRAC_PI_2) * joint.inverse();
+    let object = strip();
+    let skinned = skin(&object, &weights(), &[Mat4::identity(), bend]).unwrap();
+
+    let expected = [
+        vec3(-0.1, 0.0, 0.0),
+        vec3(0.1, 0.0, 0.0),
+        // halfway between staying and being rotated
+        vec3(-0.05, 0.95, 0.0),
+        vec3(0.05, 1.05, 0.0),
+        // the top now points to the left
+        vec3(-1.0, 0.9, 0.0),
+        vec3(-1.0, 1.1, 0.0),
+    ];
+    for (i, &expected) in expected.iter().enumerate() {
+        let p = position(&skinned, i);
+        assert!((p - expected).length() < 1e-5, "{}: {:?}", i, p);
+    }
+    // normals turn with their vertices
+    let normal = |i: usize| {
+        let n = skinned.normals[i];
+        vec3(n.x as f32, n.y as f32, n.z as f32)
+    };
+    let diagonal = vec3(1.0, 1.0, 0.0).normalize();
+    for (i, expected) in [(0, Vec3::unit_x()), (2, diagonal), (4, Vec3::unit_y())] {
+        assert!(
+            (normal(i) - expected).length() < 1e-5,
+            "{}: {:?}",
+            i,
+            normal(i)
+        );
+    }
+
+    // without rotation nothing moves
+    let still = skin(&object, &weights(), &[Mat4::identity(); 2]).unwrap();
+    assert_eq!(still.vertices, object.vertices);
+}
+
+#[test]
+fn mismatched_weights_are_errors() {
+    let object = strip();
+    let bones = [Mat4::identity(); 2];
+    assert!(skin(&object, &weights()[1..], &bones).is_err());
+    let mut weights = weights();
+    weights[0] = BoneWeights::single(2);
+    assert!(skin(&object, &weights, &bones).is_err());
+    // unweighted slots don't count
+    weights[0] = BoneWeights {
+        bones: [0, 7, 0, 0],
+        weights: [1.0, 0.0, 0.0, 0.0],
+    };
+    assert!(skin(&object, &weights, &bones).is_ok());
+}
@@ -0,0 +1,96 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_model, read_vertex_colors, Camera, LoadOptions, Renderer, Scene, ShadingMode,
+};
+
+const SIZE: u32 = 65;
+
+// two quads folded along a ridge at x = 0 towards the camera (white vertex colors), without
+// normals, in smoothing groups left and right
+fn tent(left: &str, right: &str) -> String {
+    let vertices = [
+        (-1.0, -1.0, 0.0),
+        (0.0, -1.0, 0.5),
+        (0.0, 1.0, 0.5),
+        (-1.0, 1.0, 0.0),
+        (1.0, -1.0, 0.0),
+        (1.0, 1.0, 0.0),
+    ];
+    let mut obj = String::new();
+    for (x, y, z) in vertices {
+        obj += &format!("v {} {} {} 1 1 1\n", x, y, z);
+    }
+    obj += &format!("s {}\nf 1 2 3\nf 1 3 4\n", left);
+    obj += &format!("s {}\nf 2 5 6\nf 2 6 3\n", right);
+    obj
+}
+
+// brightness (sum of channels) of the middle row
+fn render(name: &str, obj: &str, shading: ShadingMode) -> Vec<u32> {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, obj).unwrap();
+    let path = path.to_str().unwrap();
+    let model = read_model(path, &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_shading_mode(shading);
+    renderer.set_vertex_colors(read_vertex_colors(path).unwrap());
+    renderer.set_light_direction(vec3(1.0, 0.0, 1.0));
+    renderer.render_scene(&Scene::single(model, None));
+    let image = renderer.image();
+    (0..SIZE)
+        .map(|x| {
+            image
+                .get_pixel(x, SIZE / 2)
+                .0
+                .iter()
+                .map(|&c| c as u32)
+                .sum()
+        })
+        .collect()
+}
+
+#[test]
+fn smoothing_groups_keep_hard_edges() {
+    let shadings = [
+        ShadingMode::Lit,
+        ShadingMode::Normals,
+        ShadingMode::VertexColors,
+    ];
+    for shading in shadings {
+        // both sides are flat and differ across the ridge (at pixel 32)
+        let hard = render("tent_hard.obj", &tent("1", "2"), shading);
+        let flat = |pixels: &[u32]| pixels.iter().all(|&p| p.abs_diff(pixels[0]) <= 1);
+        assert!(
+            flat(&hard[2..=30]) && flat(&hard[34..=62]),
+            "{:?}: {:?}",
+            shading,
+            hard
+        );
+        let edge = hard[30].abs_diff(hard[34]);
+        assert!(edge > 50, "{:?}: {:?}", shading, hard);
+
+        // or shading changes gradually over both sides, within the smoothing group
+        let smooth = render("tent_smooth.obj", &tent("1", "1"), shading);
+        let (left, right) = (
+            smooth[2].abs_diff(smooth[30]),
+            smooth[34].abs_diff(smooth[62]),
+        );
+        assert!(left > 10 && right > 10, "{:?}: {:?}", shading, smooth);
+        assert!(
+            smooth[30].abs_diff(smooth[34]) * 4 < edge,
+            "{:?}: {:?}",
+            shading,
+            smooth
+        );
+
+        // the same without smoothing
+        assert_eq!(render("tent_off.obj", &tent("1", "off"), shading), hard);
+        assert_eq!(render("tent_none.obj", &tent("off", "off"), shading), hard);
+    }
+}
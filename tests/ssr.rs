@@ -0,0 +1,96 @@
+use glam::{vec3, Mat4};
+use tinyrenderer::{
+    read_model, read_texture, Camera, Image, Instance, LoadOptions, Material, Materials, Mesh,
+    Projection, Renderer, Scene,
+};
+
+const SIZE: u32 = 128;
+
+// the head standing on a glossy floor (or the floor alone), seen from slightly above
+fn render(ssr: bool, with_head: bool) -> Renderer {
+    let head = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let mut floor = Mesh::plane(4.0);
+    floor.model.objects[0].geometry[0].material_name = Some("mirror".to_owned());
+    let scene = Scene {
+        meshes: vec![
+            Mesh {
+                model: head,
+                texture: Some(texture),
+            },
+            floor,
+        ],
+        instances: vec![
+            Instance {
+                mesh: 1,
+                transform: Mat4::from_translation(vec3(0.0, -1.0, 0.0)),
+            },
+            Instance {
+                mesh: 0,
+                transform: Mat4::identity(),
+            },
+        ],
+    };
+    let scene = Scene {
+        instances: scene.instances[..if with_head { 2 } else { 1 }].to_vec(),
+        ..scene
+    };
+
+    let camera = Camera::with_projection(
+        vec3(0.3, 0.15, -1.0),
+        vec3(0.0, -1.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        Projection::Orthographic { scale: 0.4 },
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    let mut materials = Materials::new();
+    materials.insert(
+        "mirror".to_owned(),
+        Material {
+            color: vec3(0.3, 0.3, 0.3),
+            specular: vec3(0.6, 0.6, 0.6),
+            shininess: 20.0,
+            ..Material::default()
+        },
+    );
+    renderer.set_materials(materials);
+    renderer.set_triangle_id_buffer(true);
+    renderer.set_ssr(ssr, 64);
+    renderer.render_scene(&scene);
+    renderer
+}
+
+fn brightness(image: &Image, x: u32, y: u32) -> u32 {
+    image.get_pixel(x, y).0.iter().map(|&c| c as u32).sum()
+}
+
+#[test]
+fn floor_reflects_the_head() {
+    let (plain, reflected) = (render(false, true), render(true, true));
+    let (before, after) = (plain.image(), reflected.image());
+    let object = |x, y| plain.pick(x, y).map(|pick| pick.object);
+
+    let mut brighter = 0;
+    for x in 0..SIZE {
+        let (mut lowest_head, mut highest_reflection) = (SIZE, 0);
+        for y in 0..SIZE {
+            assert!(brightness(after, x, y) >= brightness(before, x, y));
+            if object(x, y).as_deref() == Some("head") {
+                lowest_head = lowest_head.min(y);
+            }
+            if brightness(after, x, y) > brightness(before, x, y) {
+                // only the floor reflects
+                assert_eq!(object(x, y).as_deref(), Some("plane"), "({}, {})", x, y);
+                highest_reflection = highest_reflection.max(y);
+                brighter += 1;
+            }
+        }
+        // upside down below the head (y grows up before flipv)
+        assert!(highest_reflection < lowest_head, "column {}", x);
+    }
+    assert!(brighter > 200, "{} pixels reflect", brighter);
+
+    // with nothing to reflect the floor stays as it is
+    let (plain, reflected) = (render(false, false), render(true, false));
+    assert!(plain.image() == reflected.image());
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "stl")]
+
+use glam::{vec3, Vec3};
+use tinyrenderer::{read_stl, Camera, LoadOptions, Renderer, Scene};
+use wavefront_obj::obj::Primitive;
+
+// binary STL of the cube from -1 to 1, wound counter-clockwise seen from outside
+fn cube_stl() -> Vec<u8> {
+    let corner = |i: usize| {
+        let s = |bit: usize| if i & bit != 0 { 1.0 } else { -1.0 };
+        vec3(s(1), s(2), s(4))
+    };
+    let faces = [
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+    ];
+
+    let mut stl = vec![0; 80];
+    stl.extend_from_slice(&12u32.to_le_bytes());
+    for face in &faces {
+        for triangle in &[[face[0], face[1], face[2]], [face[0], face[2], face[3]]] {
+            // facet normals are ignored
+            push(&mut stl, Vec3::zero());
+            for &i in triangle {
+                push(&mut stl, corner(i));
+            }
+            // attributes
+            stl.extend_from_slice(&[0, 0]);
+        }
+    }
+    stl
+}
+
+fn push(stl: &mut Vec<u8>, v: Vec3) {
+    for c in &[v.x(), v.y(), v.z()] {
+        stl.extend_from_slice(&c.to_le_bytes());
+    }
+}
+
+#[test]
+fn stl_cube_is_welded_and_rendered() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("cube.stl");
+    std::fs::write(&path, cube_stl()).unwrap();
+    let model = read_stl(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let object = &model.objects[0];
+    assert_eq!(object.name, "cube");
+    assert_eq!(object.vertices.len(), 8);
+    assert_eq!(object.geometry[0].shapes.len(), 12);
+    for shape in &object.geometry[0].shapes {
+        match shape.primitive {
+            Primitive::Triangle((_, _, Some(_)), (_, _, Some(_)), (_, _, Some(_))) => {}
+            _ => panic!("{:?} has no normals", shape.primitive),
+        }
+    }
+
+    // smooth shaded from the welded normals, seen from a corner
+    let camera = Camera::new(
+        vec3(-1.0, -1.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (64, 64));
+    renderer.render_scene(&Scene::single(model, None));
+    let image = renderer.image();
+    let covered: Vec<_> = image.pixels().filter(|p| p.0 != [0, 0, 0]).collect();
+    assert!(covered.len() > 64 * 64 / 4);
+    assert!(covered.iter().any(|p| p[0] != covered[0][0]));
+
+    // anything but binary STL is rejected
+    std::fs::write(&path, "solid cube\nendsolid cube\n").unwrap();
+    assert!(read_stl(path.to_str().unwrap(), &LoadOptions::default()).is_err());
+}
@@ -0,0 +1,82 @@
+use glam::Vec3;
+use tinyrenderer::{read_model, LoadOptions};
+use wavefront_obj::obj::{ObjSet, Primitive};
+
+const CUBE: &str = "
+o cube
+v -0.5 -0.5 -0.5
+v 0.5 -0.5 -0.5
+v 0.5 0.5 -0.5
+v -0.5 0.5 -0.5
+v -0.5 -0.5 0.5
+v 0.5 -0.5 0.5
+v 0.5 0.5 0.5
+v -0.5 0.5 0.5
+f 1 3 2
+f 1 4 3
+f 5 6 7
+f 5 7 8
+f 1 2 6
+f 1 6 5
+f 4 7 3
+f 4 8 7
+f 1 5 8
+f 1 8 4
+f 2 3 7
+f 2 7 6
+";
+
+fn load(loop_subdivisions: u32) -> ObjSet {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("subdivided_cube.obj");
+    std::fs::write(&path, CUBE).unwrap();
+    let options = LoadOptions {
+        loop_subdivisions,
+        ..LoadOptions::default()
+    };
+    read_model(path.to_str().unwrap(), &options).unwrap()
+}
+
+// number of triangles and distances of their vertices and centers from the center of the cube
+fn triangles_and_distances(model: &ObjSet) -> (usize, Vec<f32>) {
+    let object = &model.objects[0];
+    let shapes = &object.geometry[0].shapes;
+    let vertex = |v: usize| {
+        let v = object.vertices[v];
+        Vec3::new(v.x as f32, v.y as f32, v.z as f32)
+    };
+    let distances = shapes
+        .iter()
+        .flat_map(|shape| match shape.primitive {
+            Primitive::Triangle(a, b, c) => {
+                let [a, b, c] = [vertex(a.0), vertex(b.0), vertex(c.0)];
+                vec![a, b, c, (a + b + c) / 3.0]
+            }
+            _ => unreachable!(),
+        })
+        .map(Vec3::length)
+        .collect();
+    (shapes.len(), distances)
+}
+
+#[test]
+fn subdivided_cube_is_rounder() {
+    let mut previous: Option<(usize, f32)> = None;
+    for levels in 0..4 {
+        let (triangles, distances) = triangles_and_distances(&load(levels));
+        assert_eq!(triangles, 12 * 4usize.pow(levels));
+
+        // corners are pulled in more than faces, towards a sphere (at first corners are 1.6 times
+        // further than centers of the triangles)
+        let near = distances.iter().cloned().fold(f32::INFINITY, f32::min);
+        let far = distances.iter().cloned().fold(0.0, f32::max);
+        let spread = far / near;
+        if let Some((previous_triangles, previous_spread)) = previous {
+            assert!(triangles > previous_triangles);
+            assert!(spread < previous_spread, "{} {}", previous_spread, spread);
+        }
+        previous = Some((triangles, spread));
+    }
+    // the limit surface is rounded, but not a sphere
+    let (_, spread) = previous.unwrap();
+    assert!(spread < 1.25, "{}", spread);
+}
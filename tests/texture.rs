@@ -0,0 +1,225 @@
+use glam::vec2;
+use glam::vec3;
+use tinyrenderer::{
+    read_model, Camera, Color, Image, LoadOptions, Material, Materials, Renderer, Scene, Texture,
+    TextureExt, TextureWrap, UvInterpretation,
+};
+
+#[test]
+fn dilation_removes_dark_fringe() {
+    // a single texel island in the middle of an empty texture
+    let red = Color::from([200, 10, 10]);
+    let mut texture = Texture::from_pixel(7, 7, Color::from([0, 0, 0]));
+    texture.put_pixel(3, 3, red);
+
+    // bilinear filtering halfway between the island and its neighbour
+    let filtered = |texture: &Texture, x: u32| {
+        let (a, b) = (texture.get_pixel(3, 3), texture.get_pixel(x, 3));
+        (a[0] as u32 + b[0] as u32) / 2
+    };
+    assert_eq!(filtered(&texture, 4), 100);
+
+    texture.dilate(1);
+    assert_eq!(filtered(&texture, 4), 200);
+    for (x, y, &texel) in texture.enumerate_pixels() {
+        let near = (x as i32 - 3).abs() <= 1 && (y as i32 - 3).abs() <= 1;
+        assert_eq!(texel == red, near, "({}, {})", x, y);
+    }
+
+    // until everything is filled
+    texture.dilate(10);
+    assert!(texture.pixels().all(|&texel| texel == red));
+}
+
+#[test]
+fn dilation_averages_neighbours() {
+    let mut texture = Texture::from_pixel(3, 1, Color::from([0, 0, 0]));
+    texture.put_pixel(0, 0, Color::from([100, 0, 0]));
+    texture.put_pixel(2, 0, Color::from([0, 0, 200]));
+    texture.dilate(1);
+    assert_eq!(texture.get_pixel(1, 0).0, [50, 0, 100]);
+
+    // nothing to grow from
+    let mut empty = Texture::from_pixel(2, 2, Color::from([0, 0, 0]));
+    empty.dilate(3);
+    assert!(empty.pixels().all(|texel| texel.0 == [0, 0, 0]));
+}
+
+#[test]
+fn mip_levels_are_box_filtered() {
+    let texture = Texture::from_fn(4, 3, |x, y| {
+        Color::from([(x * 40) as u8, (y * 100) as u8, 7])
+    });
+    let chain = texture.mip_chain();
+    let sizes: Vec<_> = chain.iter().map(Texture::dimensions).collect();
+    assert_eq!(sizes, [(4, 3), (2, 1), (1, 1)]);
+    assert_eq!(chain[0], texture);
+    // 2x3 blocks, rounded
+    assert_eq!(chain[1].get_pixel(0, 0).0, [20, 100, 7]);
+    assert_eq!(chain[1].get_pixel(1, 0).0, [100, 100, 7]);
+    assert_eq!(chain[2].get_pixel(0, 0).0, [60, 100, 7]);
+
+    // a texel is a level of its own
+    assert_eq!(
+        Texture::from_pixel(1, 1, Color::from([1, 2, 3]))
+            .mip_chain()
+            .len(),
+        1
+    );
+}
+
+// square covering the whole image with UVs from -0.5 to 1.5
+const QUAD: &str = "
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+vt -0.5 -0.5
+vt 1.5 -0.5
+vt 1.5 1.5
+vt -0.5 1.5
+f 1/1 2/2 3/3
+f 1/1 3/3 4/4
+";
+
+fn render(wrap: TextureWrap) -> Image {
+    render_with(QUAD, "wrapped_quad.obj", wrap, Materials::new())
+}
+
+fn render_with(obj: &str, name: &str, wrap: TextureWrap, materials: Materials) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, obj).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    // left half blue, right half green
+    let mut texture = Texture::from_pixel(4, 4, Color::from([0, 0, 255]));
+    for y in 0..4 {
+        texture.put_pixel(2, y, Color::from([0, 255, 0]));
+        texture.put_pixel(3, y, Color::from([0, 255, 0]));
+    }
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (64, 64));
+    renderer.set_texture_wrap(wrap);
+    renderer.set_materials(materials);
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    renderer.image().clone()
+}
+
+#[test]
+fn texture_wrap_modes() {
+    // brightest channel at u (row in the middle)
+    let channel = |image: &Image, u: f32| {
+        let x = ((u + 0.5) / 2.0 * 63.0).round() as u32;
+        let p = image.get_pixel(x, 32);
+        (0..3).max_by_key(|&c| p[c]).unwrap()
+    };
+
+    let clamped = render(TextureWrap::Clamp);
+    assert_eq!(channel(&clamped, -0.4), 2);
+    assert_eq!(channel(&clamped, 0.1), 2);
+    assert_eq!(channel(&clamped, 0.9), 1);
+    assert_eq!(channel(&clamped, 1.5), 1);
+
+    let repeated = render(TextureWrap::Repeat);
+    assert_eq!(channel(&repeated, -0.1), 1);
+    assert_eq!(channel(&repeated, 1.1), 2);
+    assert_eq!(channel(&repeated, 1.45), 2);
+
+    let bordered = render(TextureWrap::Border(Color::from([255, 0, 0])));
+    assert_eq!(channel(&bordered, 1.5), 0);
+    assert_eq!(channel(&bordered, -0.4), 0);
+    assert_eq!(channel(&bordered, 0.1), 2);
+    assert_eq!(channel(&bordered, 0.9), 1);
+    // within the texture all modes agree
+    let x = 32;
+    assert_eq!(bordered.get_pixel(x, 32), clamped.get_pixel(x, 32));
+    assert_eq!(repeated.get_pixel(x, 32), clamped.get_pixel(x, 32));
+}
+
+#[test]
+fn material_uv_scale_tiles_texture() {
+    // the same square with UVs from 0 to 1
+    let quad = QUAD
+        .replace("-0.5", "0")
+        .replace("1.5", "1")
+        .replace("f 1/1", "usemtl tiled\nf 1/1");
+    let mut materials = Materials::new();
+    materials.insert(
+        "tiled".to_owned(),
+        Material {
+            uv_scale: vec2(2.0, 2.0),
+            ..Material::default()
+        },
+    );
+    let image = render_with(&quad, "tiled_quad.obj", TextureWrap::Repeat, materials);
+
+    // blue and green halves twice along the row in the middle
+    let channel = |x: u32| {
+        let p = image.get_pixel(x, 32);
+        (0..3).max_by_key(|&c| p[c]).unwrap()
+    };
+    let stripes: Vec<usize> = [8, 26, 40, 58].iter().map(|&x| channel(x)).collect();
+    assert_eq!(stripes, vec![2, 1, 2, 1]);
+
+    // offset by half a tile, the first stripe is green
+    let mut materials = Materials::new();
+    materials.insert(
+        "tiled".to_owned(),
+        Material {
+            uv_scale: vec2(2.0, 2.0),
+            uv_offset: vec2(0.5, 0.0),
+            ..Material::default()
+        },
+    );
+    let image = render_with(&quad, "tiled_quad.obj", TextureWrap::Repeat, materials);
+    let p = image.get_pixel(8, 32);
+    assert!(p[1] > p[2], "{:?}", p);
+}
+
+// the square with UVs of the corners of a 64x64 texture
+fn render_uvs(max_uv: f32, interpretation: UvInterpretation) -> Image {
+    let quad = QUAD
+        .replace("-0.5", "0")
+        .replace("1.5", &max_uv.to_string());
+    let path =
+        std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("uvs_{}.obj", max_uv));
+    std::fs::write(&path, quad).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let mut texture = Texture::new(64, 64);
+    for (x, y, texel) in texture.enumerate_pixels_mut() {
+        *texel = Color::from([x as u8 * 4, y as u8 * 4, 128]);
+    }
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (64, 64));
+    renderer.set_uv_interpretation(interpretation);
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    renderer.image().clone()
+}
+
+#[test]
+fn pixel_uvs_are_detected() {
+    let normalized = render_uvs(1.0, UvInterpretation::Auto);
+    assert!(normalized.pixels().any(|p| p[0] > 200));
+    assert!(normalized.pixels().any(|p| p[0] < 50));
+    assert!(normalized == render_uvs(1.0, UvInterpretation::Normalized));
+
+    // the same texels from UVs in texture pixels
+    let pixels = render_uvs(64.0, UvInterpretation::Auto);
+    assert!(pixels == normalized);
+    assert!(pixels == render_uvs(64.0, UvInterpretation::Pixels));
+
+    // clamped to the last texel when read as normalized UVs
+    let clamped = render_uvs(64.0, UvInterpretation::Normalized);
+    assert!(clamped != normalized);
+    let p = clamped.get_pixel(48, 48);
+    assert!(p[0] > 200 && p[1] > 200, "{:?}", p);
+}
@@ -0,0 +1,117 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, Image, LoadOptions, Renderer, Scene};
+
+const SIZE: usize = 64;
+
+// two overlapping triangles in the same plane facing the camera
+const VERTICES: &str = "
+v -1 -1 0
+v 1 -1 0
+v 0 1 0
+v -1 1 0
+v 0 -1 0
+v 1 1 0
+";
+const RED: &str = "g red\nf 1 2 3\n";
+const BLUE: &str = "g blue\nf 4 5 6\n";
+
+fn render(faces: [&str; 2], tie_break: bool) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("ties.obj");
+    std::fs::write(&path, format!("{}{}{}", VERTICES, faces[0], faces[1])).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.set_depth_tie_break(tie_break);
+    renderer.set_group_color("red", vec3(1.0, 0.0, 0.0));
+    renderer.set_group_color("blue", vec3(0.0, 0.0, 1.0));
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+#[test]
+fn depth_ties_dont_depend_on_draw_order() {
+    // the last one drawn wins by default
+    assert!(render([RED, BLUE], false) != render([BLUE, RED], false));
+
+    let image = render([RED, BLUE], true);
+    assert!(image == render([BLUE, RED], true));
+    // both triangles are still visible where they don't overlap
+    assert!(image.pixels().any(|p| p[0] > 0 && p[2] == 0));
+    assert!(image.pixels().any(|p| p[2] > 0 && p[0] == 0));
+}
+
+// a triangle pointing down across the middle, one pointing up from the bottom, at a depth
+// interpolated without rounding so they tie wherever they overlap
+const MIDDLE: &str = "
+v -1 0.5 -0.984375
+v 0 -0.5 -0.984375
+v 1 0.5 -0.984375
+g blue
+f 1 2 3
+";
+const LOW: &str = "
+v -1 -1 -0.984375
+v 1 -1 -0.984375
+v 0 0.5 -0.984375
+g red
+f 1 2 3
+";
+
+// MIDDLE, flipped vertically after drawing it if flip, and then LOW if low
+fn render_flipped(flip: bool, low: bool) -> Image {
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.set_depth_tie_break(true);
+    renderer.set_group_color("red", vec3(1.0, 0.0, 0.0));
+    renderer.set_group_color("blue", vec3(0.0, 0.0, 1.0));
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    for (obj, name, draw) in [
+        (MIDDLE, "ties_middle.obj", true),
+        (LOW, "ties_low.obj", low),
+    ] {
+        if draw {
+            let path = dir.join(name);
+            std::fs::write(&path, obj).unwrap();
+            let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+            renderer.render_scene(&Scene::single(model, None));
+        }
+        if flip && obj == MIDDLE {
+            renderer.flipv();
+        }
+    }
+    renderer.image().clone()
+}
+
+#[test]
+fn depth_ties_survive_a_vertical_flip() {
+    // blue wins where the triangles overlap
+    let image = render_flipped(false, true);
+    let middle = render_flipped(false, false);
+    assert!(image.pixels().any(|p| p[0] > 0));
+    for (x, y, p) in image.enumerate_pixels() {
+        if middle.get_pixel(x, y)[2] > 0 {
+            assert!(p[2] > 0 && p[0] == 0, "{:?}", (x, y));
+        }
+    }
+
+    // and still does after flipping it, also where it wasn't before the flip
+    let image = render_flipped(true, true);
+    let flipped = render_flipped(true, false);
+    let mut moved = 0;
+    for (x, y, p) in image.enumerate_pixels() {
+        if flipped.get_pixel(x, y)[2] > 0 {
+            assert!(p[2] > 0 && p[0] == 0, "{:?}", (x, y));
+            moved += (middle.get_pixel(x, y)[2] == 0) as usize;
+        }
+    }
+    assert!(moved > 0);
+}
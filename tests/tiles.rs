@@ -0,0 +1,63 @@
+use glam::vec3;
+use tinyrenderer::{read_model, read_texture, Camera, LoadOptions, Rect, Renderer, Scene};
+
+const SIZE: usize = 96;
+
+fn render(tile_size: Option<usize>, scissor: Option<Rect>) -> Renderer {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let camera = Camera::perspective(
+        vec3(1.0, 0.5, -2.5),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        0.9,
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.set_tile_size(tile_size);
+    renderer.set_scissor(scissor);
+    renderer.set_triangle_id_buffer(true);
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    renderer
+}
+
+fn assert_same(a: &Renderer, b: &Renderer) {
+    assert!(a.image() == b.image());
+    assert_eq!(a.linear_depth(), b.linear_depth());
+    for (x, y) in (0..SIZE as u32).flat_map(|y| (0..SIZE as u32).map(move |x| (x, y))) {
+        assert_eq!(a.pick(x, y), b.pick(x, y), "({}, {})", x, y);
+    }
+    assert_eq!(a.lod_stats(), b.lod_stats());
+}
+
+fn assert_tile_size_independent() {
+    // a single tile
+    let whole = render(Some(SIZE), None);
+    for tile_size in [None, Some(9), Some(32), Some(1000)] {
+        assert_same(&render(tile_size, None), &whole);
+    }
+
+    // tiles stay within the scissor
+    let scissor = Some(Rect {
+        x: 20,
+        y: 10,
+        width: 50,
+        height: 40,
+    });
+    assert_same(&render(Some(16), scissor), &render(Some(SIZE), scissor));
+}
+
+#[test]
+fn image_doesnt_depend_on_tile_size() {
+    assert_tile_size_independent();
+}
+
+// with copies of the renderer drawing tiles in parallel
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_tiles_match_serial_rendering() {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(3)
+        .build()
+        .unwrap();
+    pool.install(assert_tile_size_independent);
+}
@@ -0,0 +1,103 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{
+    read_model, read_texture, Camera, Image, Interpolation, Keyframe, LoadOptions, RenderConfig,
+    Renderer, Scene, Timeline,
+};
+
+const SIZE: usize = 96;
+
+fn timeline(interpolation: Interpolation) -> Timeline {
+    let keyframe = |time, lookfrom, fov| Keyframe {
+        time,
+        lookfrom,
+        lookat: Vec3::zero(),
+        fov,
+    };
+    Timeline {
+        interpolation,
+        frame_rate: 4.0,
+        keyframes: vec![
+            keyframe(1.0, vec3(0.0, 0.0, 3.0), 0.6),
+            keyframe(3.0, vec3(3.0, 0.0, 1.0), 1.0),
+        ],
+    }
+}
+
+fn render(camera: Camera) -> Image {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    renderer.image().clone()
+}
+
+#[test]
+fn poses_are_interpolated_between_keyframes() {
+    let linear = timeline(Interpolation::Linear);
+    assert_eq!(linear.frames(), 9);
+    assert_eq!(linear.frame_time(0), 1.0);
+    assert_eq!(linear.frame_time(8), 3.0);
+
+    let middle = linear.pose(2.0).unwrap();
+    assert_eq!(middle.lookfrom, vec3(1.5, 0.0, 2.0));
+    assert!((middle.fov - 0.8).abs() < 1e-6);
+    let quarter = linear.pose(1.5).unwrap();
+    assert_eq!(quarter.lookfrom, vec3(0.75, 0.0, 2.5));
+
+    // the same at the middle, eased towards the keyframes elsewhere
+    let smooth = timeline(Interpolation::Smoothstep);
+    assert_eq!(smooth.pose(2.0).unwrap().lookfrom, middle.lookfrom);
+    let eased = smooth.pose(1.5).unwrap();
+    assert!(eased.lookfrom.x() < quarter.lookfrom.x());
+    assert!(eased.fov < quarter.fov);
+
+    // held before the first keyframe and after the last one
+    assert_eq!(linear.pose(0.0).unwrap().lookfrom, vec3(0.0, 0.0, 3.0));
+    assert_eq!(linear.pose(5.0).unwrap().lookfrom, vec3(3.0, 0.0, 1.0));
+    assert!(Timeline::default().pose(0.0).is_none());
+    assert_eq!(Timeline::default().frames(), 0);
+}
+
+#[test]
+fn frames_are_rendered_from_the_interpolated_pose() {
+    let up = vec3(0.0, 1.0, 0.0);
+    let base = Camera::new(vec3(0.0, 0.0, 1.0), Vec3::zero(), up);
+    for interpolation in [Interpolation::Linear, Interpolation::Smoothstep] {
+        let timeline = timeline(interpolation);
+        let frame = 3;
+        let pose = timeline.pose(timeline.frame_time(frame)).unwrap();
+        let expected = render(Camera::perspective(
+            pose.lookfrom,
+            pose.lookat,
+            up,
+            pose.fov,
+        ));
+        assert!(expected.pixels().any(|p| p.0 != [0, 0, 0]));
+        assert!(expected == render(timeline.camera(timeline.frame_time(frame), &base)));
+        // and not just any pose
+        assert!(expected != render(timeline.camera(timeline.frame_time(frame + 1), &base)));
+    }
+}
+
+#[test]
+fn timeline_is_read_from_config() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("timeline.toml");
+    let config = RenderConfig {
+        timeline: timeline(Interpolation::Smoothstep),
+        ..RenderConfig::default()
+    };
+    config.save(path.to_str().unwrap()).unwrap();
+    assert_eq!(RenderConfig::load(path.to_str().unwrap()).unwrap(), config);
+
+    std::fs::write(
+        &path,
+        "[timeline]\ninterpolation = \"smoothstep\"\n\n[[timeline.keyframes]]\ntime = 0.0\n\
+         lookfrom = [0.0, 0.0, 3.0]\nlookat = [0.0, 0.0, 0.0]\nfov = 0.8\n",
+    )
+    .unwrap();
+    let timeline = RenderConfig::load(path.to_str().unwrap()).unwrap().timeline;
+    assert_eq!(timeline.interpolation, Interpolation::Smoothstep);
+    assert_eq!(timeline.frame_rate, 24.0);
+    assert_eq!(timeline.keyframes.len(), 1);
+    assert_eq!(timeline.frames(), 1);
+}
@@ -0,0 +1,42 @@
+use glam::vec3;
+use tinyrenderer::{read_model, read_texture, Camera, LoadOptions, PixelFormat, Renderer, Scene};
+
+fn renderer() -> Renderer {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+    let camera = Camera::new(
+        vec3(0.5, 0.0, -0.5),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (64, 64));
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    renderer
+}
+
+#[test]
+fn tint_scales_channels() {
+    let mut renderer = renderer();
+    let plain = renderer.export_as(PixelFormat::Rgb);
+
+    renderer.set_tint(vec3(1.0, 1.0, 1.0));
+    assert_eq!(renderer.export_as(PixelFormat::Rgb), plain);
+
+    // warmer: more red, less blue, green as is
+    renderer.set_tint(vec3(1.2, 1.0, 0.5));
+    let warm = renderer.export_as(PixelFormat::Rgb);
+    for (rgb, tinted) in plain.chunks(3).zip(warm.chunks(3)) {
+        let expected = [
+            (rgb[0] as f32 * 1.2).round().min(255.0) as u8,
+            rgb[1],
+            (rgb[2] as f32 * 0.5).round() as u8,
+        ];
+        assert_eq!(tinted, expected);
+    }
+    assert_ne!(warm, plain);
+
+    // the framebuffer itself isn't touched
+    renderer.clear_tint();
+    assert_eq!(renderer.export_as(PixelFormat::Rgb), plain);
+    assert_eq!(renderer.image().as_raw(), &plain);
+}
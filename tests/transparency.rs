@@ -0,0 +1,67 @@
+use glam::vec3;
+use tinyrenderer::{read_materials, read_model, Camera, Image, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 32;
+
+// a red triangle in front of a blue one, both half transparent
+const NEAR: &str = "
+usemtl red
+f 1 2 3
+";
+const FAR: &str = "
+usemtl blue
+f 4 5 6
+";
+const VERTICES: &str = "
+v -1 -1 0.5
+v 1.5 -1 0.5
+v -1 1.5 0.5
+v 1 1 0
+v -1.5 1 0
+v 1 -1.5 0
+";
+const MTL: &str = "
+newmtl red
+Kd 1 0 0
+d 0.5
+newmtl blue
+Kd 0 0 1
+d 0.5
+";
+
+fn render(faces: [&str; 2]) -> Image {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (obj, mtl) = (dir.join("translucent.obj"), dir.join("translucent.mtl"));
+    std::fs::write(&obj, format!("{}{}{}", VERTICES, faces[0], faces[1])).unwrap();
+    std::fs::write(&mtl, MTL).unwrap();
+    let model = read_model(obj.to_str().unwrap(), &LoadOptions::default()).unwrap();
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+#[test]
+fn translucent_triangles_blend_in_any_order() {
+    let image = render([NEAR, FAR]);
+    assert!(image == render([FAR, NEAR]));
+
+    // the near triangle is blended over the far one where they overlap
+    let [red, green, blue] = image.get_pixel(SIZE / 2, SIZE / 2).0;
+    assert!(
+        red > blue && blue > 0 && green == 0,
+        "{:?}",
+        [red, green, blue]
+    );
+    // and both over the background elsewhere
+    let [red, _, blue] = image.get_pixel(2, 2).0;
+    assert!(red > 0 && blue == 0);
+    let [red, _, blue] = image.get_pixel(SIZE - 3, SIZE - 3).0;
+    assert!(red == 0 && blue > 0);
+}
@@ -0,0 +1,72 @@
+use glam::vec3;
+use std::collections::HashMap;
+use tinyrenderer::{read_model, Camera, LoadOptions, Renderer, Scene, ShadingMode};
+
+const SIZE: u32 = 32;
+
+// a square of two triangles
+const QUAD: &str = "
+v -0.5 -0.5 0
+v 0.5 -0.5 0
+v 0.5 0.5 0
+v -0.5 0.5 0
+f 1 2 3
+f 1 3 4
+";
+
+fn quad() -> Scene {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("triangle_colors.obj");
+    std::fs::write(&path, QUAD).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    Scene::single(model, None)
+}
+
+fn render(shading: ShadingMode) -> Renderer {
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_shading_mode(shading);
+    renderer.set_triangle_id_buffer(true);
+    renderer.render_scene(&quad());
+    renderer
+}
+
+#[test]
+fn triangles_get_stable_distinct_colors() {
+    let renderer = render(ShadingMode::TriangleColors);
+    let mut colors = HashMap::new();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            if let Some(picked) = renderer.pick(x, y) {
+                let pixel = renderer.image().get_pixel(x, y).0;
+                // every triangle is filled with a single color
+                let color = *colors.entry(picked.triangle).or_insert(pixel);
+                assert_eq!(color, pixel, "({}, {})", x, y);
+            }
+        }
+    }
+    assert_eq!(colors.len(), 2);
+    assert_ne!(colors[&0], colors[&1]);
+
+    // the same in every render
+    assert!(render(ShadingMode::TriangleColors).image() == renderer.image());
+    assert!(render(ShadingMode::Lit).image() != renderer.image());
+}
+
+#[test]
+fn toggle_switches_between_triangle_colors_and_lit() {
+    let scene = quad();
+    let mut renderer = render(ShadingMode::Lit);
+    renderer.set_triangle_id_colors(true);
+    renderer.clear_all();
+    renderer.render_scene(&scene);
+    assert!(renderer.image() == render(ShadingMode::TriangleColors).image());
+
+    renderer.set_triangle_id_colors(false);
+    renderer.clear_all();
+    renderer.render_scene(&scene);
+    assert!(renderer.image() == render(ShadingMode::Lit).image());
+}
@@ -0,0 +1,66 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, LoadOptions, Renderer, Scene, Texture};
+
+const SIZE: u32 = 64;
+
+// square facing the camera with w growing from 0 on the left to 1 on the right
+const QUAD: &str = "
+o quad
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+vt 0 0 0
+vt 1 0 1
+vt 1 1 1
+vt 0 1 0
+f 1/1 2/2 3/3
+f 1/1 3/3 4/4
+";
+
+#[test]
+fn w_is_interpolated() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("uvw_quad.obj");
+    std::fs::write(&path, QUAD).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let texture = Texture::from_pixel(4, 4, image::Rgb([255, 255, 255]));
+
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_uvw_buffer(true);
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+
+    let mut checked = 0;
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            if let Some(uvw) = renderer.uvw(x, y) {
+                // w follows u, whichever way the camera maps x
+                assert!(
+                    (uvw.z() - uvw.x()).abs() < 1e-4,
+                    "uvw {:?} at ({}, {})",
+                    uvw,
+                    x,
+                    y
+                );
+                checked += 1;
+            }
+        }
+    }
+    assert!(
+        checked > (SIZE * SIZE / 2) as usize,
+        "only {} fragments",
+        checked
+    );
+
+    let w = |x| renderer.uvw(x, SIZE / 2).unwrap().z();
+    assert!(
+        (w(SIZE / 2) - 0.5).abs() < 0.05,
+        "w {} in the middle",
+        w(SIZE / 2)
+    );
+    assert!((w(1) - w(SIZE - 2)).abs() > 0.9);
+}
@@ -0,0 +1,70 @@
+use glam::vec3;
+use tinyrenderer::{read_model, read_vertex_ao, AoSource, Camera, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 64;
+
+// a valley along y with AO baked into the alpha of vertex colors, occluded at the crease
+const VALLEY: &str = "
+o valley
+v -1 -1 0.5 1 1 1 1
+v 0 -1 0 1 1 1 0.2
+v 1 -1 0.5 1 1 1 1
+v -1 1 0.5 1 1 1 1
+v 0 1 0 1 1 1 0.2
+v 1 1 0.5 1 1 1 1
+f 1 2 5
+f 1 5 4
+f 2 3 6
+f 2 6 5
+";
+
+// brightness of the middle row, from the left to the right
+fn render(ao: bool) -> Vec<u32> {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("valley.obj");
+    std::fs::write(&path, VALLEY).unwrap();
+    let path = path.to_str().unwrap();
+    let model = read_model(path, &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    if ao {
+        renderer.set_vertex_ao(read_vertex_ao(path, AoSource::Alpha).unwrap());
+    }
+    renderer.render_scene(&Scene::single(model, None));
+    let image = renderer.image();
+    let sum = |x| {
+        image
+            .get_pixel(x, SIZE / 2)
+            .0
+            .iter()
+            .map(|&c| c as u32)
+            .sum()
+    };
+    (0..SIZE).map(sum).collect()
+}
+
+#[test]
+fn ao_darkens_the_crease() {
+    let (plain, occluded) = (render(false), render(true));
+    let middle = (SIZE / 2) as usize;
+    for x in [1, middle - 1, middle, SIZE as usize - 2] {
+        assert!(plain[x] > 0 && occluded[x] <= plain[x], "{}", x);
+    }
+    // much darker at the crease, less so away from it and barely at the rim
+    assert!(
+        occluded[middle] * 2 < plain[middle],
+        "{:?} {:?}",
+        occluded,
+        plain
+    );
+    assert!(occluded[middle / 2] > occluded[middle]);
+    assert!(
+        occluded[1] * 10 > plain[1] * 9,
+        "{:?} {:?}",
+        occluded,
+        plain
+    );
+}
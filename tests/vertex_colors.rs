@@ -0,0 +1,68 @@
+use glam::vec3;
+use tinyrenderer::{
+    read_model, read_vertex_colors, Camera, Image, LoadOptions, Renderer, Scene, ShadingMode,
+};
+
+const SIZE: u32 = 64;
+
+// square facing the camera, red on the left and blue on the right
+const QUAD: &str = "
+o quad
+v -1 -1 0 1 0 0
+v 1 -1 0 0 0 1
+v 1 1 0 0 0 1
+v -1 1 0 1 0 0
+f 1 2 3
+f 1 3 4
+";
+
+fn render(shading: ShadingMode) -> Image {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("colored_quad.obj");
+    std::fs::write(&path, QUAD).unwrap();
+    let path = path.to_str().unwrap();
+    let model = read_model(path, &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_light_direction(vec3(0.0, 0.0, 1.0));
+    renderer.set_shading_mode(shading);
+    renderer.set_vertex_colors(read_vertex_colors(path).unwrap());
+    renderer.render_scene(&Scene::single(model, None));
+    renderer.image().clone()
+}
+
+#[test]
+fn vertex_colors_are_read_from_v_lines() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("partly_colored.obj");
+    std::fs::write(&path, "o a\nv 0 0 0 0.5 0.25 2\nv 1 0 0\nf 1 2 1\n").unwrap();
+    let colors = read_vertex_colors(path.to_str().unwrap()).unwrap();
+    // clamped, vertices without a color are white
+    assert_eq!(colors["a"], [vec3(0.5, 0.25, 1.0), vec3(1.0, 1.0, 1.0)]);
+}
+
+#[test]
+fn model_renders_with_its_vertex_colors() {
+    // instead of gray
+    let lit = render(ShadingMode::Lit);
+    assert!(lit.pixels().all(|p| p[0] == p[1] && p[1] == p[2]));
+
+    let colored = render(ShadingMode::VertexColors);
+    let row = SIZE / 2;
+    let (left, right) = (colored.get_pixel(1, row), colored.get_pixel(SIZE - 2, row));
+    assert!(left[0] > 200 && left[2] < 8 && left[1] == 0, "{:?}", left);
+    assert!(
+        right[2] > 200 && right[0] < 8 && right[1] == 0,
+        "{:?}",
+        right
+    );
+    // blended in between
+    let reds: Vec<u8> = (0..SIZE).map(|x| colored.get_pixel(x, row)[0]).collect();
+    let blues: Vec<u8> = (0..SIZE).map(|x| colored.get_pixel(x, row)[2]).collect();
+    assert!(reds.windows(2).all(|w| w[1] <= w[0]), "{:?}", reds);
+    assert!(blues.windows(2).all(|w| w[1] >= w[0]), "{:?}", blues);
+    let middle = colored.get_pixel(SIZE / 2, row);
+    assert!(middle[0] > 64 && middle[2] > 64, "{:?}", middle);
+}
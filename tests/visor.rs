@@ -0,0 +1,96 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{
+    read_materials, read_model, read_texture, Camera, Image, LoadOptions, Renderer, Scene,
+};
+
+const SIZE: u32 = 64;
+
+// translucent quad in front of the eyes, seen from either side
+const VISOR: &str = "
+o visor
+v -0.6 0.0 1.1
+v 0.6 0.0 1.1
+v 0.6 0.45 1.1
+v -0.6 0.45 1.1
+usemtl visor
+";
+// of the visor's vertices, wound both ways
+const FACES: [[usize; 3]; 4] = [[1, 2, 3], [1, 3, 4], [1, 3, 2], [1, 4, 3]];
+const MTL: &str = "
+newmtl visor
+Kd 0 1 1
+d 0.4
+";
+
+fn render(lookfrom: Vec3, head: bool, visor: bool) -> Image {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (obj, mtl) = (dir.join("visor.obj"), dir.join("visor.mtl"));
+    let mut source = String::new();
+    if head {
+        source += &std::fs::read_to_string("obj/african_head.obj").unwrap();
+    }
+    if visor {
+        // indices are global, after those of the head
+        let offset = source.lines().filter(|line| line.starts_with("v ")).count();
+        source += VISOR;
+        for [a, b, c] in FACES {
+            source += &format!("f {} {} {}\n", a + offset, b + offset, c + offset);
+        }
+    }
+    std::fs::write(&obj, source).unwrap();
+    std::fs::write(&mtl, MTL).unwrap();
+    let model = read_model(obj.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let texture = read_texture("obj/african_head_diffuse.png").unwrap();
+
+    let camera = Camera::new(lookfrom, vec3(0.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0));
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_materials(read_materials(mtl.to_str().unwrap()).unwrap());
+    renderer.render_scene(&Scene::single(model, Some(texture)));
+    renderer.image().clone()
+}
+
+#[test]
+fn visor_blends_over_the_face() {
+    // from the front and both sides
+    for lookfrom in [
+        vec3(0.0, 0.0, -1.0),
+        vec3(-0.7, 0.0, -0.7),
+        vec3(0.7, 0.0, -0.7),
+    ] {
+        let face = render(lookfrom, true, false);
+        let visor = render(lookfrom, false, true);
+        let both = render(lookfrom, true, true);
+        let mut blended = 0;
+        for ((face, visor), both) in face.pixels().zip(visor.pixels()).zip(both.pixels()) {
+            if visor.0 == [0, 0, 0] {
+                assert_eq!(face, both);
+                continue;
+            }
+            // the face shows through (with the visor over black being 40% of its color)
+            for c in 0..3 {
+                let expected = face[c] as f32 * 0.6 + visor[c] as f32;
+                assert!(
+                    (both[c] as f32 - expected).abs() <= 2.0,
+                    "{:?}",
+                    (face, visor, both)
+                );
+            }
+            blended += (face.0 != [0, 0, 0]) as usize;
+        }
+        assert!(blended > 20, "{:?}: {}", lookfrom, blended);
+    }
+
+    // and from behind the head hides it
+    let lookfrom = vec3(0.0, 0.0, 1.0);
+    let face = render(lookfrom, true, false);
+    let both = render(lookfrom, true, true);
+    let visor = render(lookfrom, false, true);
+    let mut hidden = 0;
+    for ((face, visor), both) in face.pixels().zip(visor.pixels()).zip(both.pixels()) {
+        if face.0 != [0, 0, 0] {
+            assert_eq!(face, both);
+            hidden += (visor.0 != [0, 0, 0]) as usize;
+        }
+    }
+    assert!(hidden > 20, "{}", hidden);
+}
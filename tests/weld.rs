@@ -0,0 +1,87 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{read_model, weld_vertices, LoadOptions};
+use wavefront_obj::obj::{ObjSet, Primitive};
+
+// ridge of two faces sloping towards -x and +x, the ridge vertices of the second face are
+// duplicates of the first face's ones, slightly off
+const RIDGE: &str = "
+o ridge
+v -1 0 0
+v 0 -1 1
+v 0 1 1
+v 0.00001 -1 1
+v 0 1.00001 1
+v 1 0 0
+s 1
+f 1 2 3
+f 4 6 5
+";
+
+fn load(weld_tolerance: Option<f32>) -> ObjSet {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("ridge.obj");
+    std::fs::write(&path, RIDGE).unwrap();
+    let options = LoadOptions {
+        weld_tolerance,
+        ..LoadOptions::default()
+    };
+    read_model(path.to_str().unwrap(), &options).unwrap()
+}
+
+// normals of both faces at the ridge point next to y
+fn ridge_normals(model: &ObjSet, y: f64) -> Vec<Vec3> {
+    let object = &model.objects[0];
+    object.geometry[0]
+        .shapes
+        .iter()
+        .map(|shape| match shape.primitive {
+            Primitive::Triangle(a, b, c) => {
+                let corner = [a, b, c]
+                    .iter()
+                    .find(|corner| {
+                        let v = object.vertices[corner.0];
+                        v.x.abs() < 0.001 && (v.y - y).abs() < 0.001
+                    })
+                    .unwrap()
+                    .2;
+                let n = object.normals[corner.unwrap()];
+                vec3(n.x as f32, n.y as f32, n.z as f32).normalize()
+            }
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[test]
+fn coincident_vertices_are_welded() {
+    // the first vertex wins
+    let mut model = load(None);
+    assert_eq!(weld_vertices(&mut model.clone(), 1e-6), 0);
+    assert_eq!(weld_vertices(&mut model, 1e-4), 2);
+    let vertices: Vec<Vec<usize>> = model.objects[0].geometry[0]
+        .shapes
+        .iter()
+        .map(|shape| match shape.primitive {
+            Primitive::Triangle(a, b, c) => {
+                let mut vertices = vec![a.0, b.0, c.0];
+                vertices.sort_unstable();
+                vertices
+            }
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(vertices, [vec![0, 1, 2], vec![1, 2, 5]]);
+}
+
+#[test]
+fn welded_normals_are_continuous() {
+    for y in [-1.0, 1.0] {
+        // faceted across the seam
+        let normals = ridge_normals(&load(None), y);
+        assert!(normals[0].dot(normals[1]) < 0.1, "{:?}", normals);
+
+        // smooth, straight up from the ridge
+        let normals = ridge_normals(&load(Some(1e-4)), y);
+        assert!((normals[0] - normals[1]).length() < 1e-6, "{:?}", normals);
+        assert!(normals[0].dot(Vec3::unit_z()) > 0.999, "{:?}", normals);
+    }
+}
@@ -0,0 +1,80 @@
+use glam::vec3;
+use tinyrenderer::{read_model, Camera, FrontFace, LoadOptions, Renderer, Scene};
+use wavefront_obj::obj::{ObjSet, Primitive};
+
+const SIZE: u32 = 64;
+
+fn render(model: &ObjSet, front_face: FrontFace) -> Renderer {
+    // orthographic, camera space is the world one
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_front_face(front_face);
+    renderer.set_triangle_id_buffer(true);
+    renderer.render_scene(&Scene::single(model.clone(), None));
+    renderer
+}
+
+// z of the (counterclockwise) normal of every triangle of the head
+fn facing(model: &ObjSet) -> Vec<f32> {
+    let object = &model.objects[0];
+    let position = |i: usize| {
+        let v = object.vertices[i];
+        vec3(v.x as f32, v.y as f32, v.z as f32)
+    };
+    let shapes = object.geometry.iter().flat_map(|g| &g.shapes);
+    shapes
+        .map(|shape| match shape.primitive {
+            Primitive::Triangle((a, _, _), (b, _, _), (c, _, _)) => {
+                let (a, b, c) = (position(a), position(b), position(c));
+                (b - a).cross(c - a).z()
+            }
+            _ => panic!("the head is made of triangles only"),
+        })
+        .collect()
+}
+
+#[test]
+fn front_face_flips_culling() {
+    let model = read_model("obj/african_head.obj", &LoadOptions::default()).unwrap();
+    let facing = facing(&model);
+
+    // counterclockwise triangles face the camera, clockwise ones the other way
+    for (front_face, sign) in [(FrontFace::Ccw, 1.0), (FrontFace::Cw, -1.0)] {
+        let renderer = render(&model, front_face);
+        let mut visible = 0;
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                if let Some(picked) = renderer.pick(x, y) {
+                    let z = facing[picked.triangle] * sign;
+                    assert!(z > 0.0, "{:?} at ({}, {})", front_face, x, y);
+                    visible += 1;
+                }
+            }
+        }
+        assert!(visible > (SIZE * SIZE / 4) as usize, "{}", visible);
+    }
+
+    // a model exported with the other winding renders the same with the flag flipped
+    let mut reversed = model.clone();
+    for geometry in &mut reversed.objects[0].geometry {
+        for shape in &mut geometry.shapes {
+            if let Primitive::Triangle(a, b, c) = shape.primitive {
+                shape.primitive = Primitive::Triangle(a, c, b);
+            }
+        }
+    }
+    let original = render(&model, FrontFace::Ccw);
+    let flipped = render(&reversed, FrontFace::Cw);
+    let differing = original
+        .image()
+        .pixels()
+        .zip(flipped.image().pixels())
+        .filter(|(a, b)| a != b)
+        .count();
+    assert!(differing < SIZE as usize, "{}", differing);
+    assert!(render(&reversed, FrontFace::Ccw).image() != original.image());
+}
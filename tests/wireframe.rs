@@ -0,0 +1,63 @@
+use glam::{vec3, Vec3};
+use tinyrenderer::{Camera, Color, Image, Mesh, Renderer};
+
+const SIZE: usize = 128;
+
+fn renderer(fade: bool) -> Renderer {
+    let camera = Camera::perspective(
+        vec3(1.2, 1.0, -4.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        0.8,
+    );
+    let mut renderer = Renderer::new(camera, (SIZE, SIZE));
+    if fade {
+        renderer.set_line_depth_fade(3.0, 6.0);
+    }
+    renderer
+}
+
+fn lit(image: &Image) -> Vec<(u32, u32)> {
+    image
+        .enumerate_pixels()
+        .filter(|(_, _, p)| p.0 != [0, 0, 0])
+        .map(|(x, y, _)| (x, y))
+        .collect()
+}
+
+// pixels of a single cube edge
+fn edge(from: Vec3, to: Vec3) -> Vec<(u32, u32)> {
+    let mut renderer = renderer(false);
+    renderer.line(from, to, Color::from([255, 255, 255]));
+    lit(renderer.image())
+}
+
+#[test]
+fn far_edges_fade() {
+    let white = Color::from([255, 255, 255]);
+    let cube = Mesh::cube().model;
+    let mut faded = renderer(true);
+    faded.draw_wireframe(&cube, white);
+    let mut plain = renderer(false);
+    plain.draw_wireframe(&cube, white);
+    assert_eq!(lit(faded.image()), lit(plain.image()));
+    assert!(plain
+        .image()
+        .pixels()
+        .all(|p| p.0 == [0, 0, 0] || *p == white));
+
+    // bottom edges of the front (z = 1, larger z is nearer) and back (z = -1) faces, some
+    // pixels are covered by other edges
+    let median = |pixels: Vec<(u32, u32)>| {
+        let mut values: Vec<u8> = pixels
+            .iter()
+            .map(|&(x, y)| faded.image().get_pixel(x, y)[0])
+            .collect();
+        values.sort_unstable();
+        values[values.len() / 2]
+    };
+    let front = median(edge(vec3(-1.0, -1.0, 1.0), vec3(1.0, -1.0, 1.0)));
+    let back = median(edge(vec3(-1.0, -1.0, -1.0), vec3(1.0, -1.0, -1.0)));
+    assert!(front > back + 50, "front {} back {}", front, back);
+    assert!(back > 0);
+}
@@ -0,0 +1,141 @@
+use glam::{vec2, vec3, Vec2};
+use tinyrenderer::{read_model, Camera, Color, LoadOptions, Renderer, Scene};
+
+const SIZE: u32 = 64;
+const GREEN: [u8; 3] = [0, 0xff, 0];
+
+// square of two triangles split along its diagonal
+const SQUARE: &str = "
+v -0.5 -0.5 0
+v 0.5 -0.5 0
+v 0.5 0.5 0
+v -0.5 0.5 0
+f 1 2 3
+f 1 3 4
+";
+
+// pixel coordinates of normalized device coordinates p
+fn pixel(p: Vec2) -> Vec2 {
+    (p + Vec2::one()) * (SIZE - 1) as f32 / 2.0
+}
+
+// distance from p to segment ab
+fn distance(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let t = ((p - a).dot(b - a) / (b - a).length_squared()).clamp(0.0, 1.0);
+    (p - (a + (b - a) * t)).length()
+}
+
+#[test]
+fn edges_are_drawn_over_the_triangles() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("wireframe_square.obj");
+    std::fs::write(&path, SQUARE).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::new(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    let width = 3.0;
+    renderer.set_wireframe_overlay(Color::from(GREEN), width);
+    renderer.render_scene(&Scene::single(model, None));
+
+    let corners =
+        [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)].map(|(x, y)| pixel(vec2(x, y)));
+    let edges = [(0, 1), (1, 2), (2, 3), (3, 0), (0, 2)];
+    let inside = |p: Vec2| {
+        p.x() > corners[0].x()
+            && p.x() < corners[2].x()
+            && p.y() > corners[0].y()
+            && p.y() < corners[2].y()
+    };
+    let (mut edge, mut fill) = (0, 0);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let p = vec2(x as f32, y as f32);
+            let d = edges
+                .iter()
+                .map(|&(a, b)| distance(p, corners[a], corners[b]))
+                .fold(f32::INFINITY, f32::min);
+            let color = renderer.image().get_pixel(x, y).0;
+            // lines are anti-aliased over a pixel, from width / 2 - 0.5 to width / 2 + 0.5
+            if !inside(p) {
+                // nothing is drawn around the triangles
+                assert!(color == [0, 0, 0] || d < 0.5, "({}, {})", x, y);
+            } else if d < width / 2.0 - 0.55 {
+                assert_eq!(color, GREEN, "({}, {}) at {}", x, y, d);
+                edge += 1;
+            } else if d > width / 2.0 + 0.55 {
+                assert!(
+                    color != GREEN && color != [0, 0, 0],
+                    "({}, {}) at {}",
+                    x,
+                    y,
+                    d
+                );
+                fill += 1;
+            }
+        }
+    }
+    assert!(edge > 4 * 32 && fill > 32 * 16, "{} {}", edge, fill);
+}
+
+// triangle on the floor reaching from behind the viewer to ahead of it, the viewer looks from
+// z = 1 towards -z (depth is 1 - z)
+const FLOOR: &str = "
+v -1 -0.5 3
+v 1 -0.5 -2
+v -1 -0.5 -2
+f 1 2 3
+";
+
+// distance from p to the line through a and b
+fn line_distance(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    (b - a).perp_dot(p - a).abs() / (b - a).length()
+}
+
+#[test]
+fn edges_of_triangles_clipped_by_the_near_plane_are_the_original_ones() {
+    let path = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("wireframe_floor.obj");
+    std::fs::write(&path, FLOOR).unwrap();
+    let model = read_model(path.to_str().unwrap(), &LoadOptions::default()).unwrap();
+    let camera = Camera::perspective(
+        vec3(0.0, 0.0, -1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        std::f32::consts::FRAC_PI_2,
+    );
+    let mut renderer = Renderer::new(camera, (SIZE as usize, SIZE as usize));
+    renderer.set_double_sided(true);
+    let width = 3.0;
+    renderer.set_wireframe_overlay(Color::from(GREEN), width);
+    renderer.render_scene(&Scene::single(model, None));
+
+    // the edges projected from points on them in front of the viewer: x = -1 towards the
+    // vanishing point, the far one at depth 3 and from (0.2, -0.5, 0) at depth 1 to (1, -0.5, -2)
+    let vanishing = pixel(Vec2::zero());
+    let lines = [
+        (vanishing, pixel(vec2(-1.0, -0.5))),
+        (pixel(vec2(-1.0, -0.5) / 3.0), pixel(vec2(1.0, -0.5) / 3.0)),
+        (pixel(vec2(0.2, -0.5)), pixel(vec2(1.0, -0.5) / 3.0)),
+    ];
+    let (mut edge, mut fill) = (0, 0);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let p = vec2(x as f32, y as f32);
+            let d = lines
+                .iter()
+                .map(|&(a, b)| line_distance(p, a, b))
+                .fold(f32::INFINITY, f32::min);
+            let color = renderer.image().get_pixel(x, y).0;
+            if color == GREEN {
+                // no edges between the parts the triangle is clipped into
+                assert!(d < width / 2.0 + 0.55, "({}, {}) at {}", x, y, d);
+                edge += 1;
+            } else if color != [0, 0, 0] && d > width / 2.0 + 0.55 {
+                fill += 1;
+            }
+        }
+    }
+    assert!(edge > 32 && fill > 32 * 8, "{} {}", edge, fill);
+}